@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 use std::fmt;
 use std::iter::FromIterator;
-use std::ops::{Bound, Deref, RangeBounds};
+use std::ops::{Bound, Deref, Range, RangeBounds};
 use std::str::FromStr;
 
 use symbolic_common::{clean_path, join_path, Arch, CodeId, DebugId, Name};
@@ -56,6 +56,17 @@ pub enum ObjectKind {
     /// information corresponding to the original object file.
     Sources,
 
+    /// The KernelImage type is used for the Linux kernel executable, usually
+    /// called vmlinux. At runtime, the kernel image is usually relocated by a
+    /// random offset (KASLR), which has to be subtracted from addresses in
+    /// addition to the load address.
+    KernelImage,
+
+    /// The KernelModule type is used for loadable Linux kernel modules. These
+    /// are relocatable objects with the file name extension .ko, which are
+    /// linked by the kernel when loading them.
+    KernelModule,
+
     /// The Other type represents any valid object class that does not fit any
     /// of the other classes. These are mostly CPU or OS dependent, or unique
     /// to a single kind of object.
@@ -73,6 +84,8 @@ impl ObjectKind {
             ObjectKind::Dump => "dump",
             ObjectKind::Debug => "dbg",
             ObjectKind::Sources => "src",
+            ObjectKind::KernelImage => "kernel",
+            ObjectKind::KernelModule => "kmod",
             ObjectKind::Other => "other",
         }
     }
@@ -94,6 +107,8 @@ impl ObjectKind {
             ObjectKind::Dump => "memory dump",
             ObjectKind::Debug => "debug companion",
             ObjectKind::Sources => "sources",
+            ObjectKind::KernelImage => "kernel image",
+            ObjectKind::KernelModule => "kernel module",
             ObjectKind::Other => "file",
         }
     }
@@ -121,6 +136,8 @@ impl FromStr for ObjectKind {
             "dump" => ObjectKind::Dump,
             "dbg" => ObjectKind::Debug,
             "src" => ObjectKind::Sources,
+            "kernel" => ObjectKind::KernelImage,
+            "kmod" => ObjectKind::KernelModule,
             "other" => ObjectKind::Other,
             _ => return Err(UnknownObjectKindError),
         })
@@ -154,6 +171,8 @@ pub enum FileFormat {
     Pdb,
     /// Portable Executable, an extension of COFF used on Windows.
     Pe,
+    /// Portable PDB, the debug companion format of .NET assemblies.
+    PortablePdb,
     /// Source code bundle ZIP.
     SourceBundle,
     /// WASM container.
@@ -170,6 +189,7 @@ impl FileFormat {
             FileFormat::MachO => "macho",
             FileFormat::Pdb => "pdb",
             FileFormat::Pe => "pe",
+            FileFormat::PortablePdb => "portablepdb",
             FileFormat::SourceBundle => "sourcebundle",
             FileFormat::Wasm => "wasm",
         }
@@ -192,6 +212,7 @@ impl FromStr for FileFormat {
             "macho" => FileFormat::MachO,
             "pdb" => FileFormat::Pdb,
             "pe" => FileFormat::Pe,
+            "portablepdb" => FileFormat::PortablePdb,
             "sourcebundle" => FileFormat::SourceBundle,
             "wasm" => FileFormat::Wasm,
             _ => return Err(UnknownFileFormatError),
@@ -218,6 +239,17 @@ pub struct Symbol<'data> {
     ///
     /// [`SymbolMap`]: struct.SymbolMap.html
     pub size: u64,
+
+    /// Specifies whether multiple symbols share the code at this address.
+    ///
+    /// This is usually the result of identical code folding (ICF), where the linker merges
+    /// functions with identical machine code. The name of this symbol is therefore ambiguous.
+    /// Symbols only carry this flag if the file format records it. Aliases, such as weak symbols or
+    /// constructor variants, are not marked.
+    pub multiple: bool,
+
+    /// The size of parameters passed on the stack, if known.
+    pub parameter_size: Option<u64>,
 }
 
 impl<'data> Symbol<'data> {
@@ -242,6 +274,8 @@ impl<'d> fmt::Debug for Symbol<'d> {
             .field("name", &self.name().unwrap_or("<unknown>"))
             .field("address", &format_args!("{:#x}", self.address))
             .field("size", &format_args!("{:#x}", self.size))
+            .field("multiple", &self.multiple)
+            .field("parameter_size", &self.parameter_size)
             .finish()
     }
 }
@@ -264,15 +298,17 @@ pub type SymbolMapIter<'data> = std::vec::IntoIter<Symbol<'data>>;
 /// ```rust
 /// # use symbolic_debuginfo::{Symbol, SymbolMap};
 /// let map = SymbolMap::from(vec![
-///     Symbol { name: Some("A".into()), address: 0x4400, size: 0 },
-///     Symbol { name: Some("B".into()), address: 0x4200, size: 0 },
-///     Symbol { name: Some("C".into()), address: 0x4000, size: 0 },
+///     Symbol { name: Some("A".into()), address: 0x4400, ..Default::default() },
+///     Symbol { name: Some("B".into()), address: 0x4200, ..Default::default() },
+///     Symbol { name: Some("C".into()), address: 0x4000, ..Default::default() },
 /// ]);
 ///
 /// assert_eq!(map[0], Symbol {
 ///     name: Some("C".into()),
 ///     address: 0x4000,
 ///     size: 0x200,
+///     multiple: false,
+///     parameter_size: None,
 /// });
 /// ```
 ///
@@ -465,12 +501,54 @@ impl fmt::Debug for FileInfo<'_> {
     }
 }
 
+/// A checksum of a source file's contents, along with the algorithm used to compute it.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum FileChecksum {
+    /// An MD5 digest, as emitted by `DW_LNCT_MD5` in DWARF 5 line tables.
+    Md5([u8; 16]),
+    /// A SHA-1 digest.
+    Sha1([u8; 20]),
+    /// A SHA-256 digest.
+    Sha256([u8; 32]),
+}
+
+impl FileChecksum {
+    /// Returns the name of the hash algorithm, such as `"md5"`.
+    pub fn algorithm(&self) -> &'static str {
+        match *self {
+            FileChecksum::Md5(_) => "md5",
+            FileChecksum::Sha1(_) => "sha1",
+            FileChecksum::Sha256(_) => "sha256",
+        }
+    }
+
+    /// Returns the raw bytes of the digest.
+    pub fn as_bytes(&self) -> &[u8] {
+        match *self {
+            FileChecksum::Md5(ref bytes) => bytes,
+            FileChecksum::Sha1(ref bytes) => bytes,
+            FileChecksum::Sha256(ref bytes) => bytes,
+        }
+    }
+}
+
+impl fmt::Display for FileChecksum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.as_bytes() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
 /// File information comprising a compilation directory, relative path and name.
 pub struct FileEntry<'data> {
     /// Path to the compilation directory. File paths are relative to this.
     pub compilation_dir: &'data [u8],
     /// File name and path.
     pub info: FileInfo<'data>,
+    /// Checksum of the file's contents, if recorded in the debug information.
+    pub checksum: Option<FileChecksum>,
 }
 
 impl<'data> FileEntry<'data> {
@@ -493,6 +571,7 @@ impl fmt::Debug for FileEntry<'_> {
             .field("compilation_dir", &self.compilation_dir_str())
             .field("name", &self.name_str())
             .field("dir", &self.dir_str())
+            .field("checksum", &self.checksum)
             .finish()
     }
 }
@@ -516,6 +595,8 @@ pub struct LineInfo<'data> {
     pub file: FileInfo<'data>,
     /// Absolute line number starting at 1. Zero means no line number.
     pub line: u64,
+    /// Column number starting at 1, if known.
+    pub column: Option<u64>,
 }
 
 impl fmt::Debug for LineInfo<'_> {
@@ -530,6 +611,7 @@ impl fmt::Debug for LineInfo<'_> {
 
         s.field("file", &self.file)
             .field("line", &self.line)
+            .field("column", &self.column)
             .finish()
     }
 }
@@ -551,6 +633,14 @@ pub struct Function<'data> {
     pub inlinees: Vec<Function<'data>>,
     /// Specifies whether this function is inlined.
     pub inline: bool,
+    /// Specifies whether multiple functions share the code of this function.
+    ///
+    /// This is usually the result of identical code folding (ICF), where the linker merges
+    /// functions with identical machine code. The name of this function is therefore ambiguous.
+    /// This is only set if the debug information or symbol table allows to detect folding.
+    pub multiple: bool,
+    /// The size of parameters passed on the stack, if known.
+    pub parameter_size: Option<u64>,
 }
 
 impl Function<'_> {
@@ -575,10 +665,150 @@ impl fmt::Debug for Function<'_> {
             .field("lines", &self.lines)
             .field("inlinees", &self.inlinees)
             .field("inline", &self.inline)
+            .field("multiple", &self.multiple)
+            .field("parameter_size", &self.parameter_size)
             .finish()
     }
 }
 
+/// Specifies whether a [`Variable`](struct.Variable.html) is a parameter or a local variable.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum VariableKind {
+    /// A formal parameter of the function.
+    Parameter,
+    /// A variable declared in the function body.
+    Local,
+}
+
+/// Raw description of where the value of a [`Variable`](struct.Variable.html) is stored.
+///
+/// Locations are specific to the debug information format and the target architecture. They are
+/// not evaluated.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VariableLocation<'data> {
+    /// The location is not known, for instance because the variable has been optimized out.
+    Unknown,
+    /// A DWARF location expression that is valid throughout the variable's scope.
+    Expression(&'data [u8]),
+    /// DWARF location expressions, each valid in the given range of relative addresses.
+    List(Vec<(Range<u64>, &'data [u8])>),
+    /// The value is stored in a register, identified by its CodeView register number.
+    Register(u16),
+    /// The value is stored in memory at an offset from a register, identified by its CodeView
+    /// register number.
+    RegisterRelative {
+        /// The CodeView register number.
+        register: u16,
+        /// The offset added to the register's value.
+        offset: i32,
+    },
+}
+
+/// Debug information for a parameter or local variable of a function.
+#[derive(Clone, Debug)]
+pub struct Variable<'data> {
+    /// Name of the variable.
+    pub name: Cow<'data, str>,
+    /// Specifies whether this variable is a parameter or a local variable.
+    pub kind: VariableKind,
+    /// Name of the variable's type, if known.
+    pub type_name: Option<Cow<'data, str>>,
+    /// Relative instruction address of the function that declares this variable.
+    ///
+    /// For variables of inlined functions, this is the start address of the inlinee.
+    pub function_address: u64,
+    /// Ranges of relative instruction addresses in which the variable is in scope.
+    pub scope: Vec<Range<u64>>,
+    /// Raw location of the variable's value.
+    pub location: VariableLocation<'data>,
+}
+
+/// The kind of a [`TypeInfo`](struct.TypeInfo.html).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TypeKind {
+    /// A structure declared with `struct`.
+    Struct,
+    /// A class declared with `class`.
+    Class,
+    /// A union declared with `union`.
+    Union,
+    /// An enumeration declared with `enum`.
+    Enum,
+    /// An alias of another type declared with `typedef` or `using`.
+    Typedef,
+}
+
+/// A data member of a structure, class or union.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TypeMember<'data> {
+    /// Name of the member.
+    pub name: Cow<'data, str>,
+    /// Name of the member's type, if known.
+    pub type_name: Option<Cow<'data, str>>,
+    /// Offset of the member from the start of the containing type in bytes.
+    pub offset: u64,
+    /// Size of a bitfield member in bits.
+    pub bit_size: Option<u64>,
+    /// Offset of a bitfield member in bits from the least significant bit at `offset`.
+    pub bit_offset: Option<u64>,
+}
+
+/// A base class of a structure or class.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BaseClass<'data> {
+    /// Name of the base class.
+    pub type_name: Cow<'data, str>,
+    /// Offset of the base class subobject in bytes.
+    ///
+    /// This is `None` for virtual base classes, whose offset is only known at runtime.
+    pub offset: Option<u64>,
+    /// Whether this is a virtual base class.
+    pub is_virtual: bool,
+}
+
+/// A named constant of an enumeration.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Enumerator<'data> {
+    /// Name of the constant.
+    pub name: Cow<'data, str>,
+    /// Value of the constant.
+    pub value: i64,
+}
+
+/// Debug information for a user-defined type.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TypeInfo<'data> {
+    /// The kind of this type.
+    pub kind: TypeKind,
+    /// The fully qualified name of this type, or an empty string for anonymous types.
+    pub name: Cow<'data, str>,
+    /// Size of this type in bytes, if known.
+    pub size: Option<u64>,
+    /// Data members of structures, classes and unions in declaration order.
+    pub members: Vec<TypeMember<'data>>,
+    /// Base classes of structures and classes in declaration order.
+    pub base_classes: Vec<BaseClass<'data>>,
+    /// Constants of enumerations in declaration order.
+    pub enumerators: Vec<Enumerator<'data>>,
+    /// Name of the aliased type for typedefs.
+    pub aliased_type: Option<Cow<'data, str>>,
+}
+
+impl<'data> TypeInfo<'data> {
+    /// Creates a type without members, base classes or enumerators.
+    pub(crate) fn new(kind: TypeKind, name: Cow<'data, str>, size: Option<u64>) -> Self {
+        TypeInfo {
+            kind,
+            name,
+            size,
+            members: Vec::new(),
+            base_classes: Vec::new(),
+            enumerators: Vec::new(),
+            aliased_type: None,
+        }
+    }
+}
+
 /// A dynamically dispatched iterator over items with the given lifetime.
 pub type DynIterator<'a, T> = Box<dyn Iterator<Item = T> + 'a>;
 
@@ -746,6 +976,7 @@ mod tests {
         FileEntry {
             compilation_dir: compilation_dir.as_bytes(),
             info: file_info(dir, name),
+            checksum: None,
         }
     }
 
@@ -784,4 +1015,27 @@ mod tests {
             "/src/foo.h"
         );
     }
+
+    #[test]
+    fn test_symbol_map_aliases() {
+        let symbol = |address, name| Symbol {
+            name: Some(Cow::Borrowed(name)),
+            address,
+            size: 0,
+            multiple: false,
+            parameter_size: None,
+        };
+
+        let map: SymbolMap<'_> = vec![
+            symbol(0x1000, "foo"),
+            symbol(0x1000, "foo"),
+            symbol(0x2000, "bar"),
+            symbol(0x2000, "baz"),
+        ]
+        .into();
+
+        // Aliases at the same address are merged, but do not indicate folded code.
+        let symbols: Vec<_> = map.iter().map(|s| (s.address, s.multiple)).collect();
+        assert_eq!(symbols, [(0x1000, false), (0x2000, false)]);
+    }
 }
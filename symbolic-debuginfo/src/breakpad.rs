@@ -12,7 +12,13 @@ use thiserror::Error;
 use symbolic_common::{Arch, AsSelf, CodeId, DebugId, Language, Name, NameMangling};
 
 use crate::base::*;
-use crate::private::{Lines, Parse};
+use crate::private::{FunctionStack, Lines, Parse};
+
+mod stack;
+mod validate;
+
+pub use stack::*;
+pub use validate::*;
 
 mod parser {
     use pest_derive::Parser;
@@ -329,6 +335,86 @@ impl<'d> Iterator for BreakpadFileRecords<'d> {
 /// A map of file paths by their file ID.
 pub type BreakpadFileMap<'d> = BTreeMap<u64, &'d str>;
 
+/// An [inline origin record], specifying the name of an inlined function.
+///
+/// The ID of this record is referenced by [`BreakpadInlineRecord`]. Inline origin records follow
+/// the file records and precede all function records.
+///
+/// Example: `INLINE_ORIGIN 1305 SharedLibraryInfo::Initialize()`
+///
+/// [inline origin record]: https://github.com/google/breakpad/blob/master/docs/symbol_files.md#inline_origin-records
+/// [`BreakpadInlineRecord`]: struct.BreakpadInlineRecord.html
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BreakpadInlineOriginRecord<'d> {
+    /// Breakpad-internal identifier of the inline origin.
+    pub id: u64,
+    /// The demangled name of the inlined function.
+    pub name: &'d str,
+}
+
+impl<'d> BreakpadInlineOriginRecord<'d> {
+    /// Parses an inline origin record from a single line.
+    pub fn parse(data: &'d [u8]) -> Result<Self, BreakpadError> {
+        let string = str::from_utf8(data)?;
+        let parsed = BreakpadParser::parse(Rule::inline_origin, string)?
+            .next()
+            .unwrap();
+        let mut record = BreakpadInlineOriginRecord::default();
+
+        for pair in parsed.into_inner() {
+            match pair.as_rule() {
+                Rule::origin_id => {
+                    record.id = u64::from_str_radix(pair.as_str(), 10)
+                        .map_err(|_| BreakpadErrorKind::Parse("inline origin identifier"))?;
+                }
+                Rule::name => record.name = pair.as_str(),
+                _ => unreachable!(),
+            }
+        }
+
+        Ok(record)
+    }
+}
+
+/// An iterator over inline origin records in a Breakpad object.
+#[derive(Clone, Debug)]
+pub struct BreakpadInlineOriginRecords<'d> {
+    lines: Lines<'d>,
+    finished: bool,
+}
+
+impl<'d> Iterator for BreakpadInlineOriginRecords<'d> {
+    type Item = Result<BreakpadInlineOriginRecord<'d>, BreakpadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        while let Some(line) = self.lines.next() {
+            if line.starts_with(b"MODULE ")
+                || line.starts_with(b"INFO ")
+                || line.starts_with(b"FILE ")
+            {
+                continue;
+            }
+
+            // Fast path: INLINE_ORIGIN records come right after the file records.
+            if !line.starts_with(b"INLINE_ORIGIN ") {
+                break;
+            }
+
+            return Some(BreakpadInlineOriginRecord::parse(line));
+        }
+
+        self.finished = true;
+        None
+    }
+}
+
+/// A map of inlined function names by their inline origin ID.
+pub type BreakpadInlineOriginMap<'d> = BTreeMap<u64, &'d str>;
+
 /// A [public function symbol record].
 ///
 /// Example: `PUBLIC m 2160 0 Public2_1`
@@ -477,6 +563,14 @@ impl<'d> BreakpadFuncRecord<'d> {
             finished: false,
         }
     }
+
+    /// Returns an iterator over inline records associated to this function.
+    pub fn inlinees(&self) -> BreakpadInlineRecords<'d> {
+        BreakpadInlineRecords {
+            lines: self.lines.clone(),
+            finished: false,
+        }
+    }
 }
 
 impl PartialEq for BreakpadFuncRecord<'_> {
@@ -627,8 +721,8 @@ impl<'d> Iterator for BreakpadLineRecords<'d> {
 
             // There might be empty lines throughout the file (or at the end). This is the only
             // iterator that cannot rely on a record identifier, so we have to explicitly skip empty
-            // lines.
-            if line.is_empty() {
+            // lines. INLINE records are interleaved with line records and read separately.
+            if line.is_empty() || line.starts_with(b"INLINE ") {
                 continue;
             }
 
@@ -648,6 +742,136 @@ impl<'d> Iterator for BreakpadLineRecords<'d> {
     }
 }
 
+/// An address range covered by a [`BreakpadInlineRecord`].
+///
+/// [`BreakpadInlineRecord`]: struct.BreakpadInlineRecord.html
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct BreakpadInlineAddressRange {
+    /// The start address of this range relative to the image base (load address).
+    pub address: u64,
+    /// The size of the code covered by this range.
+    pub size: u64,
+}
+
+impl BreakpadInlineAddressRange {
+    /// The end address of this range (exclusive).
+    pub fn end_address(&self) -> u64 {
+        self.address.saturating_add(self.size)
+    }
+}
+
+/// An [inline record] associated to a `BreakpadFuncRecord`.
+///
+/// Inline records describe call sites that have been inlined into the function. They immediately
+/// follow the [`BreakpadFuncRecord`] that they belong to, before its line records. The name of the
+/// inlined function is stored in a [`BreakpadInlineOriginRecord`].
+///
+/// Example: `INLINE 0 3082 52 1410 49200 10`
+///
+/// [inline record]: https://github.com/google/breakpad/blob/master/docs/symbol_files.md#inline-records
+/// [`BreakpadFuncRecord`]: struct.BreakpadFuncRecord.html
+/// [`BreakpadInlineOriginRecord`]: struct.BreakpadInlineOriginRecord.html
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BreakpadInlineRecord {
+    /// The nesting level of this inlinee, where `0` means inlined directly into the function.
+    pub inline_depth: u64,
+    /// The line number of the call site.
+    pub call_site_line: u64,
+    /// Identifier of the [`BreakpadFileRecord`] specifying the file of the call site.
+    pub call_site_file_id: u64,
+    /// Identifier of the [`BreakpadInlineOriginRecord`] specifying the name of the inlinee.
+    pub origin_id: u64,
+    /// The address ranges covered by the inlined code.
+    pub address_ranges: Vec<BreakpadInlineAddressRange>,
+}
+
+impl BreakpadInlineRecord {
+    /// Parses an inline record from a single line.
+    pub fn parse(data: &[u8]) -> Result<Self, BreakpadError> {
+        let string = str::from_utf8(data)?;
+        let parsed = BreakpadParser::parse(Rule::inline, string)?.next().unwrap();
+        let mut record = BreakpadInlineRecord::default();
+
+        for pair in parsed.into_inner() {
+            match pair.as_rule() {
+                Rule::inline_depth => {
+                    record.inline_depth = u64::from_str_radix(pair.as_str(), 10)
+                        .map_err(|_| BreakpadErrorKind::Parse("inline nest level"))?;
+                }
+                Rule::call_line => {
+                    record.call_site_line = u64::from_str_radix(pair.as_str(), 10)
+                        .map_err(|_| BreakpadErrorKind::Parse("inline call site line"))?;
+                }
+                Rule::file_id => {
+                    record.call_site_file_id = u64::from_str_radix(pair.as_str(), 10)
+                        .map_err(|_| BreakpadErrorKind::Parse("inline call site file"))?;
+                }
+                Rule::origin_id => {
+                    record.origin_id = u64::from_str_radix(pair.as_str(), 10)
+                        .map_err(|_| BreakpadErrorKind::Parse("inline origin identifier"))?;
+                }
+                Rule::inline_range => {
+                    let mut range = BreakpadInlineAddressRange::default();
+                    for pair in pair.into_inner() {
+                        match pair.as_rule() {
+                            Rule::addr => {
+                                range.address = u64::from_str_radix(pair.as_str(), 16)
+                                    .map_err(|_| BreakpadErrorKind::Parse("inline address"))?;
+                            }
+                            Rule::size => {
+                                range.size = u64::from_str_radix(pair.as_str(), 16)
+                                    .map_err(|_| BreakpadErrorKind::Parse("inline size"))?;
+                            }
+                            _ => unreachable!(),
+                        }
+                    }
+                    record.address_ranges.push(range);
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        Ok(record)
+    }
+}
+
+/// An iterator over inline records in a `BreakpadFuncRecord`.
+#[derive(Clone, Debug)]
+pub struct BreakpadInlineRecords<'d> {
+    lines: Lines<'d>,
+    finished: bool,
+}
+
+impl<'d> Iterator for BreakpadInlineRecords<'d> {
+    type Item = Result<BreakpadInlineRecord, BreakpadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        while let Some(line) = self.lines.next() {
+            // Stop parsing INLINE records once other expected records are encountered.
+            if line.starts_with(b"FUNC ")
+                || line.starts_with(b"PUBLIC ")
+                || line.starts_with(b"STACK ")
+            {
+                break;
+            }
+
+            // Line records are interleaved with INLINE records and skipped here.
+            if !line.starts_with(b"INLINE ") {
+                continue;
+            }
+
+            return Some(BreakpadInlineRecord::parse(line));
+        }
+
+        self.finished = true;
+        None
+    }
+}
+
 /// A [call frame information record] for platforms other than Windows x86.
 ///
 /// Example: `STACK CFI INIT 804c4b0 40 .cfa: $esp 4 + $eip: .cfa 4 - ^`
@@ -683,6 +907,11 @@ impl<'d> BreakpadStackCfiRecord<'d> {
 
         record
     }
+
+    /// Parses the unwind program rules of this record.
+    pub fn rules(&self) -> Result<BreakpadStackCfiRules<'d>, BreakpadError> {
+        BreakpadStackCfiRules::parse(self.text)
+    }
 }
 
 /// A [Windows stack frame record], used on x86.
@@ -720,6 +949,11 @@ impl<'d> BreakpadStackWinRecord<'d> {
 
         record
     }
+
+    /// Parses the frame sizes and the program string of this record.
+    pub fn frame_info(&self) -> Result<BreakpadStackWinFrameInfo<'d>, BreakpadError> {
+        BreakpadStackWinFrameInfo::parse(self.text)
+    }
 }
 
 /// Stack frame information record used for stack unwinding and stackwalking.
@@ -867,6 +1101,11 @@ impl<'data> BreakpadObject<'data> {
         self.module.name
     }
 
+    /// The operating system declared in the `MODULE` record, such as `Linux` or `windows`.
+    pub fn os(&self) -> &'data str {
+        self.module.os
+    }
+
     /// The kind of this object.
     pub fn kind(&self) -> ObjectKind {
         ObjectKind::Debug
@@ -914,6 +1153,7 @@ impl<'data> BreakpadObject<'data> {
     pub fn debug_session(&self) -> Result<BreakpadDebugSession<'data>, BreakpadError> {
         Ok(BreakpadDebugSession {
             file_map: self.file_map(),
+            inline_origin_map: self.inline_origin_map(),
             func_records: self.func_records(),
         })
     }
@@ -952,6 +1192,22 @@ impl<'data> BreakpadObject<'data> {
             .collect()
     }
 
+    /// Returns an iterator over inline origin records.
+    pub fn inline_origin_records(&self) -> BreakpadInlineOriginRecords<'data> {
+        BreakpadInlineOriginRecords {
+            lines: Lines::new(self.data),
+            finished: false,
+        }
+    }
+
+    /// Returns a map for inlined function name lookups by inline origin id.
+    pub fn inline_origin_map(&self) -> BreakpadInlineOriginMap<'data> {
+        self.inline_origin_records()
+            .filter_map(Result::ok)
+            .map(|origin| (origin.id, origin.name))
+            .collect()
+    }
+
     /// Returns an iterator over public symbol records.
     pub fn public_records(&self) -> BreakpadPublicRecords<'data> {
         BreakpadPublicRecords {
@@ -1091,6 +1347,8 @@ impl<'data> Iterator for BreakpadSymbolIterator<'data> {
                     name: Some(Cow::Borrowed(record.name)),
                     address: record.address,
                     size: 0,
+                    multiple: record.multiple,
+                    parameter_size: Some(record.parameter_size),
                 });
             }
         }
@@ -1102,6 +1360,7 @@ impl<'data> Iterator for BreakpadSymbolIterator<'data> {
 /// Debug session for Breakpad objects.
 pub struct BreakpadDebugSession<'data> {
     file_map: BreakpadFileMap<'data>,
+    inline_origin_map: BreakpadInlineOriginMap<'data>,
     func_records: BreakpadFuncRecords<'data>,
 }
 
@@ -1110,6 +1369,7 @@ impl<'data> BreakpadDebugSession<'data> {
     pub fn functions(&self) -> BreakpadFunctionIterator<'_> {
         BreakpadFunctionIterator {
             file_map: &self.file_map,
+            inline_origin_map: &self.inline_origin_map,
             func_records: self.func_records.clone(),
         }
     }
@@ -1160,6 +1420,7 @@ impl<'s> Iterator for BreakpadFileIterator<'s> {
         Some(Ok(FileEntry {
             compilation_dir: &[],
             info: FileInfo::from_path(path.as_bytes()),
+            checksum: None,
         }))
     }
 }
@@ -1167,6 +1428,7 @@ impl<'s> Iterator for BreakpadFileIterator<'s> {
 /// An iterator over functions in a Breakpad object.
 pub struct BreakpadFunctionIterator<'s> {
     file_map: &'s BreakpadFileMap<'s>,
+    inline_origin_map: &'s BreakpadInlineOriginMap<'s>,
     func_records: BreakpadFuncRecords<'s>,
 }
 
@@ -1182,19 +1444,159 @@ impl<'s> BreakpadFunctionIterator<'s> {
                 size: Some(line.size),
                 file: FileInfo::from_path(filename.as_bytes()),
                 line: line.line,
+                column: None,
             });
         }
 
-        Ok(Function {
-            address: record.address,
-            size: record.size,
-            name: Name::new(record.name, NameMangling::Unmangled, Language::Unknown),
-            compilation_dir: &[],
-            lines,
-            inlinees: Vec::new(),
-            inline: false,
-        })
+        let mut stack = FunctionStack::new();
+        stack.push(
+            0,
+            Function {
+                address: record.address,
+                size: record.size,
+                name: Name::new(record.name, NameMangling::Unmangled, Language::Unknown),
+                compilation_dir: &[],
+                lines,
+                inlinees: Vec::new(),
+                inline: false,
+                multiple: record.multiple,
+                parameter_size: Some(record.parameter_size),
+            },
+        );
+
+        let mut functions = Vec::with_capacity(1);
+        for inline in record.inlinees() {
+            let inline = inline?;
+
+            let mut ranges = inline.address_ranges;
+            ranges.retain(|range| range.size > 0);
+            ranges.sort_by_key(|range| range.address);
+
+            let start = match ranges.first() {
+                Some(range) => range.address,
+                None => continue,
+            };
+
+            let end = ranges
+                .iter()
+                .map(BreakpadInlineAddressRange::end_address)
+                .max()
+                .unwrap_or(start);
+
+            // The function record is at depth 0, so inlinees start at depth 1. Flushing never
+            // removes the function record, which therefore always serves as the parent.
+            let depth = inline.inline_depth.min(isize::MAX as u64 - 1) as isize + 1;
+            stack.flush(depth, &mut functions);
+            let parent = stack.peek_mut().unwrap();
+
+            let filename = self
+                .file_map
+                .get(&inline.call_site_file_id)
+                .copied()
+                .unwrap_or_default();
+            let call_file = FileInfo::from_path(filename.as_bytes());
+            let lines = split_inline_lines(
+                &mut parent.lines,
+                &ranges,
+                &call_file,
+                inline.call_site_line,
+            );
+
+            let name = self
+                .inline_origin_map
+                .get(&inline.origin_id)
+                .copied()
+                .unwrap_or(UNKNOWN_NAME);
+
+            stack.push(
+                depth,
+                Function {
+                    address: start,
+                    size: end - start,
+                    name: Name::new(name, NameMangling::Unmangled, Language::Unknown),
+                    compilation_dir: &[],
+                    lines,
+                    inlinees: Vec::new(),
+                    inline: true,
+                    multiple: false,
+                    parameter_size: None,
+                },
+            );
+        }
+
+        stack.flush(0, &mut functions);
+        Ok(functions.pop().unwrap())
+    }
+}
+
+/// Moves line records covered by the address ranges of an inlinee out of its parent.
+///
+/// Line records in Breakpad files always refer to the innermost inlined function. Records that
+/// overlap with the given ranges are split at the range boundaries. The covered parts are returned,
+/// and in the parent they are replaced with records pointing to the call site of the inlinee.
+fn split_inline_lines<'s>(
+    lines: &mut Vec<LineInfo<'s>>,
+    ranges: &[BreakpadInlineAddressRange],
+    call_file: &FileInfo<'s>,
+    call_line: u64,
+) -> Vec<LineInfo<'s>> {
+    let mut parent_lines = Vec::with_capacity(lines.len());
+    let mut inlinee_lines = Vec::new();
+
+    for line in lines.drain(..) {
+        let end = line.address + line.size.unwrap_or(0);
+        let mut address = line.address;
+
+        for range in ranges {
+            if range.end_address() <= address || range.address >= end {
+                continue;
+            }
+
+            if range.address > address {
+                push_line(&mut parent_lines, &line, address, range.address);
+                address = range.address;
+            }
+
+            let split_end = end.min(range.end_address());
+            push_line(&mut inlinee_lines, &line, address, split_end);
+
+            let call_site = LineInfo {
+                file: call_file.clone(),
+                line: call_line,
+                column: None,
+                ..line.clone()
+            };
+            push_line(&mut parent_lines, &call_site, address, split_end);
+            address = split_end;
+        }
+
+        if address < end {
+            push_line(&mut parent_lines, &line, address, end);
+        }
+    }
+
+    *lines = parent_lines;
+    inlinee_lines
+}
+
+/// Appends a copy of the line record covering the given range.
+///
+/// If the previous record refers to the same file and line and ends at the start of the range, it
+/// is extended instead.
+fn push_line<'s>(lines: &mut Vec<LineInfo<'s>>, line: &LineInfo<'s>, start: u64, end: u64) {
+    if let Some(prev) = lines.last_mut() {
+        let prev_end = prev.address + prev.size.unwrap_or(0);
+        if prev_end == start && prev.line == line.line && prev.file == line.file {
+            prev.size = Some(end - prev.address);
+            return;
+        }
     }
+
+    lines.push(LineInfo {
+        address: start,
+        size: Some(end - start),
+        ..line.clone()
+    });
 }
 
 impl<'s> Iterator for BreakpadFunctionIterator<'s> {
@@ -1369,6 +1771,48 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_inline_origin_record() -> Result<(), BreakpadError> {
+        let string = b"INLINE_ORIGIN 1305 SharedLibraryInfo::Initialize()";
+        let record = BreakpadInlineOriginRecord::parse(string)?;
+
+        insta::assert_debug_snapshot!(record, @r###"
+       ⋮BreakpadInlineOriginRecord {
+       ⋮    id: 1305,
+       ⋮    name: "SharedLibraryInfo::Initialize()",
+       ⋮}
+        "###);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_inline_record() -> Result<(), BreakpadError> {
+        let string = b"INLINE 1 3082 52 1410 49200 10 49300 a";
+        let record = BreakpadInlineRecord::parse(string)?;
+
+        insta::assert_debug_snapshot!(record, @r###"
+       ⋮BreakpadInlineRecord {
+       ⋮    inline_depth: 1,
+       ⋮    call_site_line: 3082,
+       ⋮    call_site_file_id: 52,
+       ⋮    origin_id: 1410,
+       ⋮    address_ranges: [
+       ⋮        BreakpadInlineAddressRange {
+       ⋮            address: 299520,
+       ⋮            size: 16,
+       ⋮        },
+       ⋮        BreakpadInlineAddressRange {
+       ⋮            address: 299776,
+       ⋮            size: 10,
+       ⋮        },
+       ⋮    ],
+       ⋮}
+        "###);
+
+        Ok(())
+    }
+
     #[test]
     fn test_parse_public_record() -> Result<(), BreakpadError> {
         let string = b"PUBLIC 5180 0 __clang_call_terminate";
@@ -1452,4 +1896,33 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_multiple_and_parameter_size() -> Result<(), BreakpadError> {
+        let data = b"MODULE windows x86 0B7C6E2E1F9D4A6A8C5E2F1B3D4C5A6B1 test.pdb
+FUNC m 1000 10 8 foo
+FUNC 1010 10 0 bar
+PUBLIC m 2000 4 baz
+PUBLIC 2010 0 qux
+";
+        let object = BreakpadObject::parse(data)?;
+
+        let symbols: Vec<_> = object
+            .symbols()
+            .map(|s| (s.address, s.multiple, s.parameter_size))
+            .collect();
+        assert_eq!(symbols, [(0x2000, true, Some(4)), (0x2010, false, Some(0))]);
+
+        let session = object.debug_session()?;
+        let functions = session
+            .functions()
+            .map(|f| f.map(|f| (f.address, f.multiple, f.parameter_size)))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            functions,
+            [(0x1000, true, Some(8)), (0x1010, false, Some(0))]
+        );
+
+        Ok(())
+    }
 }
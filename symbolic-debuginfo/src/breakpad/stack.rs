@@ -0,0 +1,1000 @@
+//! Typed representation and evaluation of Breakpad stack unwinding records.
+//!
+//! Breakpad describes how to recover the registers of a caller frame with two kinds of records:
+//!
+//!  - `STACK CFI` records contain a rule for the canonical frame address (CFA) and a rule for each
+//!    recovered register. Rules are postfix expressions over the callee's registers.
+//!  - `STACK WIN` records describe Windows x86 frames. They either contain a program string that
+//!    assigns postfix expressions to registers and temporary variables, or only the sizes of the
+//!    frame's parameters, saved registers and locals.
+//!
+//! The records are parsed with [`BreakpadStackCfiRecord::rules`] and
+//! [`BreakpadStackWinRecord::frame_info`]. A [`BreakpadStackEvaluator`] computes the caller's
+//! registers from the callee's registers and a [`BreakpadMemory`] to read stack memory.
+//!
+//! [`BreakpadStackCfiRecord::rules`]: ../struct.BreakpadStackCfiRecord.html#method.rules
+//! [`BreakpadStackWinRecord::frame_info`]: ../struct.BreakpadStackWinRecord.html#method.frame_info
+//! [`BreakpadStackEvaluator`]: struct.BreakpadStackEvaluator.html
+//! [`BreakpadMemory`]: trait.BreakpadMemory.html
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+use thiserror::Error;
+
+use symbolic_common::Arch;
+
+use super::{BreakpadError, BreakpadErrorKind};
+
+/// The variable holding the canonical frame address in `STACK CFI` rules.
+const CFA_REGISTER: &str = ".cfa";
+
+/// The variable holding the return address in `STACK CFI` rules.
+const RA_REGISTER: &str = ".ra";
+
+/// The program evaluated for `STACK WIN` records without a program string.
+const DEFAULT_WIN_PROGRAM: &str = "$eip .raSearchStart ^ = $esp .raSearchStart 4 + =";
+
+/// The error type for [`BreakpadEvalError`].
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BreakpadEvalErrorKind {
+    /// An expression refers to a register or variable that is not defined.
+    UndefinedVariable,
+
+    /// Reading stack memory failed.
+    MemoryReadFailed,
+
+    /// An expression divides by zero.
+    DivisionByZero,
+
+    /// A rule required for unwinding is missing.
+    MissingRule,
+}
+
+impl fmt::Display for BreakpadEvalErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UndefinedVariable => write!(f, "undefined variable"),
+            Self::MemoryReadFailed => write!(f, "failed to read memory"),
+            Self::DivisionByZero => write!(f, "division by zero"),
+            Self::MissingRule => write!(f, "missing unwind rule"),
+        }
+    }
+}
+
+/// An error returned by [`BreakpadStackEvaluator`](struct.BreakpadStackEvaluator.html).
+#[derive(Debug, Error)]
+#[error("{kind}")]
+pub struct BreakpadEvalError {
+    kind: BreakpadEvalErrorKind,
+    #[source]
+    source: Option<Box<dyn Error + Send + Sync + 'static>>,
+}
+
+impl BreakpadEvalError {
+    /// Creates a new evaluation error from a known kind of error as well as an arbitrary error
+    /// payload.
+    fn new<E>(kind: BreakpadEvalErrorKind, source: E) -> Self
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        let source = Some(source.into());
+        Self { kind, source }
+    }
+
+    /// Returns the corresponding [`BreakpadEvalErrorKind`] for this error.
+    pub fn kind(&self) -> BreakpadEvalErrorKind {
+        self.kind
+    }
+}
+
+impl From<BreakpadEvalErrorKind> for BreakpadEvalError {
+    fn from(kind: BreakpadEvalErrorKind) -> Self {
+        Self { kind, source: None }
+    }
+}
+
+/// An operator in a postfix expression.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BreakpadPostfixOperator {
+    /// Addition of two operands (`+`).
+    Add,
+    /// Subtraction of the second operand from the first (`-`).
+    Subtract,
+    /// Multiplication of two operands (`*`).
+    Multiply,
+    /// Unsigned division of the first operand by the second (`/`).
+    Divide,
+    /// Unsigned remainder of the division of the first operand by the second (`%`).
+    Remainder,
+    /// Alignment of the first operand down to a multiple of the second (`@`).
+    Align,
+    /// Reads a pointer-sized value from memory at the address of the operand (`^`).
+    Dereference,
+}
+
+impl BreakpadPostfixOperator {
+    /// Parses an operator from a single token.
+    fn from_token(token: &str) -> Option<Self> {
+        Some(match token {
+            "+" => Self::Add,
+            "-" => Self::Subtract,
+            "*" => Self::Multiply,
+            "/" => Self::Divide,
+            "%" => Self::Remainder,
+            "@" => Self::Align,
+            "^" => Self::Dereference,
+            _ => return None,
+        })
+    }
+
+    /// Returns the token of this operator.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Add => "+",
+            Self::Subtract => "-",
+            Self::Multiply => "*",
+            Self::Divide => "/",
+            Self::Remainder => "%",
+            Self::Align => "@",
+            Self::Dereference => "^",
+        }
+    }
+
+    /// Returns the number of operands consumed by this operator.
+    pub fn arity(self) -> usize {
+        match self {
+            Self::Dereference => 1,
+            _ => 2,
+        }
+    }
+}
+
+impl fmt::Display for BreakpadPostfixOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A token in a postfix expression.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BreakpadPostfixToken<'d> {
+    /// A decimal integer literal.
+    Number(i64),
+    /// A register or variable, such as `$rsp`, `.cfa` or `$T0`.
+    Variable(&'d str),
+    /// An operator applied to the preceding operands.
+    Operator(BreakpadPostfixOperator),
+}
+
+impl<'d> BreakpadPostfixToken<'d> {
+    /// Parses a single token of a postfix expression.
+    fn parse(token: &'d str) -> Result<Self, BreakpadError> {
+        if let Some(operator) = BreakpadPostfixOperator::from_token(token) {
+            return Ok(Self::Operator(operator));
+        }
+
+        let first = token.chars().next().unwrap_or_default();
+        if first.is_ascii_digit() || first == '-' {
+            return token
+                .parse()
+                .map(Self::Number)
+                .map_err(|_| BreakpadErrorKind::Parse("postfix number").into());
+        }
+
+        Ok(Self::Variable(token))
+    }
+}
+
+impl fmt::Display for BreakpadPostfixToken<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Number(number) => write!(f, "{}", number),
+            Self::Variable(name) => f.write_str(name),
+            Self::Operator(operator) => write!(f, "{}", operator),
+        }
+    }
+}
+
+/// A postfix expression used in `STACK CFI` and `STACK WIN` records.
+///
+/// Example: `$rsp 8 + ^`
+///
+/// Expressions are validated during parsing: every operator has enough operands, and evaluating
+/// the expression yields exactly one value.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BreakpadPostfixExpression<'d> {
+    tokens: Vec<BreakpadPostfixToken<'d>>,
+}
+
+impl<'d> BreakpadPostfixExpression<'d> {
+    /// Parses a postfix expression from a string of whitespace-separated tokens.
+    pub fn parse(string: &'d str) -> Result<Self, BreakpadError> {
+        Self::from_tokens(string.split_whitespace())
+    }
+
+    /// Parses and validates a postfix expression from a sequence of tokens.
+    fn from_tokens<I>(tokens: I) -> Result<Self, BreakpadError>
+    where
+        I: IntoIterator<Item = &'d str>,
+    {
+        let tokens = tokens
+            .into_iter()
+            .map(BreakpadPostfixToken::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut depth = 0usize;
+        for token in &tokens {
+            match token {
+                BreakpadPostfixToken::Operator(operator) => {
+                    depth = depth
+                        .checked_sub(operator.arity())
+                        .ok_or(BreakpadErrorKind::Parse("postfix expression"))?;
+                    depth += 1;
+                }
+                _ => depth += 1,
+            }
+        }
+
+        if depth != 1 {
+            return Err(BreakpadErrorKind::Parse("postfix expression").into());
+        }
+
+        Ok(BreakpadPostfixExpression { tokens })
+    }
+
+    /// Returns the tokens of this expression in postfix order.
+    pub fn tokens(&self) -> &[BreakpadPostfixToken<'d>] {
+        &self.tokens
+    }
+}
+
+impl fmt::Display for BreakpadPostfixExpression<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, token) in self.tokens.iter().enumerate() {
+            if index > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}", token)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A rule to recover a register in a `STACK CFI` record.
+///
+/// Example: `$rbp: .cfa -16 + ^`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BreakpadStackCfiRule<'d> {
+    /// The register recovered by this rule, such as `.ra` or `$rbp`.
+    pub register: &'d str,
+    /// The expression computing the caller's value of the register.
+    pub expression: BreakpadPostfixExpression<'d>,
+}
+
+/// The parsed rules of a `STACK CFI` record.
+///
+/// Rules are given either by an `INIT` record, which covers an address range, or by a delta record
+/// that updates the rules starting at a specific address. To obtain the full set of rules at an
+/// address, start with the `INIT` record and [`update`](#method.update) it with all subsequent
+/// delta records up to that address.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BreakpadStackCfiRules<'d> {
+    /// The address at which the rules start to apply.
+    pub address: u64,
+    /// The size of the covered address range. Only `INIT` records declare a size.
+    pub size: Option<u64>,
+    /// The rule computing the canonical frame address.
+    pub cfa_rule: Option<BreakpadPostfixExpression<'d>>,
+    /// Rules for all other recovered registers, including the return address `.ra`.
+    pub register_rules: Vec<BreakpadStackCfiRule<'d>>,
+}
+
+impl<'d> BreakpadStackCfiRules<'d> {
+    /// Parses the text of a `STACK CFI` record, excluding the `STACK CFI` prefix.
+    pub fn parse(text: &'d str) -> Result<Self, BreakpadError> {
+        let mut tokens = text.split_whitespace().peekable();
+        let mut rules = BreakpadStackCfiRules::default();
+
+        let mut address = tokens.next().unwrap_or_default();
+        if address == "INIT" {
+            address = tokens.next().unwrap_or_default();
+            let size = tokens.next().unwrap_or_default();
+            rules.size = Some(
+                u64::from_str_radix(size, 16).map_err(|_| BreakpadErrorKind::Parse("cfi size"))?,
+            );
+        }
+
+        rules.address = u64::from_str_radix(address, 16)
+            .map_err(|_| BreakpadErrorKind::Parse("cfi address"))?;
+
+        while let Some(token) = tokens.next() {
+            let register = token
+                .strip_suffix(':')
+                .filter(|register| !register.is_empty())
+                .ok_or(BreakpadErrorKind::Parse("cfi rule register"))?;
+
+            let mut expression = Vec::new();
+            while let Some(&token) = tokens.peek() {
+                if token.ends_with(':') {
+                    break;
+                }
+                expression.push(token);
+                tokens.next();
+            }
+
+            let expression = BreakpadPostfixExpression::from_tokens(expression)?;
+            if register == CFA_REGISTER {
+                rules.cfa_rule = Some(expression);
+            } else {
+                rules.set_register_rule(register, expression);
+            }
+        }
+
+        Ok(rules)
+    }
+
+    /// Returns whether these rules come from an `INIT` record.
+    pub fn is_init(&self) -> bool {
+        self.size.is_some()
+    }
+
+    /// Returns the rule for the given register, if any.
+    pub fn register_rule(&self, register: &str) -> Option<&BreakpadPostfixExpression<'d>> {
+        self.register_rules
+            .iter()
+            .find(|rule| rule.register == register)
+            .map(|rule| &rule.expression)
+    }
+
+    /// Adds or replaces the rule for a register.
+    fn set_register_rule(&mut self, register: &'d str, expression: BreakpadPostfixExpression<'d>) {
+        match self
+            .register_rules
+            .iter_mut()
+            .find(|rule| rule.register == register)
+        {
+            Some(rule) => rule.expression = expression,
+            None => self.register_rules.push(BreakpadStackCfiRule {
+                register,
+                expression,
+            }),
+        }
+    }
+
+    /// Applies the rules of a subsequent delta record.
+    ///
+    /// Rules in `delta` replace rules for the same register. The address range of these rules is
+    /// left unchanged.
+    pub fn update(&mut self, delta: &BreakpadStackCfiRules<'d>) {
+        if let Some(ref cfa_rule) = delta.cfa_rule {
+            self.cfa_rule = Some(cfa_rule.clone());
+        }
+
+        for rule in &delta.register_rules {
+            self.set_register_rule(rule.register, rule.expression.clone());
+        }
+    }
+}
+
+/// The type of frame described by a `STACK WIN` record.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BreakpadStackWinRecordType {
+    /// Frame pointer omitted; FPO info available.
+    Fpo = 0,
+    /// Kernel trap frame.
+    Trap = 1,
+    /// Kernel task state segment frame.
+    Tss = 2,
+    /// Standard EBP stack frame.
+    Standard = 3,
+    /// Frame pointer omitted; frame data info available.
+    FrameData = 4,
+    /// A frame type that is not known to this library.
+    Unknown = -1,
+}
+
+/// An assignment in the program string of a `STACK WIN` record.
+///
+/// Example: `$eip $T0 4 + ^ =`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BreakpadStackWinAssignment<'d> {
+    /// The register or temporary variable that is assigned.
+    pub variable: &'d str,
+    /// The expression computing the assigned value.
+    pub expression: BreakpadPostfixExpression<'d>,
+}
+
+/// The parsed fields of a `STACK WIN` record.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BreakpadStackWinFrameInfo<'d> {
+    /// The type of the frame.
+    pub ty: BreakpadStackWinRecordType,
+    /// The address of the first instruction covered by this record.
+    pub address: u64,
+    /// The size of the covered code.
+    pub code_size: u64,
+    /// The size of the function prologue.
+    pub prologue_size: u32,
+    /// The size of the function epilogue.
+    pub epilogue_size: u32,
+    /// The size of parameters passed to the function.
+    pub parameter_size: u32,
+    /// The size of callee-saved registers pushed by the function.
+    pub saved_register_size: u32,
+    /// The size of local variables.
+    pub local_size: u32,
+    /// The maximum number of bytes pushed on the stack in the frame.
+    pub max_stack_size: u32,
+    /// The program string, if this record contains one.
+    pub program: Option<Vec<BreakpadStackWinAssignment<'d>>>,
+    /// Whether the function uses `$ebp` as a general-purpose register.
+    ///
+    /// This is only declared by records without a program string.
+    pub allocates_base_pointer: bool,
+}
+
+impl<'d> BreakpadStackWinFrameInfo<'d> {
+    /// Parses the text of a `STACK WIN` record, excluding the `STACK WIN` prefix.
+    pub fn parse(text: &'d str) -> Result<Self, BreakpadError> {
+        let mut tokens = text.split_whitespace();
+        let mut field = |name| parse_hex_field(tokens.next(), name);
+
+        let ty = match field("stack win type")? {
+            0 => BreakpadStackWinRecordType::Fpo,
+            1 => BreakpadStackWinRecordType::Trap,
+            2 => BreakpadStackWinRecordType::Tss,
+            3 => BreakpadStackWinRecordType::Standard,
+            4 => BreakpadStackWinRecordType::FrameData,
+            _ => BreakpadStackWinRecordType::Unknown,
+        };
+
+        let address = field("stack win address")?;
+        let code_size = field("stack win code size")?;
+        let prologue_size = field("stack win prologue size")? as u32;
+        let epilogue_size = field("stack win epilogue size")? as u32;
+        let parameter_size = field("stack win parameter size")? as u32;
+        let saved_register_size = field("stack win saved register size")? as u32;
+        let local_size = field("stack win local size")? as u32;
+        let max_stack_size = field("stack win max stack size")? as u32;
+        let has_program = field("stack win program flag")? != 0;
+
+        let (program, allocates_base_pointer) = if has_program {
+            (Some(parse_win_program(tokens)?), false)
+        } else {
+            let flag = parse_hex_field(tokens.next(), "stack win base pointer flag")?;
+            (None, flag != 0)
+        };
+
+        Ok(BreakpadStackWinFrameInfo {
+            ty,
+            address,
+            code_size,
+            prologue_size,
+            epilogue_size,
+            parameter_size,
+            saved_register_size,
+            local_size,
+            max_stack_size,
+            program,
+            allocates_base_pointer,
+        })
+    }
+}
+
+/// Parses a hexadecimal field of a `STACK WIN` record.
+fn parse_hex_field(token: Option<&str>, name: &'static str) -> Result<u64, BreakpadError> {
+    u64::from_str_radix(token.unwrap_or_default(), 16)
+        .map_err(|_| BreakpadErrorKind::Parse(name).into())
+}
+
+/// Parses a sequence of assignments of the form `variable expression =`.
+///
+/// Some compilers omit the space after an assignment, as in `$T0 $ebp 204 + =$eip`. Such tokens
+/// are split into the assignment and the following token.
+fn parse_win_program<'d, I>(tokens: I) -> Result<Vec<BreakpadStackWinAssignment<'d>>, BreakpadError>
+where
+    I: IntoIterator<Item = &'d str>,
+{
+    let mut program = Vec::new();
+    let mut current = Vec::new();
+
+    for token in tokens {
+        let token = match token.strip_prefix('=') {
+            Some(rest) if !rest.is_empty() => {
+                program.push(parse_win_assignment(&mut current)?);
+                rest
+            }
+            _ => token,
+        };
+
+        if token == "=" {
+            program.push(parse_win_assignment(&mut current)?);
+        } else {
+            current.push(token);
+        }
+    }
+
+    if !current.is_empty() {
+        return Err(BreakpadErrorKind::Parse("stack win assignment").into());
+    }
+
+    Ok(program)
+}
+
+/// Parses the tokens of a single assignment, excluding the trailing `=`, and clears them.
+fn parse_win_assignment<'d>(
+    tokens: &mut Vec<&'d str>,
+) -> Result<BreakpadStackWinAssignment<'d>, BreakpadError> {
+    let variable = match tokens.first() {
+        Some(&variable) => BreakpadPostfixToken::parse(variable)?,
+        None => return Err(BreakpadErrorKind::Parse("stack win assignment").into()),
+    };
+
+    let variable = match variable {
+        BreakpadPostfixToken::Variable(variable) => variable,
+        _ => return Err(BreakpadErrorKind::Parse("stack win assignment").into()),
+    };
+
+    let expression = BreakpadPostfixExpression::from_tokens(tokens.drain(..).skip(1))?;
+    Ok(BreakpadStackWinAssignment {
+        variable,
+        expression,
+    })
+}
+
+/// Returns whether a variable of a `STACK WIN` program is temporary rather than a register.
+fn is_win_temporary(variable: &str) -> bool {
+    match variable.strip_prefix("$T") {
+        Some(suffix) => suffix.bytes().all(|b| b.is_ascii_digit()),
+        None => variable == "$L" || variable == "$P" || !variable.starts_with('$'),
+    }
+}
+
+/// Memory of a process that is read when evaluating unwind rules.
+pub trait BreakpadMemory {
+    /// Reads a little-endian integer of `size` bytes at the given address.
+    ///
+    /// Returns `None` if the memory is not available. `size` is either 4 or 8.
+    fn read(&self, address: u64, size: usize) -> Option<u64>;
+}
+
+impl<M: BreakpadMemory + ?Sized> BreakpadMemory for &M {
+    fn read(&self, address: u64, size: usize) -> Option<u64> {
+        (**self).read(address, size)
+    }
+}
+
+/// Evaluates Breakpad unwind rules to compute the registers of a caller frame.
+///
+/// All arithmetic wraps at the pointer size of the architecture. Registers are identified by their
+/// names in the symbol file, such as `$rsp` on x86_64 or `sp` on ARM.
+///
+/// ## Example
+///
+/// ```rust
+/// use std::collections::BTreeMap;
+///
+/// use symbolic_common::Arch;
+/// use symbolic_debuginfo::breakpad::{BreakpadMemory, BreakpadStackCfiRules, BreakpadStackEvaluator};
+///
+/// struct Stack;
+///
+/// impl BreakpadMemory for Stack {
+///     fn read(&self, address: u64, _size: usize) -> Option<u64> {
+///         if address == 0x7000 {
+///             Some(0x1234)
+///         } else {
+///             None
+///         }
+///     }
+/// }
+///
+/// let rules = BreakpadStackCfiRules::parse("INIT 1000 10 .cfa: $rsp 8 + .ra: .cfa -8 + ^").unwrap();
+///
+/// let mut registers = BTreeMap::new();
+/// registers.insert("$rsp".to_owned(), 0x7000);
+///
+/// let evaluator = BreakpadStackEvaluator::new(Arch::Amd64, &Stack);
+/// let caller = evaluator.unwind_cfi(&rules, &registers).unwrap();
+/// assert_eq!(caller[".cfa"], 0x7008);
+/// assert_eq!(caller[".ra"], 0x1234);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct BreakpadStackEvaluator<M> {
+    memory: M,
+    pointer_size: usize,
+}
+
+impl<M: BreakpadMemory> BreakpadStackEvaluator<M> {
+    /// Creates a new evaluator for the given architecture that reads from `memory`.
+    ///
+    /// If the pointer size of the architecture is unknown, 64-bit pointers are assumed.
+    pub fn new(arch: Arch, memory: M) -> Self {
+        let pointer_size = arch.cpu_family().pointer_size().unwrap_or(8);
+        BreakpadStackEvaluator {
+            memory,
+            pointer_size,
+        }
+    }
+
+    /// Returns the pointer size in bytes used for arithmetic and memory reads.
+    pub fn pointer_size(&self) -> usize {
+        self.pointer_size
+    }
+
+    /// Truncates a value to the pointer size.
+    fn truncate(&self, value: u64) -> u64 {
+        match self.pointer_size {
+            4 => value & 0xffff_ffff,
+            _ => value,
+        }
+    }
+
+    /// Evaluates an expression with the given values of registers and variables.
+    pub fn evaluate(
+        &self,
+        expression: &BreakpadPostfixExpression<'_>,
+        variables: &BTreeMap<String, u64>,
+    ) -> Result<u64, BreakpadEvalError> {
+        let mut stack = Vec::with_capacity(expression.tokens.len());
+
+        for token in &expression.tokens {
+            let value = match *token {
+                BreakpadPostfixToken::Number(number) => number as u64,
+                BreakpadPostfixToken::Variable(name) => match variables.get(name) {
+                    Some(&value) => value,
+                    None => {
+                        let message = format!("`{}` is not defined", name);
+                        return Err(BreakpadEvalError::new(
+                            BreakpadEvalErrorKind::UndefinedVariable,
+                            message,
+                        ));
+                    }
+                },
+                BreakpadPostfixToken::Operator(BreakpadPostfixOperator::Dereference) => {
+                    // Expressions are validated during parsing, so operands are always present.
+                    let address = stack.pop().unwrap_or_default();
+                    self.memory
+                        .read(address, self.pointer_size)
+                        .ok_or_else(|| {
+                            let message = format!("cannot read address {:#x}", address);
+                            BreakpadEvalError::new(BreakpadEvalErrorKind::MemoryReadFailed, message)
+                        })?
+                }
+                BreakpadPostfixToken::Operator(operator) => {
+                    let rhs = stack.pop().unwrap_or_default();
+                    let lhs = stack.pop().unwrap_or_default();
+                    match operator {
+                        BreakpadPostfixOperator::Add => lhs.wrapping_add(rhs),
+                        BreakpadPostfixOperator::Subtract => lhs.wrapping_sub(rhs),
+                        BreakpadPostfixOperator::Multiply => lhs.wrapping_mul(rhs),
+                        BreakpadPostfixOperator::Divide => lhs
+                            .checked_div(rhs)
+                            .ok_or(BreakpadEvalErrorKind::DivisionByZero)?,
+                        BreakpadPostfixOperator::Remainder => lhs
+                            .checked_rem(rhs)
+                            .ok_or(BreakpadEvalErrorKind::DivisionByZero)?,
+                        BreakpadPostfixOperator::Align => lhs & rhs.wrapping_neg(),
+                        BreakpadPostfixOperator::Dereference => unreachable!(),
+                    }
+                }
+            };
+
+            stack.push(self.truncate(value));
+        }
+
+        Ok(stack.pop().unwrap_or_default())
+    }
+
+    /// Computes the caller's registers from `STACK CFI` rules.
+    ///
+    /// The rules must be complete for the current instruction, see
+    /// [`BreakpadStackCfiRules::update`](struct.BreakpadStackCfiRules.html#method.update). All
+    /// rules are evaluated with the callee's `registers` and the canonical frame address `.cfa`.
+    ///
+    /// The result contains `.cfa`, which is the caller's stack pointer, `.ra`, which is the
+    /// return address, and all other registers that have a rule.
+    pub fn unwind_cfi(
+        &self,
+        rules: &BreakpadStackCfiRules<'_>,
+        registers: &BTreeMap<String, u64>,
+    ) -> Result<BTreeMap<String, u64>, BreakpadEvalError> {
+        let cfa_rule = rules.cfa_rule.as_ref().ok_or_else(|| {
+            BreakpadEvalError::new(BreakpadEvalErrorKind::MissingRule, "missing `.cfa` rule")
+        })?;
+
+        if rules.register_rule(RA_REGISTER).is_none() {
+            let message = "missing `.ra` rule";
+            return Err(BreakpadEvalError::new(
+                BreakpadEvalErrorKind::MissingRule,
+                message,
+            ));
+        }
+
+        let cfa = self.evaluate(cfa_rule, registers)?;
+        let mut variables = registers.clone();
+        variables.insert(CFA_REGISTER.to_owned(), cfa);
+
+        let mut caller = BTreeMap::new();
+        caller.insert(CFA_REGISTER.to_owned(), cfa);
+        for rule in &rules.register_rules {
+            let value = self.evaluate(&rule.expression, &variables)?;
+            caller.insert(rule.register.to_owned(), value);
+        }
+
+        Ok(caller)
+    }
+
+    /// Computes the caller's registers from a `STACK WIN` record.
+    ///
+    /// The program string is evaluated with the callee's `registers`, such as `$eip`, `$esp` and
+    /// `$ebp`, as well as the following variables:
+    ///
+    ///  - `.cbParams`, `.cbSavedRegs` and `.cbLocals`: The sizes declared by the record.
+    ///  - `.cbCalleeParams`: Always zero, since the callee's parameter size is not known.
+    ///  - `.raSearch` and `.raSearchStart`: The address of the return address, which is assumed
+    ///    directly above the locals and saved registers. Unlike Breakpad's processor, the stack is
+    ///    not scanned for a plausible return address.
+    ///
+    /// Records without a program string recover `$eip` and `$esp` from `.raSearchStart`. If such
+    /// a record declares that the function uses `$ebp` as a general-purpose register, the
+    /// caller's `$ebp` cannot be recovered and is omitted from the result.
+    ///
+    /// The result contains all callee registers, updated with the registers assigned by the
+    /// program. Temporary variables such as `$T0` are omitted.
+    pub fn unwind_win(
+        &self,
+        info: &BreakpadStackWinFrameInfo<'_>,
+        registers: &BTreeMap<String, u64>,
+    ) -> Result<BTreeMap<String, u64>, BreakpadEvalError> {
+        let esp = registers.get("$esp").copied().ok_or_else(|| {
+            BreakpadEvalError::new(
+                BreakpadEvalErrorKind::UndefinedVariable,
+                "`$esp` is not defined",
+            )
+        })?;
+
+        let search_start = self.truncate(
+            esp.wrapping_add(u64::from(info.local_size))
+                .wrapping_add(u64::from(info.saved_register_size)),
+        );
+
+        let mut variables = registers.clone();
+        variables.insert(".cbParams".to_owned(), info.parameter_size.into());
+        variables.insert(".cbSavedRegs".to_owned(), info.saved_register_size.into());
+        variables.insert(".cbLocals".to_owned(), info.local_size.into());
+        variables.insert(".cbCalleeParams".to_owned(), 0);
+        variables.insert(".raSearch".to_owned(), search_start);
+        variables.insert(".raSearchStart".to_owned(), search_start);
+
+        let default_program;
+        let program = match info.program {
+            Some(ref program) => program,
+            None => {
+                default_program = parse_win_program(DEFAULT_WIN_PROGRAM.split_whitespace())
+                    .expect("invalid default STACK WIN program");
+                &default_program
+            }
+        };
+
+        let mut caller = registers.clone();
+        for assignment in program {
+            let value = self.evaluate(&assignment.expression, &variables)?;
+            variables.insert(assignment.variable.to_owned(), value);
+            if !is_win_temporary(assignment.variable) {
+                caller.insert(assignment.variable.to_owned(), value);
+            }
+        }
+
+        if info.program.is_none() && info.allocates_base_pointer {
+            caller.remove("$ebp");
+        }
+
+        Ok(caller)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Stack memory starting at a base address.
+    struct Stack(u64, Vec<u8>);
+
+    impl BreakpadMemory for Stack {
+        fn read(&self, address: u64, size: usize) -> Option<u64> {
+            let offset = address.checked_sub(self.0)? as usize;
+            let bytes = self.1.get(offset..offset + size)?;
+            let mut value = [0; 8];
+            value[..size].copy_from_slice(bytes);
+            Some(u64::from_le_bytes(value))
+        }
+    }
+
+    fn registers(values: &[(&str, u64)]) -> BTreeMap<String, u64> {
+        values
+            .iter()
+            .map(|&(name, value)| (name.to_owned(), value))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_expression() -> Result<(), BreakpadError> {
+        let expression = BreakpadPostfixExpression::parse(".cfa -8 + ^")?;
+        assert_eq!(
+            expression.tokens(),
+            [
+                BreakpadPostfixToken::Variable(".cfa"),
+                BreakpadPostfixToken::Number(-8),
+                BreakpadPostfixToken::Operator(BreakpadPostfixOperator::Add),
+                BreakpadPostfixToken::Operator(BreakpadPostfixOperator::Dereference),
+            ]
+        );
+        assert_eq!(expression.to_string(), ".cfa -8 + ^");
+
+        assert!(BreakpadPostfixExpression::parse("$rsp +").is_err());
+        assert!(BreakpadPostfixExpression::parse("$rsp 8").is_err());
+        assert!(BreakpadPostfixExpression::parse("").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_cfi_rules() -> Result<(), BreakpadError> {
+        let mut rules =
+            BreakpadStackCfiRules::parse("INIT 1880 2d .cfa: $rsp 8 + .ra: .cfa -8 + ^")?;
+        assert_eq!(rules.address, 0x1880);
+        assert_eq!(rules.size, Some(0x2d));
+        assert_eq!(rules.cfa_rule.as_ref().unwrap().to_string(), "$rsp 8 +");
+        assert_eq!(
+            rules.register_rule(".ra").unwrap().to_string(),
+            ".cfa -8 + ^"
+        );
+
+        let delta = BreakpadStackCfiRules::parse("1881 .cfa: $rsp 16 + $rbp: .cfa -16 + ^")?;
+        assert!(!delta.is_init());
+        rules.update(&delta);
+
+        assert_eq!(rules.address, 0x1880);
+        assert_eq!(rules.cfa_rule.as_ref().unwrap().to_string(), "$rsp 16 +");
+        assert_eq!(rules.register_rules.len(), 2);
+        assert_eq!(
+            rules.register_rule("$rbp").unwrap().to_string(),
+            ".cfa -16 + ^"
+        );
+
+        assert!(BreakpadStackCfiRules::parse("1881 .cfa:").is_err());
+        assert!(BreakpadStackCfiRules::parse("1881 $rsp 8 +").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_win_frame_info() -> Result<(), BreakpadError> {
+        let info = BreakpadStackWinFrameInfo::parse(
+            "4 371a c 0 0 0 0 0 0 1 $T0 .raSearch = $eip $T0 ^ = $esp $T0 4 + =",
+        )?;
+
+        assert_eq!(info.ty, BreakpadStackWinRecordType::FrameData);
+        assert_eq!(info.address, 0x371a);
+        assert_eq!(info.code_size, 0xc);
+        let program = info.program.unwrap();
+        assert_eq!(program.len(), 3);
+        assert_eq!(program[1].variable, "$eip");
+        assert_eq!(program[1].expression.to_string(), "$T0 ^");
+
+        let info = BreakpadStackWinFrameInfo::parse("0 1000 20 3 0 8 4 10 0 0 1")?;
+        assert_eq!(info.ty, BreakpadStackWinRecordType::Fpo);
+        assert_eq!(info.parameter_size, 8);
+        assert_eq!(info.local_size, 0x10);
+        assert!(info.program.is_none());
+        assert!(info.allocates_base_pointer);
+
+        let info = BreakpadStackWinFrameInfo::parse(
+            "4 2910 15e 36 0 8 4 f4 0 1 $T0 $ebp 204 + =$eip $T0 4 + ^ =",
+        )?;
+        let program = info.program.unwrap();
+        assert_eq!(program.len(), 2);
+        assert_eq!(program[1].variable, "$eip");
+
+        assert!(BreakpadStackWinFrameInfo::parse("4 371a c 0 0 0 0 0 0 1 $eip $T0 ^").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unwind_cfi() -> Result<(), Box<dyn Error>> {
+        let mut data = vec![0; 16];
+        data[..8].copy_from_slice(&0x7100u64.to_le_bytes());
+        data[8..].copy_from_slice(&0x1234u64.to_le_bytes());
+        let evaluator = BreakpadStackEvaluator::new(Arch::Amd64, Stack(0x7000, data));
+
+        let mut rules =
+            BreakpadStackCfiRules::parse("INIT 1000 40 .cfa: $rsp 8 + .ra: .cfa -8 + ^")?;
+        rules.update(&BreakpadStackCfiRules::parse(
+            "1001 .cfa: $rsp 16 + $rbp: .cfa -16 + ^",
+        )?);
+
+        let caller = evaluator.unwind_cfi(&rules, &registers(&[("$rsp", 0x7000)]))?;
+        assert_eq!(
+            caller,
+            registers(&[(".cfa", 0x7010), (".ra", 0x1234), ("$rbp", 0x7100)])
+        );
+
+        let error = evaluator
+            .unwind_cfi(&rules, &registers(&[("$rbp", 0x7000)]))
+            .unwrap_err();
+        assert_eq!(error.kind(), BreakpadEvalErrorKind::UndefinedVariable);
+
+        let error = evaluator
+            .unwind_cfi(&rules, &registers(&[("$rsp", 0x8000)]))
+            .unwrap_err();
+        assert_eq!(error.kind(), BreakpadEvalErrorKind::MemoryReadFailed);
+
+        let rules = BreakpadStackCfiRules::parse("INIT 1000 40 .cfa: $rsp 8 +")?;
+        let error = evaluator
+            .unwind_cfi(&rules, &registers(&[("$rsp", 0x7000)]))
+            .unwrap_err();
+        assert_eq!(error.kind(), BreakpadEvalErrorKind::MissingRule);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unwind_win() -> Result<(), Box<dyn Error>> {
+        let mut data = vec![0; 16];
+        data[..4].copy_from_slice(&0x1234u32.to_le_bytes());
+        data[4..8].copy_from_slice(&0x2000u32.to_le_bytes());
+        let evaluator = BreakpadStackEvaluator::new(Arch::X86, Stack(0x7000, data));
+
+        let callee = registers(&[("$eip", 0x1000), ("$esp", 0x6ff8), ("$ebp", 0x6ffc)]);
+
+        let info = BreakpadStackWinFrameInfo::parse(
+            "4 1000 20 0 0 0 0 8 0 1 $T0 .raSearch = $eip $T0 ^ = $esp $T0 4 + =",
+        )?;
+        let caller = evaluator.unwind_win(&info, &callee)?;
+        assert_eq!(
+            caller,
+            registers(&[("$eip", 0x1234), ("$esp", 0x7004), ("$ebp", 0x6ffc)])
+        );
+
+        let info = BreakpadStackWinFrameInfo::parse(
+            "4 1000 20 0 0 0 0 0 0 1 $T0 $ebp = $eip $T0 4 + ^ = $ebp $T0 ^ = $esp $T0 8 + =",
+        )?;
+        let callee = registers(&[("$eip", 0x1000), ("$esp", 0x6ff0), ("$ebp", 0x7000)]);
+        let caller = evaluator.unwind_win(&info, &callee)?;
+        assert_eq!(
+            caller,
+            registers(&[("$eip", 0x2000), ("$esp", 0x7008), ("$ebp", 0x1234)])
+        );
+
+        let info = BreakpadStackWinFrameInfo::parse("0 1000 20 3 0 8 4 4 0 0 1")?;
+        let callee = registers(&[("$eip", 0x1000), ("$esp", 0x6ff8), ("$ebp", 0x6ffc)]);
+        let caller = evaluator.unwind_win(&info, &callee)?;
+        assert_eq!(caller, registers(&[("$eip", 0x1234), ("$esp", 0x7004)]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_evaluate_wrapping() -> Result<(), Box<dyn Error>> {
+        let evaluator = BreakpadStackEvaluator::new(Arch::X86, Stack(0, vec![]));
+        let variables = registers(&[("$esp", 4)]);
+
+        let expression = BreakpadPostfixExpression::parse("$esp -8 +")?;
+        assert_eq!(evaluator.evaluate(&expression, &variables)?, 0xffff_fffc);
+
+        let expression = BreakpadPostfixExpression::parse("$esp 27 + 16 @")?;
+        assert_eq!(evaluator.evaluate(&expression, &variables)?, 16);
+
+        let expression = BreakpadPostfixExpression::parse("$esp 0 /")?;
+        let error = evaluator.evaluate(&expression, &variables).unwrap_err();
+        assert_eq!(error.kind(), BreakpadEvalErrorKind::DivisionByZero);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,422 @@
+//! Validation of Breakpad symbol files.
+//!
+//! [`BreakpadObject`] parses records lazily, so that issues in a symbol file only surface when the
+//! affected records are read. [`BreakpadObject::validate`] instead scans the entire file once and
+//! reports every issue along with its line number.
+//!
+//! [`BreakpadObject`]: ../struct.BreakpadObject.html
+//! [`BreakpadObject::validate`]: ../struct.BreakpadObject.html#method.validate
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use crate::private::Lines;
+
+use super::*;
+
+/// The kind of a [`BreakpadIssue`](struct.BreakpadIssue.html).
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BreakpadIssueKind {
+    /// A record cannot be parsed or is not allowed at its position.
+    InvalidRecord,
+
+    /// A line or inline record refers to a `FILE` record that does not exist.
+    MissingFile,
+
+    /// An inline record refers to an `INLINE_ORIGIN` record that does not exist.
+    MissingInlineOrigin,
+
+    /// The address range of a `FUNC` record overlaps with a preceding function.
+    OverlappingFunction,
+
+    /// A line or inline record is not covered by the address range of its `FUNC` record.
+    LineOutsideFunction,
+
+    /// A `PUBLIC` record has the same address as a preceding `PUBLIC` record.
+    DuplicatePublic,
+
+    /// The rules of a `STACK CFI` or `STACK WIN` record cannot be parsed.
+    InvalidStackRule,
+}
+
+impl fmt::Display for BreakpadIssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidRecord => write!(f, "invalid record"),
+            Self::MissingFile => write!(f, "missing file"),
+            Self::MissingInlineOrigin => write!(f, "missing inline origin"),
+            Self::OverlappingFunction => write!(f, "overlapping function"),
+            Self::LineOutsideFunction => write!(f, "line outside of function"),
+            Self::DuplicatePublic => write!(f, "duplicate public symbol"),
+            Self::InvalidStackRule => write!(f, "invalid stack rule"),
+        }
+    }
+}
+
+/// An issue found while validating a Breakpad symbol file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BreakpadIssue {
+    /// The line number of the offending record, starting at `1`.
+    pub line: usize,
+    /// The kind of this issue.
+    pub kind: BreakpadIssueKind,
+    /// A description of this issue.
+    pub message: String,
+}
+
+impl fmt::Display for BreakpadIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}: {}", self.line, self.kind, self.message)
+    }
+}
+
+/// The record that line and inline records are currently attributed to.
+#[derive(Clone, Copy, Debug)]
+enum Scope {
+    /// Line records are not allowed.
+    None,
+    /// Line records belong to a function with the given address range.
+    Function { address: u64, end: u64 },
+    /// Line records belong to a function that failed to parse and cannot be checked.
+    InvalidFunction,
+}
+
+/// A `FUNC` record for the detection of overlaps.
+#[derive(Clone, Copy, Debug)]
+struct FunctionRange {
+    address: u64,
+    end: u64,
+    line: usize,
+}
+
+/// Collects issues while scanning a Breakpad symbol file.
+#[derive(Debug, Default)]
+struct Validator {
+    issues: Vec<BreakpadIssue>,
+    files: BTreeSet<u64>,
+    origins: BTreeSet<u64>,
+    file_refs: Vec<(usize, u64)>,
+    origin_refs: Vec<(usize, u64)>,
+    functions: Vec<FunctionRange>,
+    publics: BTreeMap<u64, usize>,
+    has_cfi_init: bool,
+}
+
+impl Validator {
+    fn report(&mut self, line: usize, kind: BreakpadIssueKind, message: String) {
+        self.issues.push(BreakpadIssue {
+            line,
+            kind,
+            message,
+        });
+    }
+
+    fn report_invalid(&mut self, line: usize, record: &str, error: BreakpadError) {
+        let message = format!("invalid {} record: {}", record, error);
+        self.report(line, BreakpadIssueKind::InvalidRecord, message);
+    }
+
+    /// Validates a single line and returns the scope for subsequent lines.
+    fn validate_line(&mut self, number: usize, line: &[u8], scope: Scope) -> Scope {
+        if line.starts_with(b"MODULE ") {
+            if number > 1 {
+                let message = "MODULE record must be the first line".to_owned();
+                self.report(number, BreakpadIssueKind::InvalidRecord, message);
+            }
+        } else if line.starts_with(b"INFO ") {
+            if let Err(error) = BreakpadInfoRecord::parse(line) {
+                self.report_invalid(number, "INFO", error);
+            }
+        } else if line.starts_with(b"FILE ") {
+            match BreakpadFileRecord::parse(line) {
+                Ok(record) => {
+                    self.files.insert(record.id);
+                }
+                Err(error) => self.report_invalid(number, "FILE", error),
+            }
+        } else if line.starts_with(b"INLINE_ORIGIN ") {
+            match BreakpadInlineOriginRecord::parse(line) {
+                Ok(record) => {
+                    self.origins.insert(record.id);
+                }
+                Err(error) => self.report_invalid(number, "INLINE_ORIGIN", error),
+            }
+        } else if line.starts_with(b"FUNC ") {
+            return self.validate_func(number, line);
+        } else if line.starts_with(b"INLINE ") {
+            self.validate_inline(number, line, scope);
+            return scope;
+        } else if line.starts_with(b"PUBLIC ") {
+            self.validate_public(number, line);
+        } else if line.starts_with(b"STACK ") {
+            self.validate_stack(number, line);
+        } else if line.is_empty() {
+            // Blank lines do not end the records of a function.
+            return scope;
+        } else {
+            self.validate_line_record(number, line, scope);
+            return scope;
+        }
+
+        Scope::None
+    }
+
+    fn validate_func(&mut self, number: usize, line: &[u8]) -> Scope {
+        match BreakpadFuncRecord::parse(line, Lines::default()) {
+            Ok(record) => {
+                let end = record.address.saturating_add(record.size);
+                self.functions.push(FunctionRange {
+                    address: record.address,
+                    end,
+                    line: number,
+                });
+
+                Scope::Function {
+                    address: record.address,
+                    end,
+                }
+            }
+            Err(error) => {
+                self.report_invalid(number, "FUNC", error);
+                Scope::InvalidFunction
+            }
+        }
+    }
+
+    fn validate_inline(&mut self, number: usize, line: &[u8], scope: Scope) {
+        let record = match BreakpadInlineRecord::parse(line) {
+            Ok(record) => record,
+            Err(error) => return self.report_invalid(number, "INLINE", error),
+        };
+
+        self.file_refs.push((number, record.call_site_file_id));
+        self.origin_refs.push((number, record.origin_id));
+
+        match scope {
+            Scope::Function { address, end } => {
+                for range in &record.address_ranges {
+                    if range.address < address || range.end_address() > end {
+                        let message = format!(
+                            "inline range {:#x}..{:#x} is outside of function {:#x}..{:#x}",
+                            range.address,
+                            range.end_address(),
+                            address,
+                            end
+                        );
+                        self.report(number, BreakpadIssueKind::LineOutsideFunction, message);
+                    }
+                }
+            }
+            Scope::InvalidFunction => (),
+            Scope::None => {
+                let message = "INLINE record does not follow a FUNC record".to_owned();
+                self.report(number, BreakpadIssueKind::LineOutsideFunction, message);
+            }
+        }
+    }
+
+    fn validate_line_record(&mut self, number: usize, line: &[u8], scope: Scope) {
+        let record = match BreakpadLineRecord::parse(line) {
+            Ok(record) => record,
+            Err(_) if matches!(scope, Scope::None) => {
+                let message = "unknown record".to_owned();
+                return self.report(number, BreakpadIssueKind::InvalidRecord, message);
+            }
+            Err(error) => return self.report_invalid(number, "line", error),
+        };
+
+        self.file_refs.push((number, record.file_id));
+
+        match scope {
+            Scope::Function { address, end } => {
+                let record_end = record.address.saturating_add(record.size);
+                if record.address < address || record_end > end {
+                    let message = format!(
+                        "line {:#x}..{:#x} is outside of function {:#x}..{:#x}",
+                        record.address, record_end, address, end
+                    );
+                    self.report(number, BreakpadIssueKind::LineOutsideFunction, message);
+                }
+            }
+            Scope::InvalidFunction => (),
+            Scope::None => {
+                let message = "line record does not follow a FUNC record".to_owned();
+                self.report(number, BreakpadIssueKind::LineOutsideFunction, message);
+            }
+        }
+    }
+
+    fn validate_public(&mut self, number: usize, line: &[u8]) {
+        let record = match BreakpadPublicRecord::parse(line) {
+            Ok(record) => record,
+            Err(error) => return self.report_invalid(number, "PUBLIC", error),
+        };
+
+        if let Some(&previous) = self.publics.get(&record.address) {
+            let message = format!(
+                "PUBLIC record at {:#x} is already defined in line {}",
+                record.address, previous
+            );
+            self.report(number, BreakpadIssueKind::DuplicatePublic, message);
+        } else {
+            self.publics.insert(record.address, number);
+        }
+    }
+
+    fn validate_stack(&mut self, number: usize, line: &[u8]) {
+        let result = match BreakpadStackRecord::parse(line) {
+            Ok(BreakpadStackRecord::Cfi(record)) => record.rules().map(|rules| {
+                if rules.is_init() {
+                    self.has_cfi_init = true;
+                } else if !self.has_cfi_init {
+                    let message = "STACK CFI record does not follow a STACK CFI INIT record";
+                    self.report(
+                        number,
+                        BreakpadIssueKind::InvalidStackRule,
+                        message.to_owned(),
+                    );
+                }
+            }),
+            Ok(BreakpadStackRecord::Win(record)) => record.frame_info().map(|_| ()),
+            Err(error) => return self.report_invalid(number, "STACK", error),
+        };
+
+        if let Err(error) = result {
+            let message = format!("invalid unwind rules: {}", error);
+            self.report(number, BreakpadIssueKind::InvalidStackRule, message);
+        }
+    }
+
+    /// Checks references and overlaps after all records have been scanned.
+    fn finish(mut self) -> Vec<BreakpadIssue> {
+        for (number, file_id) in std::mem::take(&mut self.file_refs) {
+            if !self.files.contains(&file_id) {
+                let message = format!("FILE {} is not defined", file_id);
+                self.report(number, BreakpadIssueKind::MissingFile, message);
+            }
+        }
+
+        for (number, origin_id) in std::mem::take(&mut self.origin_refs) {
+            if !self.origins.contains(&origin_id) {
+                let message = format!("INLINE_ORIGIN {} is not defined", origin_id);
+                self.report(number, BreakpadIssueKind::MissingInlineOrigin, message);
+            }
+        }
+
+        let mut functions = std::mem::take(&mut self.functions);
+        functions.sort_by_key(|function| (function.address, function.line));
+
+        let mut previous: Option<FunctionRange> = None;
+        for function in functions {
+            match previous {
+                Some(prev) if function.address < prev.end => {
+                    let message = format!(
+                        "function {:#x}..{:#x} overlaps with function {:#x}..{:#x} in line {}",
+                        function.address, function.end, prev.address, prev.end, prev.line
+                    );
+                    self.report(
+                        function.line,
+                        BreakpadIssueKind::OverlappingFunction,
+                        message,
+                    );
+                    if function.end > prev.end {
+                        previous = Some(function);
+                    }
+                }
+                _ => previous = Some(function),
+            }
+        }
+
+        self.issues.sort_by_key(|issue| issue.line);
+        self.issues
+    }
+}
+
+impl<'data> BreakpadObject<'data> {
+    /// Scans the entire symbol file and returns all issues found, ordered by line number.
+    ///
+    /// Unlike the record iterators of this object, validation does not stop at the first error.
+    /// It reports records that cannot be parsed, references to undefined `FILE` and
+    /// `INLINE_ORIGIN` records, overlapping functions, line and inline records outside of their
+    /// function, duplicate `PUBLIC` records and unparseable `STACK` rules.
+    ///
+    /// An empty result means that the file is valid.
+    pub fn validate(&self) -> Vec<BreakpadIssue> {
+        let mut validator = Validator::default();
+        let mut scope = Scope::None;
+
+        for (index, line) in Lines::new(self.data).enumerate() {
+            scope = validator.validate_line(index + 1, line, scope);
+        }
+
+        validator.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate(data: &[u8]) -> Vec<String> {
+        let object = BreakpadObject::parse(data).unwrap();
+        object
+            .validate()
+            .iter()
+            .map(|issue| issue.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_valid() {
+        let data = b"MODULE Linux x86_64 0B3C7D5C2E8B4A7D9F1E2D3C4B5A69780 test\n\
+            INFO CODE_ID 5C7D3C0B8B2E7D4A9F1E2D3C4B5A6978\n\
+            FILE 0 /src/main.c\n\
+            INLINE_ORIGIN 0 inlined\n\
+            FUNC 1000 20 0 main\n\
+            INLINE 0 10 0 0 1008 8\n\
+            1000 10 9 0\n\
+            \n\
+            1010 10 11 0\n\
+            PUBLIC 2000 0 other\n\
+            STACK CFI INIT 1000 20 .cfa: $rsp 8 + .ra: .cfa -8 + ^\n\
+            STACK CFI 1001 .cfa: $rsp 16 +\n";
+
+        assert!(validate(data).is_empty());
+    }
+
+    #[test]
+    fn test_validate_issues() {
+        let data = b"MODULE Linux x86_64 0B3C7D5C2E8B4A7D9F1E2D3C4B5A69780 test\n\
+            FILE 0 /src/main.c\n\
+            FUNC 1000 20 0 main\n\
+            INLINE 0 10 0 3 1008 30\n\
+            1000 10 9 0\n\
+            1010 20 11 1\n\
+            FUNC 1010 10 0 overlap\n\
+            FUNC zzzz 10 0 broken\n\
+            1040 4 1 0\n\
+            PUBLIC 2000 0 first\n\
+            PUBLIC 2000 0 second\n\
+            3000 4 1 0\n\
+            garbage\n\
+            STACK CFI 1001 .cfa: $rsp 16 +\n\
+            STACK CFI INIT 1000 20 .cfa: $rsp +\n";
+
+        insta::assert_debug_snapshot!(validate(data), @r###"
+        [
+            "line 4: line outside of function: inline range 0x1008..0x1038 is outside of function 0x1000..0x1020",
+            "line 4: missing inline origin: INLINE_ORIGIN 3 is not defined",
+            "line 6: line outside of function: line 0x1010..0x1030 is outside of function 0x1000..0x1020",
+            "line 6: missing file: FILE 1 is not defined",
+            "line 7: overlapping function: function 0x1010..0x1020 overlaps with function 0x1000..0x1020 in line 3",
+            "line 8: invalid record: invalid FUNC record: invalid syntax",
+            "line 11: duplicate public symbol: PUBLIC record at 0x2000 is already defined in line 10",
+            "line 12: line outside of function: line record does not follow a FUNC record",
+            "line 13: invalid record: unknown record",
+            "line 14: invalid stack rule: STACK CFI record does not follow a STACK CFI INIT record",
+            "line 15: invalid stack rule: invalid unwind rules: postfix expression",
+        ]
+        "###);
+    }
+}
@@ -8,13 +8,16 @@
 //! [`MachObject`]: ../macho/struct.MachObject.html
 
 use std::borrow::Cow;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::fmt;
 use std::marker::PhantomData;
 use std::ops::{Deref, RangeBounds};
+use std::sync::Arc;
 
 use fallible_iterator::FallibleIterator;
-use gimli::read::{AttributeValue, Error as GimliError, Range};
+use gimli::read::{AttributeValue, Error as GimliError, Range, Reader, ReaderOffset, Section};
 use gimli::{constants, DwarfFileType, UnitSectionOffset};
 use lazycell::LazyCell;
 use thiserror::Error;
@@ -22,6 +25,7 @@ use thiserror::Error;
 use symbolic_common::{AsSelf, Language, Name, NameMangling, SelfCell};
 
 use crate::base::*;
+use crate::macho::BcSymbolMap;
 use crate::private::FunctionStack;
 
 #[doc(hidden)]
@@ -30,6 +34,8 @@ pub use gimli::RunTimeEndian as Endian;
 
 type Slice<'a> = gimli::read::EndianSlice<'a, Endian>;
 type RangeLists<'a> = gimli::read::RangeLists<Slice<'a>>;
+type LocationLists<'a> = gimli::read::LocationLists<Slice<'a>>;
+type RngListIter<'a> = gimli::read::RngListIter<Slice<'a>>;
 type Unit<'a> = gimli::read::Unit<Slice<'a>>;
 type DwarfInner<'a> = gimli::read::Dwarf<Slice<'a>>;
 
@@ -37,12 +43,20 @@ type Die<'d, 'u> = gimli::read::DebuggingInformationEntry<'u, 'u, Slice<'d>, usi
 type Attribute<'a> = gimli::read::Attribute<Slice<'a>>;
 type UnitOffset = gimli::read::UnitOffset<usize>;
 type DebugInfoOffset = gimli::DebugInfoOffset<usize>;
+type VariableInfo<'a> = (Option<Cow<'a, str>>, Option<String>);
+type DebugAddrIndex = gimli::DebugAddrIndex<usize>;
 
 type UnitHeader<'a> = gimli::read::UnitHeader<Slice<'a>>;
 type IncompleteLineNumberProgram<'a> = gimli::read::IncompleteLineProgram<Slice<'a>>;
 type LineNumberProgramHeader<'a> = gimli::read::LineProgramHeader<Slice<'a>>;
 type LineProgramFileEntry<'a> = gimli::read::FileEntry<Slice<'a>>;
 
+/// Maximum number of nested type references followed when formatting type names.
+const MAX_TYPE_DEPTH: usize = 16;
+
+/// Content type of embedded source code in DWARF 5 line tables, emitted by `clang -gembed-source`.
+const DW_LNCT_LLVM_SOURCE: constants::DwLnct = constants::DwLnct(0x2001);
+
 /// This applies the offset to the address.
 ///
 /// This function does not panic but would wrap around if too large or small
@@ -196,6 +210,26 @@ pub trait Dwarf<'data> {
     fn has_section(&self, name: &str) -> bool {
         self.raw_section(name).is_some()
     }
+
+    /// Returns split DWARF objects that complement the skeleton units of this object.
+    ///
+    /// When compiling with `-gsplit-dwarf`, compilation units in the main object are reduced to
+    /// skeletons that only reference their split units via a DWO identifier. The actual debugging
+    /// information entries live in `.dwo` files or a packaged `.dwp` file, whose sections carry a
+    /// `.dwo` suffix. By default, no split objects are attached.
+    fn split_dwarf(&self) -> Vec<&dyn Dwarf<'data>> {
+        Vec::new()
+    }
+
+    /// Returns the supplementary object file that contains DWARF shared by this object.
+    ///
+    /// Tools like `dwz` move debugging information entries and strings that are common to multiple
+    /// objects into a supplementary file, which is referenced through `.gnu_debugaltlink`. Entries
+    /// in this object then refer to it via `DW_FORM_GNU_ref_alt` and `DW_FORM_GNU_strp_alt`. By
+    /// default, no supplementary object is attached.
+    fn supplementary_dwarf(&self) -> Option<&dyn Dwarf<'data>> {
+        None
+    }
 }
 
 /// A row in the DWARF line program.
@@ -204,6 +238,7 @@ struct DwarfRow {
     address: u64,
     file_index: u64,
     line: Option<u64>,
+    column: Option<u64>,
     size: Option<u64>,
 }
 
@@ -272,11 +307,16 @@ impl<'d, 'a> DwarfLineProgram<'d> {
             } else {
                 let file_index = program_row.file_index();
                 let line = program_row.line();
+                let column = match program_row.column() {
+                    gimli::read::ColumnType::LeftEdge => None,
+                    gimli::read::ColumnType::Column(column) => Some(column),
+                };
                 let mut duplicate = false;
                 if let Some(last_row) = sequence_rows.last_mut() {
                     if last_row.address == address {
                         last_row.file_index = file_index;
                         last_row.line = line;
+                        last_row.column = column;
                         duplicate = true;
                     }
                 }
@@ -285,6 +325,7 @@ impl<'d, 'a> DwarfLineProgram<'d> {
                         address,
                         file_index,
                         line,
+                        column,
                         size: None,
                     });
                 }
@@ -338,19 +379,47 @@ impl<'d, 'a> DwarfLineProgram<'d> {
 #[derive(Clone, Copy, Debug)]
 struct UnitRef<'d, 'a> {
     info: &'a DwarfInfo<'d>,
+    dwarf: &'a DwarfInner<'d>,
     unit: &'a Unit<'d>,
+    split: bool,
+    type_scopes: &'a LazyCell<HashMap<UnitOffset, String>>,
 }
 
 impl<'d, 'a> UnitRef<'d, 'a> {
     /// Resolve the binary value of an attribute.
     #[inline(always)]
     fn slice_value(&self, value: AttributeValue<Slice<'d>>) -> Option<&'d [u8]> {
-        self.info
+        self.dwarf
             .attr_string(self.unit, value)
             .map(|reader| reader.slice())
             .ok()
     }
 
+    /// Resolves the address at the given index into `.debug_addr`.
+    fn address(&self, index: DebugAddrIndex) -> Result<u64, DwarfError> {
+        Ok(self.dwarf.address(self.unit, index)?)
+    }
+
+    /// Resolves a range list attribute into an iterator over ranges.
+    fn attr_ranges(
+        &self,
+        value: AttributeValue<Slice<'d>>,
+    ) -> Result<Option<RngListIter<'d>>, DwarfError> {
+        let value = match value {
+            // The GNU split DWARF extension to DWARF 4 stores range list offsets relative to the
+            // `DW_AT_GNU_ranges_base` of the skeleton unit, which gimli does not apply by itself.
+            AttributeValue::RangeListsRef(offset)
+                if self.split && self.unit.header.version() < 5 =>
+            {
+                let base = self.unit.rnglists_base.0;
+                AttributeValue::RangeListsRef(gimli::RangeListsOffset(offset.0 + base))
+            }
+            value => value,
+        };
+
+        Ok(self.dwarf.attr_ranges(self.unit, value)?)
+    }
+
     /// Resolve the actual string value of an attribute.
     #[inline(always)]
     fn string_value(&self, value: AttributeValue<Slice<'d>>) -> Option<Cow<'d, str>> {
@@ -368,7 +437,19 @@ impl<'d, 'a> UnitRef<'d, 'a> {
     {
         let (unit, offset) = match attr.value() {
             AttributeValue::UnitRef(offset) => (*self, offset),
+            AttributeValue::DebugInfoRef(offset) if self.split => {
+                // Split units live in a separate file, so references can only be resolved within
+                // the same unit.
+                match UnitSectionOffset::DebugInfoOffset(offset).to_unit_offset(self.unit) {
+                    Some(unit_offset) => (*self, unit_offset),
+                    None => return Ok(None),
+                }
+            }
             AttributeValue::DebugInfoRef(offset) => self.info.find_unit_offset(offset)?,
+            AttributeValue::DebugInfoRefSup(offset) => match self.info.sup {
+                Some(ref sup) => sup.find_unit_offset(offset)?,
+                None => return Ok(None),
+            },
             // TODO: There is probably more that can come back here.
             _ => return Ok(None),
         };
@@ -424,7 +505,10 @@ impl<'d, 'a> UnitRef<'d, 'a> {
 
         if let Some(attr) = reference_target {
             return self.resolve_reference(attr, |ref_unit, ref_entry| {
-                if self.offset() != ref_unit.offset() || entry.offset() != ref_entry.offset() {
+                if !std::ptr::eq(self.dwarf, ref_unit.dwarf)
+                    || self.offset() != ref_unit.offset()
+                    || entry.offset() != ref_entry.offset()
+                {
                     ref_unit.resolve_function_name(ref_entry, language)
                 } else {
                     Ok(None)
@@ -434,6 +518,242 @@ impl<'d, 'a> UnitRef<'d, 'a> {
 
         Ok(None)
     }
+
+    /// Resolves the name and type name of a variable, following abstract origins.
+    fn resolve_variable_info(&self, entry: &Die<'d, '_>) -> Result<VariableInfo<'d>, DwarfError> {
+        let mut attrs = entry.attrs();
+        let mut name = None;
+        let mut type_name = None;
+        let mut reference_target = None;
+
+        while let Some(attr) = attrs.next()? {
+            match attr.name() {
+                constants::DW_AT_name => name = self.string_value(attr.value()),
+                constants::DW_AT_type => type_name = self.resolve_type_name(attr, 0)?,
+                constants::DW_AT_abstract_origin | constants::DW_AT_specification => {
+                    reference_target = Some(attr);
+                }
+                _ => {}
+            }
+        }
+
+        // Concrete instances of inlined or out-of-line functions only declare the location of
+        // their variables and refer to the abstract instance for everything else.
+        if let (Some(attr), true) = (reference_target, name.is_none() || type_name.is_none()) {
+            let origin = self.resolve_reference(attr, |ref_unit, ref_entry| {
+                if !std::ptr::eq(self.dwarf, ref_unit.dwarf)
+                    || self.offset() != ref_unit.offset()
+                    || entry.offset() != ref_entry.offset()
+                {
+                    ref_unit.resolve_variable_info(ref_entry).map(Some)
+                } else {
+                    Ok(None)
+                }
+            })?;
+
+            if let Some((origin_name, origin_type)) = origin {
+                name = name.or(origin_name);
+                type_name = type_name.or(origin_type);
+            }
+        }
+
+        Ok((name, type_name))
+    }
+
+    /// Formats the name of the type referenced by a `DW_AT_type` attribute.
+    ///
+    /// Nested type modifiers are followed up to a fixed depth to guard against cycles.
+    fn resolve_type_name(
+        &self,
+        attr: Attribute<'d>,
+        depth: usize,
+    ) -> Result<Option<String>, DwarfError> {
+        if depth >= MAX_TYPE_DEPTH {
+            return Ok(None);
+        }
+
+        self.resolve_reference(attr, |ref_unit, ref_entry| {
+            ref_unit.format_type(ref_entry, depth + 1)
+        })
+    }
+
+    /// Formats the name of a type entry in C-like syntax.
+    fn format_type(&self, entry: &Die<'d, '_>, depth: usize) -> Result<Option<String>, DwarfError> {
+        let inner = match entry.attr(constants::DW_AT_type)? {
+            Some(attr) => self.resolve_type_name(attr, depth)?,
+            None => None,
+        };
+
+        // Modifiers of missing types apply to `void`, such as in `void *`.
+        let inner_or_void = inner.as_deref().unwrap_or("void");
+
+        let formatted = match entry.tag() {
+            constants::DW_TAG_pointer_type => format!("{}*", inner_or_void),
+            constants::DW_TAG_reference_type => format!("{}&", inner_or_void),
+            constants::DW_TAG_rvalue_reference_type => format!("{}&&", inner_or_void),
+            constants::DW_TAG_const_type | constants::DW_TAG_volatile_type => {
+                let modifier = if entry.tag() == constants::DW_TAG_const_type {
+                    "const"
+                } else {
+                    "volatile"
+                };
+
+                // Qualifiers of pointers and references follow the declarator.
+                if inner_or_void.ends_with(&['*', '&'][..]) {
+                    format!("{} {}", inner_or_void, modifier)
+                } else {
+                    format!("{} {}", modifier, inner_or_void)
+                }
+            }
+            constants::DW_TAG_array_type => {
+                format!("{}{}", inner_or_void, self.array_dimensions(entry)?)
+            }
+            constants::DW_TAG_subroutine_type => format!("{} ()", inner_or_void),
+            constants::DW_TAG_restrict_type | constants::DW_TAG_atomic_type => return Ok(inner),
+            _ => {
+                let name = match entry
+                    .attr_value(constants::DW_AT_name)?
+                    .and_then(|value| self.string_value(value))
+                {
+                    Some(name) => name,
+                    None => return Ok(None),
+                };
+
+                return Ok(Some(match self.type_scopes()?.get(&entry.offset()) {
+                    Some(scope) => qualify_name(scope, &name),
+                    None => name.into_owned(),
+                }));
+            }
+        };
+
+        Ok(Some(formatted))
+    }
+
+    /// Returns the enclosing namespaces and types of all nested type entries in this unit.
+    ///
+    /// Type entries declared at the top level are not contained in the map.
+    fn type_scopes(&self) -> Result<&'a HashMap<UnitOffset, String>, DwarfError> {
+        self.type_scopes
+            .try_borrow_with(|| self.collect_type_scopes())
+    }
+
+    /// Walks all entries of this unit to compute the scopes of nested type entries.
+    fn collect_type_scopes(&self) -> Result<HashMap<UnitOffset, String>, DwarfError> {
+        let mut type_scopes = HashMap::new();
+        let mut depth = 0;
+
+        // Stack of enclosing namespaces and types with their depth and qualified name.
+        let mut scopes = Vec::<(isize, String)>::new();
+
+        let mut entries = self.unit.entries();
+        while let Some((movement, entry)) = entries.next_dfs()? {
+            depth += movement;
+
+            while scopes.last().map_or(false, |&(d, _)| d >= depth) {
+                scopes.pop();
+            }
+
+            let is_type = match entry.tag() {
+                constants::DW_TAG_structure_type
+                | constants::DW_TAG_class_type
+                | constants::DW_TAG_union_type
+                | constants::DW_TAG_enumeration_type
+                | constants::DW_TAG_typedef => true,
+                constants::DW_TAG_namespace => false,
+                _ => continue,
+            };
+
+            let scope = scopes.last().map_or("", |(_, scope)| scope.as_str());
+            if is_type && !scope.is_empty() {
+                type_scopes.insert(entry.offset(), scope.to_owned());
+            }
+
+            // Typedefs do not declare any nested entries.
+            if entry.tag() == constants::DW_TAG_typedef {
+                continue;
+            }
+
+            let name = entry
+                .attr_value(constants::DW_AT_name)?
+                .and_then(|value| self.string_value(value));
+
+            let name = match name {
+                Some(name) => name,
+                None if is_type => continue,
+                None => Cow::Borrowed("(anonymous namespace)"),
+            };
+
+            let qualified = qualify_name(scope, &name);
+            scopes.push((depth, qualified));
+        }
+
+        Ok(type_scopes)
+    }
+
+    /// Formats the dimensions of an array type entry, such as `[2][4]`.
+    fn array_dimensions(&self, entry: &Die<'d, '_>) -> Result<String, DwarfError> {
+        let mut dimensions = String::new();
+
+        let mut tree = self.unit.entries_tree(Some(entry.offset()))?;
+        let mut children = tree.root()?.children();
+        while let Some(child) = children.next()? {
+            let child = child.entry();
+            if child.tag() != constants::DW_TAG_subrange_type {
+                continue;
+            }
+
+            let count = match child.attr_value(constants::DW_AT_count)? {
+                Some(value) => value.udata_value(),
+                None => child
+                    .attr_value(constants::DW_AT_upper_bound)?
+                    .and_then(|value| value.udata_value())
+                    .map(|upper_bound| upper_bound + 1),
+            };
+
+            match count {
+                Some(count) => dimensions.push_str(&format!("[{}]", count)),
+                None => dimensions.push_str("[]"),
+            }
+        }
+
+        if dimensions.is_empty() {
+            dimensions.push_str("[]");
+        }
+
+        Ok(dimensions)
+    }
+
+    /// Resolves the location of a variable from its `DW_AT_location` attribute.
+    ///
+    /// Location lists that cannot be parsed are reported as unknown locations.
+    fn resolve_location(&self, value: AttributeValue<Slice<'d>>) -> VariableLocation<'d> {
+        let mut list = match value {
+            AttributeValue::Exprloc(expression) => {
+                return VariableLocation::Expression(expression.0.slice())
+            }
+            AttributeValue::Block(block) => return VariableLocation::Expression(block.slice()),
+            value => match self.dwarf.attr_locations(self.unit, value) {
+                Ok(Some(list)) => list,
+                _ => return VariableLocation::Unknown,
+            },
+        };
+
+        let address_offset = self.info.address_offset;
+        let mut entries = Vec::new();
+        loop {
+            match list.next() {
+                Ok(Some(entry)) => entries.push((
+                    offset(entry.range.begin, address_offset)
+                        ..offset(entry.range.end, address_offset),
+                    entry.data.0.slice(),
+                )),
+                Ok(None) => break,
+                Err(_) => return VariableLocation::Unknown,
+            }
+        }
+
+        VariableLocation::List(entries)
+    }
 }
 
 /// Wrapper around a DWARF Unit.
@@ -447,7 +767,8 @@ struct DwarfUnit<'d, 'a> {
 
 impl<'d, 'a> DwarfUnit<'d, 'a> {
     /// Creates a DWARF unit from the gimli `Unit` type.
-    fn from_unit(unit: &'a Unit<'d>, info: &'a DwarfInfo<'d>) -> Result<Option<Self>, DwarfError> {
+    fn from_unit(inner: UnitRef<'d, 'a>) -> Result<Option<Self>, DwarfError> {
+        let unit = inner.unit;
         let mut entries = unit.entries();
         let entry = match entries.next_dfs()? {
             Some((_, entry)) => entry,
@@ -457,11 +778,9 @@ impl<'d, 'a> DwarfUnit<'d, 'a> {
         // Clang's LLD might eliminate an entire compilation unit and simply set the low_pc to zero
         // and remove all range entries to indicate that it is missing. Skip such a unit, as it does
         // not contain any code that can be executed. Special case relocatable objects, as here the
-        // range information has not been written yet and all units look like this.
-        if info.kind != ObjectKind::Relocatable
-            && unit.low_pc == 0
-            && entry.attr(constants::DW_AT_ranges)?.is_none()
-        {
+        // range information has not been written yet and all units look like this. Split units
+        // carry their ranges in the skeleton, which has been checked when loading the unit.
+        if !inner.split && is_eliminated_unit(unit, entry, inner.info.kind)? {
             return Ok(None);
         }
 
@@ -485,7 +804,7 @@ impl<'d, 'a> DwarfUnit<'d, 'a> {
         let prefer_dwarf_names = producer.as_deref() == Some(b"Dart VM");
 
         Ok(Some(DwarfUnit {
-            inner: UnitRef { info, unit },
+            inner,
             language,
             line_program,
             prefer_dwarf_names,
@@ -505,8 +824,8 @@ impl<'d, 'a> DwarfUnit<'d, 'a> {
         &self,
         entry: &Die<'d, '_>,
         range_buf: &mut Vec<Range>,
-    ) -> Result<(Option<u64>, Option<u64>), DwarfError> {
-        let mut tuple = (None, None);
+    ) -> Result<(Option<u64>, Option<u64>, Option<u64>), DwarfError> {
+        let mut tuple = (None, None, None);
         let mut low_pc = None;
         let mut high_pc = None;
         let mut high_pc_rel = None;
@@ -517,14 +836,14 @@ impl<'d, 'a> DwarfUnit<'d, 'a> {
                 constants::DW_AT_low_pc => match attr.value() {
                     AttributeValue::Addr(addr) => low_pc = Some(addr),
                     AttributeValue::DebugAddrIndex(index) => {
-                        low_pc = Some(self.inner.info.address(self.inner.unit, index)?)
+                        low_pc = Some(self.inner.address(index)?)
                     }
                     _ => return Err(GimliError::UnsupportedAttributeForm.into()),
                 },
                 constants::DW_AT_high_pc => match attr.value() {
                     AttributeValue::Addr(addr) => high_pc = Some(addr),
                     AttributeValue::DebugAddrIndex(index) => {
-                        low_pc = Some(self.inner.info.address(self.inner.unit, index)?)
+                        low_pc = Some(self.inner.address(index)?)
                     }
                     AttributeValue::Udata(size) => high_pc_rel = Some(size),
                     _ => return Err(GimliError::UnsupportedAttributeForm.into()),
@@ -537,18 +856,22 @@ impl<'d, 'a> DwarfUnit<'d, 'a> {
                     AttributeValue::FileIndex(file) => tuple.1 = Some(file),
                     _ => return Err(GimliError::UnsupportedAttributeForm.into()),
                 },
+                constants::DW_AT_call_column => match attr.value() {
+                    // A column of zero indicates that the column is unknown.
+                    AttributeValue::Udata(0) => (),
+                    AttributeValue::Udata(column) => tuple.2 = Some(column),
+                    _ => return Err(GimliError::UnsupportedAttributeForm.into()),
+                },
                 constants::DW_AT_ranges
                 | constants::DW_AT_rnglists_base
-                | constants::DW_AT_start_scope => {
-                    match self.inner.info.attr_ranges(self.inner.unit, attr.value())? {
-                        Some(mut ranges) => {
-                            while let Some(range) = ranges.next()? {
-                                range_buf.push(range);
-                            }
+                | constants::DW_AT_start_scope => match self.inner.attr_ranges(attr.value())? {
+                    Some(mut ranges) => {
+                        while let Some(range) = ranges.next()? {
+                            range_buf.push(range);
                         }
-                        None => continue,
                     }
-                }
+                    None => continue,
+                },
                 _ => continue,
             }
         }
@@ -564,7 +887,7 @@ impl<'d, 'a> DwarfUnit<'d, 'a> {
         // yet, so we want to retain them.
         let kind = self.inner.info.kind;
         let low_pc = match low_pc {
-            Some(low_pc) if low_pc != 0 || kind == ObjectKind::Relocatable => low_pc,
+            Some(low_pc) if low_pc != 0 || is_relocatable(kind) => low_pc,
             _ => return Ok(tuple),
         };
 
@@ -627,13 +950,18 @@ impl<'d, 'a> DwarfUnit<'d, 'a> {
                     size: first.size.map(|s| s + first.address - range.begin),
                     file: self.resolve_file(first.file_index).unwrap_or_default(),
                     line: first.line.unwrap_or(0),
+                    column: first.column,
                 };
 
                 for row in rows {
                     let line = row.line.unwrap_or(0);
 
-                    // We're in a range so we can collapse the lines without any side effects
-                    if (last_file, last_info.line) == (row.file_index, line) {
+                    // We're in a range so we can collapse the lines without any side effects. Rows
+                    // are only collapsed if they share the same column, so that expression-level
+                    // locations within a line are retained.
+                    if (last_file, last_info.line, last_info.column)
+                        == (row.file_index, line, row.column)
+                    {
                         // We collapse the lines but need to fix the last line size
                         if let Some(size) = last_info.size.as_mut() {
                             *size += row.size.unwrap_or(0);
@@ -651,6 +979,7 @@ impl<'d, 'a> DwarfUnit<'d, 'a> {
                         size: row.size,
                         file: self.resolve_file(row.file_index).unwrap_or_default(),
                         line,
+                        column: row.column,
                     };
                 }
 
@@ -693,6 +1022,38 @@ impl<'d, 'a> DwarfUnit<'d, 'a> {
             .map(|file| self.file_info(line_program, file))
     }
 
+    /// Collects source contents embedded into the line program, keyed by absolute file path.
+    fn collect_sources(
+        &self,
+        sources: &mut HashMap<String, Cow<'d, str>>,
+    ) -> Result<(), DwarfError> {
+        let line_program = match self.line_program {
+            Some(ref program) => &program.header,
+            None => return Ok(()),
+        };
+
+        let debug_line = *self.inner.dwarf.debug_line.reader();
+        let values = read_embedded_sources(debug_line, line_program)?;
+
+        for (file, value) in line_program.file_names().iter().zip(values) {
+            // Clang emits empty strings for files without embedded source.
+            let source = match value.and_then(|v| self.inner.string_value(v)) {
+                Some(source) if !source.is_empty() => source,
+                _ => continue,
+            };
+
+            let entry = FileEntry {
+                compilation_dir: self.compilation_dir(),
+                info: self.file_info(line_program, file),
+                checksum: None,
+            };
+
+            sources.insert(entry.abs_path_str(), source);
+        }
+
+        Ok(())
+    }
+
     /// Resolves the name of a function from the symbol table.
     fn resolve_symbol_name<R>(&self, range: R) -> Option<Name<'d>>
     where
@@ -711,6 +1072,295 @@ impl<'d, 'a> DwarfUnit<'d, 'a> {
             .flatten()
     }
 
+    /// Collects parameters and local variables of all functions within this compilation unit.
+    fn variables(&self, range_buf: &mut Vec<Range>) -> Result<Vec<Variable<'d>>, DwarfError> {
+        let address_offset = self.inner.info.address_offset;
+        let mut depth = 0;
+        let mut skipped_depth = None;
+        let mut variables = Vec::new();
+
+        // Stack of scopes with their depth, the address of the declaring function and the ranges
+        // of the scope itself.
+        let mut scopes = Vec::<(isize, u64, Vec<std::ops::Range<u64>>)>::new();
+
+        let mut entries = self.inner.unit.entries();
+        while let Some((movement, entry)) = entries.next_dfs()? {
+            depth += movement;
+
+            match skipped_depth {
+                Some(skipped) if depth > skipped => continue,
+                _ => skipped_depth = None,
+            }
+
+            while scopes.last().map_or(false, |&(d, _, _)| d >= depth) {
+                scopes.pop();
+            }
+
+            match entry.tag() {
+                constants::DW_TAG_subprogram
+                | constants::DW_TAG_inlined_subroutine
+                | constants::DW_TAG_lexical_block => {
+                    range_buf.clear();
+                    self.parse_ranges(entry, range_buf)?;
+
+                    let ranges = range_buf
+                        .iter()
+                        .map(|r| offset(r.begin, address_offset)..offset(r.end, address_offset))
+                        .collect::<Vec<_>>();
+
+                    if entry.tag() == constants::DW_TAG_lexical_block {
+                        // Blocks without ranges still scope their variables to the parent.
+                        let (function_address, parent_ranges) = match scopes.last() {
+                            Some((_, address, parent_ranges)) => (*address, parent_ranges),
+                            None => continue,
+                        };
+
+                        let ranges = if ranges.is_empty() {
+                            parent_ranges.clone()
+                        } else {
+                            ranges
+                        };
+
+                        scopes.push((depth, function_address, ranges));
+                    } else if let Some(first) = ranges.first() {
+                        scopes.push((depth, first.start, ranges));
+                    } else {
+                        // Declarations and abstract instances of functions do not have any code.
+                        // Their variables are resolved through the concrete instances.
+                        skipped_depth = Some(depth);
+                    }
+                }
+                constants::DW_TAG_formal_parameter | constants::DW_TAG_variable => {
+                    // Only consider variables declared directly within a function or block.
+                    let (function_address, scope) = match scopes.last() {
+                        Some((d, address, ranges)) if *d == depth - 1 => (*address, ranges),
+                        _ => continue,
+                    };
+
+                    let kind = if entry.tag() == constants::DW_TAG_formal_parameter {
+                        VariableKind::Parameter
+                    } else {
+                        VariableKind::Local
+                    };
+
+                    let (name, type_name) = self.inner.resolve_variable_info(entry)?;
+                    let location = match entry.attr_value(constants::DW_AT_location)? {
+                        Some(value) => self.inner.resolve_location(value),
+                        None => VariableLocation::Unknown,
+                    };
+
+                    variables.push(Variable {
+                        name: name.unwrap_or_default(),
+                        kind,
+                        type_name: type_name.map(Cow::Owned),
+                        function_address,
+                        scope: scope.clone(),
+                        location,
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        Ok(variables)
+    }
+
+    /// Collects all user-defined types declared within this compilation unit.
+    ///
+    /// Only namespaces and type definitions are descended into. Their members, base classes and
+    /// enumerators are collected into the containing type.
+    fn types(&self) -> Result<Vec<TypeInfo<'d>>, DwarfError> {
+        let mut types = Vec::<TypeInfo<'d>>::new();
+        let mut entries = self.inner.unit.entries();
+
+        // Skip the root entry of the unit, which is the top-level scope.
+        if entries.next_dfs()?.is_none() {
+            return Ok(types);
+        }
+
+        // Stack of entered namespaces and types with their depth, qualified name and the index of
+        // the type in `types`, if any.
+        let mut scopes = vec![(0, String::new(), None)];
+        let mut depth = 0;
+
+        while let Some((movement, entry)) = entries.next_dfs()? {
+            depth += movement;
+
+            while scopes.last().map_or(false, |&(d, _, _)| d >= depth) {
+                scopes.pop();
+            }
+
+            // Skip entries nested in anything other than a namespace or type definition.
+            let (scope, parent) = match scopes.last() {
+                Some(&(d, ref scope, parent)) if d == depth - 1 => (scope.as_str(), parent),
+                _ => continue,
+            };
+
+            let name = entry
+                .attr_value(constants::DW_AT_name)?
+                .and_then(|value| self.inner.string_value(value));
+
+            let kind = match entry.tag() {
+                constants::DW_TAG_structure_type => TypeKind::Struct,
+                constants::DW_TAG_class_type => TypeKind::Class,
+                constants::DW_TAG_union_type => TypeKind::Union,
+                constants::DW_TAG_enumeration_type => TypeKind::Enum,
+                constants::DW_TAG_typedef => TypeKind::Typedef,
+                constants::DW_TAG_namespace => {
+                    let name = name.as_deref().unwrap_or("(anonymous namespace)");
+                    let qualified = qualify_name(scope, name);
+                    scopes.push((depth, qualified, None));
+                    continue;
+                }
+                tag => {
+                    if let Some(parent) = parent {
+                        self.collect_type_child(tag, entry, &mut types[parent])?;
+                    }
+                    continue;
+                }
+            };
+
+            // Declarations only refer to the definition, which is listed separately.
+            if entry.attr(constants::DW_AT_declaration)?.is_some() {
+                continue;
+            }
+
+            let name = match name {
+                Some(name) if !scope.is_empty() => Cow::Owned(qualify_name(scope, &name)),
+                Some(name) => name,
+                None => Cow::Borrowed(""),
+            };
+
+            let size = entry
+                .attr_value(constants::DW_AT_byte_size)?
+                .and_then(|value| value.udata_value());
+
+            let mut info = TypeInfo::new(kind, name, size);
+            if kind == TypeKind::Typedef {
+                if let Some(attr) = entry.attr(constants::DW_AT_type)? {
+                    info.aliased_type = self.inner.resolve_type_name(attr, 0)?.map(Cow::Owned);
+                }
+            }
+
+            // Anonymous types do not open a scope, so their nested types keep the enclosing one.
+            let nested_scope = match info.name.is_empty() {
+                true => scope.to_owned(),
+                false => info.name.to_string(),
+            };
+
+            let index = types.len();
+            types.push(info);
+
+            if kind != TypeKind::Typedef {
+                scopes.push((depth, nested_scope, Some(index)));
+            }
+        }
+
+        Ok(types)
+    }
+
+    /// Adds a member, base class or enumerator entry to the containing type.
+    fn collect_type_child(
+        &self,
+        tag: constants::DwTag,
+        entry: &Die<'d, '_>,
+        info: &mut TypeInfo<'d>,
+    ) -> Result<(), DwarfError> {
+        let type_name = match entry.attr(constants::DW_AT_type)? {
+            Some(attr) => self.inner.resolve_type_name(attr, 0)?,
+            None => None,
+        };
+
+        match tag {
+            constants::DW_TAG_member => {
+                // Static members are declared like data members in DWARF 4, but without a location.
+                let location = entry.attr_value(constants::DW_AT_data_member_location)?;
+                if location.is_none() && entry.attr(constants::DW_AT_declaration)?.is_some() {
+                    return Ok(());
+                }
+
+                let bit_size = entry
+                    .attr_value(constants::DW_AT_bit_size)?
+                    .and_then(|value| value.udata_value());
+
+                let mut offset = location.and_then(member_offset).unwrap_or(0);
+                let mut bit_offset = None;
+                if let Some(bits) = entry
+                    .attr_value(constants::DW_AT_data_bit_offset)?
+                    .and_then(|value| value.udata_value())
+                {
+                    offset += bits / 8;
+                    bit_offset = Some(bits % 8);
+                } else if let Some(bits) = entry
+                    .attr_value(constants::DW_AT_bit_offset)?
+                    .and_then(|value| value.udata_value())
+                {
+                    // DWARF 2 and 3 count bits from the most significant bit of the storage unit.
+                    let storage_size = entry
+                        .attr_value(constants::DW_AT_byte_size)?
+                        .and_then(|value| value.udata_value());
+
+                    if let (Some(storage_size), Some(bit_size)) = (storage_size, bit_size) {
+                        bit_offset = (storage_size * 8).checked_sub(bits + bit_size);
+                    }
+                }
+
+                let name = entry
+                    .attr_value(constants::DW_AT_name)?
+                    .and_then(|value| self.inner.string_value(value));
+
+                info.members.push(TypeMember {
+                    name: name.unwrap_or_default(),
+                    type_name: type_name.map(Cow::Owned),
+                    offset,
+                    bit_size,
+                    bit_offset,
+                });
+            }
+            constants::DW_TAG_inheritance => {
+                let is_virtual = match entry.attr_value(constants::DW_AT_virtuality)? {
+                    Some(AttributeValue::Virtuality(virtuality)) => {
+                        virtuality != constants::DW_VIRTUALITY_none
+                    }
+                    _ => false,
+                };
+
+                let offset = match is_virtual {
+                    true => None,
+                    false => entry
+                        .attr_value(constants::DW_AT_data_member_location)?
+                        .and_then(member_offset)
+                        .or(Some(0)),
+                };
+
+                info.base_classes.push(BaseClass {
+                    type_name: Cow::Owned(type_name.unwrap_or_default()),
+                    offset,
+                    is_virtual,
+                });
+            }
+            constants::DW_TAG_enumerator => {
+                let value = match entry.attr_value(constants::DW_AT_const_value)? {
+                    Some(AttributeValue::Sdata(value)) => value,
+                    Some(value) => value.udata_value().unwrap_or_default() as i64,
+                    None => 0,
+                };
+
+                let name = entry
+                    .attr_value(constants::DW_AT_name)?
+                    .and_then(|value| self.inner.string_value(value));
+
+                info.enumerators.push(Enumerator {
+                    name: name.unwrap_or_default(),
+                    value,
+                });
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
     /// Collects all functions within this compilation unit.
     fn functions(&self, range_buf: &mut Vec<Range>) -> Result<Vec<Function<'d>>, DwarfError> {
         let mut depth = 0;
@@ -743,7 +1393,7 @@ impl<'d, 'a> DwarfUnit<'d, 'a> {
             };
 
             range_buf.clear();
-            let (call_line, call_file) = self.parse_ranges(entry, range_buf)?;
+            let (call_line, call_file, call_column) = self.parse_ranges(entry, range_buf)?;
 
             // Ranges can be empty for two reasons: (1) the function is a no-op and does not
             // contain any code, or (2) the function did contain eliminated dead code. In the
@@ -822,6 +1472,7 @@ impl<'d, 'a> DwarfUnit<'d, 'a> {
                                     size: Some(range_end.min(next.address) - range_begin),
                                     file: file.clone(),
                                     line,
+                                    column: call_column,
                                 };
 
                                 lines.insert(index, line_info);
@@ -857,6 +1508,7 @@ impl<'d, 'a> DwarfUnit<'d, 'a> {
                                     size: Some(record_end - range_end),
                                     file: record.file.clone(),
                                     line: record.line,
+                                    column: record.column,
                                 })
                             } else {
                                 None
@@ -882,6 +1534,7 @@ impl<'d, 'a> DwarfUnit<'d, 'a> {
                                     size: Some(size),
                                     file: file.clone(),
                                     line,
+                                    column: call_column,
                                 };
 
                                 lines.insert(index, line_info);
@@ -889,6 +1542,7 @@ impl<'d, 'a> DwarfUnit<'d, 'a> {
                             } else {
                                 record.file = file.clone();
                                 record.line = line;
+                                record.column = call_column;
                             };
 
                             // Insert the split record after mutating the previous one to avoid
@@ -909,6 +1563,7 @@ impl<'d, 'a> DwarfUnit<'d, 'a> {
                                     size: Some(range_end - record_end),
                                     file: file.clone(),
                                     line,
+                                    column: call_column,
                                 };
 
                                 lines.insert(index, line_info);
@@ -927,6 +1582,8 @@ impl<'d, 'a> DwarfUnit<'d, 'a> {
                 lines,
                 inlinees: Vec::new(),
                 inline,
+                multiple: false,
+                parameter_size: None,
             };
 
             stack.push(depth, function)
@@ -937,31 +1594,307 @@ impl<'d, 'a> DwarfUnit<'d, 'a> {
 
         Ok(functions)
     }
-}
-
-/// Converts a DWARF language number into our `Language` type.
-fn language_from_dwarf(language: gimli::DwLang) -> Language {
-    match language {
-        constants::DW_LANG_C => Language::C,
-        constants::DW_LANG_C11 => Language::C,
-        constants::DW_LANG_C89 => Language::C,
-        constants::DW_LANG_C99 => Language::C,
-        constants::DW_LANG_C_plus_plus => Language::Cpp,
-        constants::DW_LANG_C_plus_plus_03 => Language::Cpp,
-        constants::DW_LANG_C_plus_plus_11 => Language::Cpp,
-        constants::DW_LANG_C_plus_plus_14 => Language::Cpp,
-        constants::DW_LANG_D => Language::D,
-        constants::DW_LANG_Go => Language::Go,
-        constants::DW_LANG_ObjC => Language::ObjC,
-        constants::DW_LANG_ObjC_plus_plus => Language::ObjCpp,
-        constants::DW_LANG_Rust => Language::Rust,
-        constants::DW_LANG_Swift => Language::Swift,
-        _ => Language::Unknown,
-    }
-}
 
-/// Data of a specific DWARF section.
-struct DwarfSectionData<'data, S> {
+    /// Collects addresses claimed by functions with different names, using the same rules as
+    /// `functions`.
+    ///
+    /// `names` holds the name of the first function seen at every address. Functions with the same
+    /// name are duplicates of inline functions that have been merged by the linker, instead.
+    fn collect_folded_addresses(
+        &self,
+        range_buf: &mut Vec<Range>,
+        names: &mut HashMap<u64, Name<'d>>,
+        folded: &mut HashSet<u64>,
+    ) -> Result<(), DwarfError> {
+        let mut depth = 0;
+        let mut skipped_depth = None;
+
+        let mut entries = self.inner.unit.entries();
+        while let Some((movement, entry)) = entries.next_dfs()? {
+            depth += movement;
+
+            match skipped_depth {
+                Some(skipped) if depth > skipped => continue,
+                _ => skipped_depth = None,
+            }
+
+            let inline = match entry.tag() {
+                constants::DW_TAG_subprogram => false,
+                constants::DW_TAG_inlined_subroutine => true,
+                _ => continue,
+            };
+
+            range_buf.clear();
+            self.parse_ranges(entry, range_buf)?;
+
+            if range_buf.is_empty() || (!inline && range_buf.len() != 1) {
+                skipped_depth = Some(depth);
+            } else if !inline {
+                let address = offset(range_buf[0].begin, self.inner.info.address_offset);
+                let name = self
+                    .resolve_dwarf_name(entry)
+                    .unwrap_or_else(|| Name::new("", NameMangling::Unmangled, self.language));
+
+                match names.entry(address) {
+                    Entry::Occupied(first) => {
+                        if first.get().as_str() != name.as_str() {
+                            folded.insert(address);
+                        }
+                    }
+                    Entry::Vacant(vacant) => {
+                        vacant.insert(name);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Joins a scope and a name with the C++ scope separator.
+fn qualify_name(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{}::{}", scope, name)
+    }
+}
+
+/// Reads the offset of a member from its `DW_AT_data_member_location` attribute.
+///
+/// Besides constant offsets, this supports location expressions consisting of a single
+/// `DW_OP_plus_uconst` operation, as emitted by older compilers.
+fn member_offset(value: AttributeValue<Slice<'_>>) -> Option<u64> {
+    match value {
+        AttributeValue::Exprloc(expression) => {
+            let mut reader = expression.0;
+            if reader.read_u8().ok()? != constants::DW_OP_plus_uconst.0 {
+                return None;
+            }
+            reader.read_uleb128().ok()
+        }
+        value => value.udata_value(),
+    }
+}
+
+/// Marks functions as `multiple` if their code is claimed by another function.
+///
+/// Identical code folding retains the debug information of all folded functions, which then point
+/// to the same code, possibly across compilation units. Aliases, such as constructor variants,
+/// share a single subprogram instead and are therefore not marked.
+fn mark_folded_functions(functions: &mut [Function<'_>], folded: &HashSet<u64>) {
+    for function in functions {
+        function.multiple = folded.contains(&function.address);
+    }
+}
+
+/// Determines whether a compilation unit has been eliminated by the linker.
+fn is_eliminated_unit(
+    unit: &Unit<'_>,
+    entry: &Die<'_, '_>,
+    kind: ObjectKind,
+) -> Result<bool, DwarfError> {
+    Ok(!is_relocatable(kind) && unit.low_pc == 0 && entry.attr(constants::DW_AT_ranges)?.is_none())
+}
+
+/// Checks whether objects of this kind have not been linked yet.
+///
+/// Code in such objects legitimately starts at address zero.
+fn is_relocatable(kind: ObjectKind) -> bool {
+    matches!(kind, ObjectKind::Relocatable | ObjectKind::KernelModule)
+}
+
+/// Returns the identifier linking a skeleton unit to its split unit, if any.
+///
+/// DWARF 5 declares the identifier in the unit header. The GNU extension to DWARF 4 instead
+/// declares it in a `DW_AT_GNU_dwo_id` attribute on the root DIE.
+fn dwo_id(header: &UnitHeader<'_>, entry: &Die<'_, '_>) -> Result<Option<u64>, DwarfError> {
+    match header.type_() {
+        gimli::UnitType::Skeleton(dwo_id) | gimli::UnitType::SplitCompilation(dwo_id) => {
+            return Ok(Some(dwo_id.0))
+        }
+        _ => (),
+    }
+
+    Ok(match entry.attr_value(constants::DW_AT_GNU_dwo_id)? {
+        Some(AttributeValue::DwoId(dwo_id)) => Some(dwo_id.0),
+        _ => None,
+    })
+}
+
+/// Returns the checksum of a file entry in the line program, if present.
+fn file_checksum(
+    line_program: &LineNumberProgramHeader<'_>,
+    file: &LineProgramFileEntry<'_>,
+) -> Option<FileChecksum> {
+    if line_program.file_has_md5() {
+        Some(FileChecksum::Md5(*file.md5()))
+    } else {
+        None
+    }
+}
+
+/// Reads a single attribute value from a DWARF 5 entry format in the line program header.
+///
+/// Only string values are returned. All other values are skipped and yield `None`.
+fn read_line_attribute<'d>(
+    reader: &mut Slice<'d>,
+    form: constants::DwForm,
+    format: gimli::Format,
+) -> Result<Option<AttributeValue<Slice<'d>>>, GimliError> {
+    let value = match form {
+        constants::DW_FORM_string => AttributeValue::String(reader.read_null_terminated_slice()?),
+        constants::DW_FORM_line_strp => {
+            AttributeValue::DebugLineStrRef(gimli::DebugLineStrOffset(reader.read_offset(format)?))
+        }
+        constants::DW_FORM_strp => {
+            AttributeValue::DebugStrRef(gimli::DebugStrOffset(reader.read_offset(format)?))
+        }
+        constants::DW_FORM_strp_sup | constants::DW_FORM_GNU_strp_alt => {
+            AttributeValue::DebugStrRefSup(gimli::DebugStrOffset(reader.read_offset(format)?))
+        }
+        constants::DW_FORM_strx => {
+            let index = reader.read_uleb128().and_then(usize::from_u64)?;
+            AttributeValue::DebugStrOffsetsIndex(gimli::DebugStrOffsetsIndex(index))
+        }
+        constants::DW_FORM_strx1
+        | constants::DW_FORM_strx2
+        | constants::DW_FORM_strx3
+        | constants::DW_FORM_strx4 => {
+            let size = match form {
+                constants::DW_FORM_strx1 => 1,
+                constants::DW_FORM_strx2 => 2,
+                constants::DW_FORM_strx3 => 3,
+                _ => 4,
+            };
+            let index = reader.read_uint(size).and_then(usize::from_u64)?;
+            AttributeValue::DebugStrOffsetsIndex(gimli::DebugStrOffsetsIndex(index))
+        }
+        constants::DW_FORM_udata => {
+            reader.read_uleb128()?;
+            return Ok(None);
+        }
+        constants::DW_FORM_data1
+        | constants::DW_FORM_data2
+        | constants::DW_FORM_data4
+        | constants::DW_FORM_data8
+        | constants::DW_FORM_data16 => {
+            let size = match form {
+                constants::DW_FORM_data1 => 1,
+                constants::DW_FORM_data2 => 2,
+                constants::DW_FORM_data4 => 4,
+                constants::DW_FORM_data8 => 8,
+                _ => 16,
+            };
+            reader.skip(size)?;
+            return Ok(None);
+        }
+        constants::DW_FORM_block => {
+            let size = reader.read_uleb128().and_then(usize::from_u64)?;
+            reader.skip(size)?;
+            return Ok(None);
+        }
+        _ => return Err(GimliError::UnknownForm),
+    };
+
+    Ok(Some(value))
+}
+
+/// Reads entry format descriptions of a DWARF 5 line program header.
+fn read_entry_formats(
+    reader: &mut Slice<'_>,
+) -> Result<Vec<(constants::DwLnct, constants::DwForm)>, GimliError> {
+    let count = reader.read_u8()?;
+    let mut formats = Vec::with_capacity(count.into());
+
+    for _ in 0..count {
+        let content_type = reader.read_uleb128_u16()?;
+        let form = reader.read_uleb128_u16()?;
+        formats.push((constants::DwLnct(content_type), constants::DwForm(form)));
+    }
+
+    Ok(formats)
+}
+
+/// Reads `DW_LNCT_LLVM_source` values for all file entries in a DWARF 5 line program header.
+///
+/// gimli discards content types it does not know, so the header is read again from the raw
+/// `.debug_line` section. The returned values are in the same order as `file_names()`. If the
+/// header does not contain embedded sources, an empty list is returned.
+fn read_embedded_sources<'d>(
+    debug_line: Slice<'d>,
+    header: &LineNumberProgramHeader<'d>,
+) -> Result<Vec<Option<AttributeValue<Slice<'d>>>>, GimliError> {
+    let has_sources = header
+        .file_name_entry_format()
+        .iter()
+        .any(|format| format.content_type == DW_LNCT_LLVM_SOURCE);
+
+    if header.version() < 5 || !has_sources {
+        return Ok(Vec::new());
+    }
+
+    let mut reader = debug_line;
+    reader.skip(header.offset().0)?;
+
+    let (_, format) = reader.read_initial_length()?;
+    // version, address_size, segment_selector_size
+    reader.skip(4)?;
+    // header_length
+    reader.read_offset(format)?;
+    // minimum_instruction_length, maximum_operations_per_instruction, default_is_stmt, line_base,
+    // line_range, opcode_base, followed by the standard opcode lengths
+    reader.skip(6 + header.standard_opcode_lengths().len())?;
+
+    let directory_formats = read_entry_formats(&mut reader)?;
+    let directory_count = reader.read_uleb128()?;
+    for _ in 0..directory_count {
+        for &(_, form) in &directory_formats {
+            read_line_attribute(&mut reader, form, format)?;
+        }
+    }
+
+    let file_formats = read_entry_formats(&mut reader)?;
+    let file_count = reader.read_uleb128()?;
+    let mut sources = Vec::new();
+    for _ in 0..file_count {
+        let mut source = None;
+        for &(content_type, form) in &file_formats {
+            let value = read_line_attribute(&mut reader, form, format)?;
+            if content_type == DW_LNCT_LLVM_SOURCE {
+                source = value;
+            }
+        }
+        sources.push(source);
+    }
+
+    Ok(sources)
+}
+
+/// Converts a DWARF language number into our `Language` type.
+fn language_from_dwarf(language: gimli::DwLang) -> Language {
+    match language {
+        constants::DW_LANG_C => Language::C,
+        constants::DW_LANG_C11 => Language::C,
+        constants::DW_LANG_C89 => Language::C,
+        constants::DW_LANG_C99 => Language::C,
+        constants::DW_LANG_C_plus_plus => Language::Cpp,
+        constants::DW_LANG_C_plus_plus_03 => Language::Cpp,
+        constants::DW_LANG_C_plus_plus_11 => Language::Cpp,
+        constants::DW_LANG_C_plus_plus_14 => Language::Cpp,
+        constants::DW_LANG_D => Language::D,
+        constants::DW_LANG_Go => Language::Go,
+        constants::DW_LANG_ObjC => Language::ObjC,
+        constants::DW_LANG_ObjC_plus_plus => Language::ObjCpp,
+        constants::DW_LANG_Rust => Language::Rust,
+        constants::DW_LANG_Swift => Language::Swift,
+        _ => Language::Unknown,
+    }
+}
+
+/// Data of a specific DWARF section.
+struct DwarfSectionData<'data, S> {
     data: Cow<'data, [u8]>,
     endianity: Endian,
     _ph: PhantomData<S>,
@@ -974,11 +1907,32 @@ where
     /// Loads data for this section from the object file.
     fn load<D>(dwarf: &D) -> Self
     where
-        D: Dwarf<'data>,
+        D: Dwarf<'data> + ?Sized,
+    {
+        Self::load_named(dwarf, &S::section_name()[1..])
+    }
+
+    /// Loads data for this section from a split DWARF object file.
+    ///
+    /// Sections in `.dwo` and `.dwp` files carry a `.dwo` suffix.
+    fn load_dwo<D>(dwarf: &D) -> Self
+    where
+        D: Dwarf<'data> + ?Sized,
+    {
+        match S::dwo_section_name() {
+            Some(name) => Self::load_named(dwarf, &name[1..]),
+            None => Self::load_named(dwarf, &S::section_name()[1..]),
+        }
+    }
+
+    /// Loads data for the section with the given name.
+    fn load_named<D>(dwarf: &D, name: &str) -> Self
+    where
+        D: Dwarf<'data> + ?Sized,
     {
         DwarfSectionData {
             data: dwarf
-                .section(&S::section_name()[1..])
+                .section(name)
                 .map(|section| section.data)
                 .unwrap_or_default(),
             endianity: dwarf.endianity(),
@@ -990,6 +1944,23 @@ where
     fn to_gimli(&'data self) -> S {
         S::from(Slice::new(&self.data, self.endianity))
     }
+
+    /// Creates a gimli dwarf section object from a contribution within the loaded data.
+    ///
+    /// If no contribution is given, this returns the entire section. If the contribution exceeds
+    /// the section bounds, this returns an empty section.
+    fn to_gimli_range(&'data self, range: Option<DwpContribution>) -> S {
+        let data = match range {
+            Some(range) => self
+                .data
+                .get(range.offset..)
+                .and_then(|data| data.get(..range.size))
+                .unwrap_or_default(),
+            None => &self.data,
+        };
+
+        S::from(Slice::new(data, self.endianity))
+    }
 }
 
 impl<'d, S> fmt::Debug for DwarfSectionData<'d, S>
@@ -1014,6 +1985,7 @@ where
 /// All DWARF sections that are needed by `DwarfDebugSession`.
 struct DwarfSections<'data> {
     debug_abbrev: DwarfSectionData<'data, gimli::read::DebugAbbrev<Slice<'data>>>,
+    debug_addr: DwarfSectionData<'data, gimli::read::DebugAddr<Slice<'data>>>,
     debug_info: DwarfSectionData<'data, gimli::read::DebugInfo<Slice<'data>>>,
     debug_line: DwarfSectionData<'data, gimli::read::DebugLine<Slice<'data>>>,
     debug_line_str: DwarfSectionData<'data, gimli::read::DebugLineStr<Slice<'data>>>,
@@ -1021,16 +1993,21 @@ struct DwarfSections<'data> {
     debug_str_offsets: DwarfSectionData<'data, gimli::read::DebugStrOffsets<Slice<'data>>>,
     debug_ranges: DwarfSectionData<'data, gimli::read::DebugRanges<Slice<'data>>>,
     debug_rnglists: DwarfSectionData<'data, gimli::read::DebugRngLists<Slice<'data>>>,
+    debug_loc: DwarfSectionData<'data, gimli::read::DebugLoc<Slice<'data>>>,
+    debug_loclists: DwarfSectionData<'data, gimli::read::DebugLocLists<Slice<'data>>>,
+    split: Vec<DwarfSplitSections<'data>>,
+    sup: Option<Box<DwarfSections<'data>>>,
 }
 
 impl<'data> DwarfSections<'data> {
     /// Loads all sections from a DWARF object.
     fn from_dwarf<D>(dwarf: &D) -> Self
     where
-        D: Dwarf<'data>,
+        D: Dwarf<'data> + ?Sized,
     {
         DwarfSections {
             debug_abbrev: DwarfSectionData::load(dwarf),
+            debug_addr: DwarfSectionData::load(dwarf),
             debug_info: DwarfSectionData::load(dwarf),
             debug_line: DwarfSectionData::load(dwarf),
             debug_line_str: DwarfSectionData::load(dwarf),
@@ -1038,14 +2015,197 @@ impl<'data> DwarfSections<'data> {
             debug_str_offsets: DwarfSectionData::load(dwarf),
             debug_ranges: DwarfSectionData::load(dwarf),
             debug_rnglists: DwarfSectionData::load(dwarf),
+            debug_loc: DwarfSectionData::load(dwarf),
+            debug_loclists: DwarfSectionData::load(dwarf),
+            split: dwarf
+                .split_dwarf()
+                .into_iter()
+                .map(DwarfSplitSections::from_dwarf)
+                .collect(),
+            sup: dwarf
+                .supplementary_dwarf()
+                .map(|sup| Box::new(DwarfSections::from_dwarf(sup))),
         }
     }
 }
 
+/// All DWARF sections that are needed from a split DWARF object (`.dwo` or `.dwp`).
+struct DwarfSplitSections<'data> {
+    debug_abbrev: DwarfSectionData<'data, gimli::read::DebugAbbrev<Slice<'data>>>,
+    debug_info: DwarfSectionData<'data, gimli::read::DebugInfo<Slice<'data>>>,
+    debug_line: DwarfSectionData<'data, gimli::read::DebugLine<Slice<'data>>>,
+    debug_str: DwarfSectionData<'data, gimli::read::DebugStr<Slice<'data>>>,
+    debug_str_offsets: DwarfSectionData<'data, gimli::read::DebugStrOffsets<Slice<'data>>>,
+    debug_rnglists: DwarfSectionData<'data, gimli::read::DebugRngLists<Slice<'data>>>,
+    debug_loc: DwarfSectionData<'data, gimli::read::DebugLoc<Slice<'data>>>,
+    debug_loclists: DwarfSectionData<'data, gimli::read::DebugLocLists<Slice<'data>>>,
+    debug_cu_index: Cow<'data, [u8]>,
+    endianity: Endian,
+}
+
+impl<'data> DwarfSplitSections<'data> {
+    /// Loads all sections from a split DWARF object.
+    fn from_dwarf(dwarf: &dyn Dwarf<'data>) -> Self {
+        DwarfSplitSections {
+            debug_abbrev: DwarfSectionData::load_dwo(dwarf),
+            debug_info: DwarfSectionData::load_dwo(dwarf),
+            debug_line: DwarfSectionData::load_dwo(dwarf),
+            debug_str: DwarfSectionData::load_dwo(dwarf),
+            debug_str_offsets: DwarfSectionData::load_dwo(dwarf),
+            debug_rnglists: DwarfSectionData::load_dwo(dwarf),
+            debug_loc: DwarfSectionData::load_dwo(dwarf),
+            debug_loclists: DwarfSectionData::load_dwo(dwarf),
+            debug_cu_index: dwarf
+                .section("debug_cu_index")
+                .map(|section| section.data)
+                .unwrap_or_default(),
+            endianity: dwarf.endianity(),
+        }
+    }
+}
+
+/// A contribution of a single unit to a section in a DWARF package.
+#[derive(Clone, Copy, Debug)]
+struct DwpContribution {
+    offset: usize,
+    size: usize,
+}
+
+/// An entry in the compilation unit index (`.debug_cu_index`) of a DWARF package.
+#[derive(Debug, Default)]
+struct DwpUnitEntry {
+    dwo_id: u64,
+    info: Option<DwpContribution>,
+    abbrev: Option<DwpContribution>,
+    line: Option<DwpContribution>,
+    str_offsets: Option<DwpContribution>,
+    rnglists: Option<DwpContribution>,
+    /// Contribution to `.debug_loc.dwo` in version 2, or `.debug_loclists.dwo` in version 5.
+    loclists: Option<DwpContribution>,
+}
+
+/// Parses the compilation unit index of a DWARF package (`.dwp`).
+///
+/// Both the GNU extension to DWARF 4 (version 2) and DWARF 5 package indexes are supported. The
+/// index maps DWO identifiers to the contributions of each unit to the package's sections.
+fn parse_cu_index(data: &[u8], endianity: Endian) -> Result<Vec<DwpUnitEntry>, GimliError> {
+    use gimli::read::Reader;
+
+    let mut reader = Slice::new(data, endianity);
+
+    // DWARF 5 declares a 2-byte version followed by 2 bytes of padding, while version 2 of the GNU
+    // extension declares a 4-byte version.
+    let mut peek = reader;
+    let version = match peek.read_u16()? {
+        5 => {
+            reader.skip(4)?;
+            5
+        }
+        _ => reader.read_u32()?,
+    };
+
+    if version != 2 && version != 5 {
+        return Err(GimliError::UnknownVersion(version.into()));
+    }
+
+    let section_count = reader.read_u32()? as usize;
+    let unit_count = reader.read_u32()? as usize;
+    let slot_count = reader.read_u32()? as usize;
+
+    let row_size = section_count
+        .checked_mul(4)
+        .ok_or_else(|| GimliError::UnexpectedEof(reader.offset_id()))?;
+
+    let mut hashes = reader;
+    let mut indexes = skip_table(reader, slot_count, 8)?;
+    let mut columns = skip_table(indexes, slot_count, 4)?;
+    let offsets = skip_table(columns, 1, row_size)?;
+    let sizes = skip_table(offsets, unit_count, row_size)?;
+
+    let mut section_ids = Vec::with_capacity(section_count.min(data.len() / 4));
+    for _ in 0..section_count {
+        section_ids.push(columns.read_u32()?);
+    }
+
+    let mut entries = Vec::with_capacity(unit_count.min(slot_count));
+    for _ in 0..slot_count {
+        let dwo_id = hashes.read_u64()?;
+
+        // Rows are numbered from one, and zero marks an unused slot. Rows beyond the unit count
+        // would read into the size table or past the end of the index.
+        let row = match indexes.read_u32()? as usize {
+            0 => continue,
+            index if index > unit_count => {
+                return Err(GimliError::UnexpectedEof(indexes.offset_id()))
+            }
+            index => index - 1,
+        };
+
+        let mut entry = DwpUnitEntry {
+            dwo_id,
+            ..Default::default()
+        };
+
+        let mut row_offsets = skip_table(offsets, row, row_size)?;
+        let mut row_sizes = skip_table(sizes, row, row_size)?;
+
+        for &section_id in &section_ids {
+            let contribution = Some(DwpContribution {
+                offset: row_offsets.read_u32()? as usize,
+                size: row_sizes.read_u32()? as usize,
+            });
+
+            match (version, section_id) {
+                (_, 1) => entry.info = contribution,
+                (_, 3) => entry.abbrev = contribution,
+                (_, 4) => entry.line = contribution,
+                (_, 5) => entry.loclists = contribution,
+                (_, 6) => entry.str_offsets = contribution,
+                (5, 8) => entry.rnglists = contribution,
+                _ => (),
+            }
+        }
+
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+/// Returns a reader positioned after a table of `count` entries of `size` bytes each.
+///
+/// Returns an error if the table exceeds the reader or its size overflows.
+fn skip_table(mut reader: Slice<'_>, count: usize, size: usize) -> Result<Slice<'_>, GimliError> {
+    use gimli::read::Reader;
+
+    let len = count
+        .checked_mul(size)
+        .ok_or_else(|| GimliError::UnexpectedEof(reader.offset_id()))?;
+    reader.skip(len)?;
+    Ok(reader)
+}
+
+/// A split compilation unit loaded from a `.dwo` or `.dwp` file.
+struct SplitUnit<'data> {
+    dwarf: DwarfInner<'data>,
+    header: UnitHeader<'data>,
+}
+
+/// A compilation unit along with a reference to the split unit it was loaded from.
+struct UnitData<'data> {
+    unit: Unit<'data>,
+    split: Option<u64>,
+    type_scopes: LazyCell<HashMap<UnitOffset, String>>,
+}
+
 struct DwarfInfo<'data> {
     inner: DwarfInner<'data>,
     headers: Vec<UnitHeader<'data>>,
-    units: Vec<LazyCell<Option<Unit<'data>>>>,
+    units: Vec<LazyCell<Option<UnitData<'data>>>>,
+    split_units: HashMap<u64, SplitUnit<'data>>,
+    sup: Option<Box<DwarfInfo<'data>>>,
+    sources: LazyCell<HashMap<String, Cow<'data, str>>>,
+    folded_addresses: LazyCell<HashSet<u64>>,
     symbol_map: SymbolMap<'data>,
     address_offset: i64,
     kind: ObjectKind,
@@ -1069,15 +2229,21 @@ impl<'d> DwarfInfo<'d> {
     ) -> Result<Self, DwarfError> {
         let inner = gimli::read::Dwarf {
             debug_abbrev: sections.debug_abbrev.to_gimli(),
-            debug_addr: Default::default(),
+            debug_addr: sections.debug_addr.to_gimli(),
             debug_info: sections.debug_info.to_gimli(),
             debug_line: sections.debug_line.to_gimli(),
             debug_line_str: sections.debug_line_str.to_gimli(),
             debug_str: sections.debug_str.to_gimli(),
             debug_str_offsets: sections.debug_str_offsets.to_gimli(),
-            debug_str_sup: Default::default(),
+            debug_str_sup: match sections.sup {
+                Some(ref sup) => sup.debug_str.to_gimli(),
+                None => Default::default(),
+            },
             debug_types: Default::default(),
-            locations: Default::default(),
+            locations: LocationLists::new(
+                sections.debug_loc.to_gimli(),
+                sections.debug_loclists.to_gimli(),
+            ),
             ranges: RangeLists::new(
                 sections.debug_ranges.to_gimli(),
                 sections.debug_rnglists.to_gimli(),
@@ -1089,18 +2255,102 @@ impl<'d> DwarfInfo<'d> {
         let headers = inner.units().collect::<Vec<_>>()?;
         let units = headers.iter().map(|_| LazyCell::new()).collect();
 
+        let mut split_units = HashMap::new();
+        for split in &sections.split {
+            Self::load_split_units(sections, split, &mut split_units)?;
+        }
+
+        // Entries in the supplementary file are only resolved through references, so it does not
+        // need a symbol table.
+        let sup = match sections.sup {
+            Some(ref sup) => Some(Box::new(Self::parse(sup, SymbolMap::default(), 0, kind)?)),
+            None => None,
+        };
+
         Ok(DwarfInfo {
             inner,
             headers,
             units,
+            split_units,
+            sup,
+            sources: LazyCell::new(),
+            folded_addresses: LazyCell::new(),
             symbol_map,
             address_offset,
             kind,
         })
     }
 
+    /// Indexes all split units in a `.dwo` or `.dwp` file by their DWO identifier.
+    fn load_split_units(
+        sections: &'d DwarfSections<'d>,
+        split: &'d DwarfSplitSections<'d>,
+        split_units: &mut HashMap<u64, SplitUnit<'d>>,
+    ) -> Result<(), DwarfError> {
+        // Split units resolve addresses and, in DWARF 4, ranges through the skeleton's sections.
+        // Line programs are declared by the skeleton, so their strings also live in the main file.
+        let create_dwarf = |entry: Option<&DwpUnitEntry>| gimli::read::Dwarf {
+            debug_abbrev: split
+                .debug_abbrev
+                .to_gimli_range(entry.and_then(|e| e.abbrev)),
+            debug_addr: sections.debug_addr.to_gimli(),
+            debug_info: split.debug_info.to_gimli_range(entry.and_then(|e| e.info)),
+            debug_line: split.debug_line.to_gimli_range(entry.and_then(|e| e.line)),
+            debug_line_str: sections.debug_line_str.to_gimli(),
+            debug_str: split.debug_str.to_gimli(),
+            debug_str_offsets: split
+                .debug_str_offsets
+                .to_gimli_range(entry.and_then(|e| e.str_offsets)),
+            debug_str_sup: Default::default(),
+            debug_types: Default::default(),
+            locations: LocationLists::new(
+                split
+                    .debug_loc
+                    .to_gimli_range(entry.and_then(|e| e.loclists)),
+                split
+                    .debug_loclists
+                    .to_gimli_range(entry.and_then(|e| e.loclists)),
+            ),
+            ranges: RangeLists::new(
+                sections.debug_ranges.to_gimli(),
+                split
+                    .debug_rnglists
+                    .to_gimli_range(entry.and_then(|e| e.rnglists)),
+            ),
+            file_type: DwarfFileType::Dwo,
+        };
+
+        if split.debug_cu_index.is_empty() {
+            // A `.dwo` file contains units of a single compilation, each declaring its DWO
+            // identifier in the header or the root DIE.
+            let dwarf = create_dwarf(None);
+            let mut headers = dwarf.units();
+            while let Some(header) = headers.next()? {
+                let abbrevs = dwarf.abbreviations(&header)?;
+                let mut entries = header.entries(&abbrevs);
+                if let Some((_, entry)) = entries.next_dfs()? {
+                    if let Some(dwo_id) = dwo_id(&header, entry)? {
+                        let dwarf = create_dwarf(None);
+                        split_units.insert(dwo_id, SplitUnit { dwarf, header });
+                    }
+                }
+            }
+        } else {
+            // A `.dwp` package contains units of many compilations, whose section contributions
+            // are listed in the unit index.
+            for entry in parse_cu_index(&split.debug_cu_index, split.endianity)? {
+                let dwarf = create_dwarf(Some(&entry));
+                if let Some(header) = dwarf.units().next()? {
+                    split_units.insert(entry.dwo_id, SplitUnit { dwarf, header });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Loads a compilation unit.
-    fn get_unit(&self, index: usize) -> Result<Option<&Unit<'d>>, DwarfError> {
+    fn get_unit(&self, index: usize) -> Result<Option<UnitRef<'d, '_>>, DwarfError> {
         // Silently ignore unit references out-of-bound
         let cell = match self.units.get(index) {
             Some(cell) => cell,
@@ -1114,13 +2364,92 @@ impl<'d> DwarfInfo<'d> {
             // an empty unit for us.
             let header = self.headers[index];
             match self.inner.unit(header) {
-                Ok(unit) => Ok(Some(unit)),
+                Ok(unit) => self.resolve_split_unit(unit).map(Some),
                 Err(gimli::read::Error::MissingUnitDie) => Ok(None),
                 Err(error) => Err(DwarfError::from(error)),
             }
         })?;
 
-        Ok(unit_opt.as_ref())
+        Ok(unit_opt.as_ref().map(|data| self.unit_ref(data)))
+    }
+
+    /// Replaces a skeleton unit with its split unit, if the split unit has been loaded.
+    ///
+    /// The split unit inherits the address base, line program and compilation directory from the
+    /// skeleton unit. If there is no split unit for the given unit, it is returned unchanged.
+    fn resolve_split_unit(&self, skeleton: Unit<'d>) -> Result<UnitData<'d>, DwarfError> {
+        let unchanged = |unit| {
+            Ok(UnitData {
+                unit,
+                split: None,
+                type_scopes: LazyCell::new(),
+            })
+        };
+
+        if self.split_units.is_empty() {
+            return unchanged(skeleton);
+        }
+
+        let mut entries = skeleton.entries();
+        let entry = match entries.next_dfs()? {
+            Some((_, entry)) => entry,
+            None => return unchanged(skeleton),
+        };
+
+        // Eliminated units are skipped later on. Since the skeleton carries the ranges of the
+        // entire unit, this can only be determined before switching to the split unit.
+        if is_eliminated_unit(&skeleton, entry, self.kind)? {
+            return unchanged(skeleton);
+        }
+
+        let split_unit = match dwo_id(&skeleton.header, entry)? {
+            Some(dwo_id) => self.split_units.get(&dwo_id).map(|split| (dwo_id, split)),
+            None => None,
+        };
+
+        let (dwo_id, split) = match split_unit {
+            Some(split_unit) => split_unit,
+            None => return unchanged(skeleton),
+        };
+
+        let mut unit = match split.dwarf.unit(split.header) {
+            Ok(unit) => unit,
+            Err(gimli::read::Error::MissingUnitDie) => return unchanged(skeleton),
+            Err(error) => return Err(error.into()),
+        };
+
+        unit.copy_relocated_attributes(&skeleton);
+        if unit.name.is_none() {
+            unit.name = skeleton.name;
+        }
+        if unit.comp_dir.is_none() {
+            unit.comp_dir = skeleton.comp_dir;
+        }
+        if unit.line_program.is_none() {
+            unit.line_program = skeleton.line_program;
+        }
+
+        Ok(UnitData {
+            unit,
+            split: Some(dwo_id),
+            type_scopes: LazyCell::new(),
+        })
+    }
+
+    /// Creates a reference to a loaded unit and the sections it was loaded from.
+    fn unit_ref<'a>(&'a self, data: &'a UnitData<'d>) -> UnitRef<'d, 'a> {
+        let dwarf = match data.split {
+            Some(dwo_id) => &self.split_units[&dwo_id].dwarf,
+            None => &self.inner,
+        };
+
+        UnitRef {
+            info: self,
+            dwarf,
+            unit: &data.unit,
+            split: data.split.is_some(),
+            type_scopes: &data.type_scopes,
+        }
     }
 
     /// Resolves an offset into a different compilation unit.
@@ -1140,8 +2469,8 @@ impl<'d> DwarfInfo<'d> {
         };
 
         if let Some(unit) = self.get_unit(index)? {
-            if let Some(unit_offset) = section_offset.to_unit_offset(unit) {
-                return Ok((UnitRef { unit, info: self }, unit_offset));
+            if let Some(unit_offset) = section_offset.to_unit_offset(unit.unit) {
+                return Ok((unit, unit_offset));
             }
         }
 
@@ -1155,6 +2484,30 @@ impl<'d> DwarfInfo<'d> {
             index: 0,
         }
     }
+
+    /// Returns source contents embedded into line programs, keyed by absolute file path.
+    fn sources(&'d self) -> Result<&HashMap<String, Cow<'d, str>>, DwarfError> {
+        self.sources.try_borrow_with(|| {
+            let mut sources = HashMap::new();
+            for unit in self.units() {
+                unit?.collect_sources(&mut sources)?;
+            }
+            Ok(sources)
+        })
+    }
+
+    /// Returns the start addresses of functions folded by the linker across all compilation units.
+    fn folded_addresses(&'d self) -> Result<&HashSet<u64>, DwarfError> {
+        self.folded_addresses.try_borrow_with(|| {
+            let mut names = HashMap::new();
+            let mut folded = HashSet::new();
+            let mut range_buf = Vec::new();
+            for unit in self.units() {
+                unit?.collect_folded_addresses(&mut range_buf, &mut names, &mut folded)?;
+            }
+            Ok(folded)
+        })
+    }
 }
 
 impl<'slf, 'd: 'slf> AsSelf<'slf> for DwarfInfo<'d> {
@@ -1169,6 +2522,8 @@ impl fmt::Debug for DwarfInfo<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("DwarfInfo")
             .field("headers", &self.headers)
+            .field("split_units", &self.split_units.len())
+            .field("sup", &self.sup)
             .field("symbol_map", &self.symbol_map)
             .field("address_offset", &self.address_offset)
             .finish()
@@ -1195,7 +2550,7 @@ impl<'s> Iterator for DwarfUnitIterator<'s> {
                 Err(error) => return Some(Err(error)),
             };
 
-            match DwarfUnit::from_unit(unit, self.info) {
+            match DwarfUnit::from_unit(unit) {
                 Ok(Some(unit)) => return Some(Ok(unit)),
                 Ok(None) => continue,
                 Err(error) => return Some(Err(error)),
@@ -1211,6 +2566,7 @@ impl std::iter::FusedIterator for DwarfUnitIterator<'_> {}
 /// A debugging session for DWARF debugging information.
 pub struct DwarfDebugSession<'data> {
     cell: SelfCell<Box<DwarfSections<'data>>, DwarfInfo<'data>>,
+    bcsymbolmap: Option<Arc<BcSymbolMap<'data>>>,
 }
 
 impl<'data> DwarfDebugSession<'data> {
@@ -1229,7 +2585,27 @@ impl<'data> DwarfDebugSession<'data> {
             DwarfInfo::parse(unsafe { &*sections }, symbol_map, address_offset, kind)
         })?;
 
-        Ok(DwarfDebugSession { cell })
+        Ok(DwarfDebugSession {
+            cell,
+            bcsymbolmap: None,
+        })
+    }
+
+    /// Loads a [`BcSymbolMap`] to resolve hidden names in functions and files.
+    ///
+    /// Bitcode-enabled builds replace function names and paths with placeholders of the form
+    /// `__hidden#N_`. Once a symbol map is loaded, these placeholders are resolved by
+    /// [`functions`](struct.DwarfDebugSession.html#method.functions) and
+    /// [`files`](struct.DwarfDebugSession.html#method.files).
+    ///
+    /// [`BcSymbolMap`]: ../macho/struct.BcSymbolMap.html
+    pub fn load_symbolmap(&mut self, symbolmap: BcSymbolMap<'data>) {
+        self.bcsymbolmap = Some(Arc::new(symbolmap));
+    }
+
+    /// Shares a symbol map that has been loaded into an object with this session.
+    pub(crate) fn set_symbolmap(&mut self, symbolmap: Option<Arc<BcSymbolMap<'data>>>) {
+        self.bcsymbolmap = symbolmap;
     }
 
     /// Returns an iterator over all source files in this debug file.
@@ -1237,6 +2613,7 @@ impl<'data> DwarfDebugSession<'data> {
         DwarfFileIterator {
             units: self.cell.get().units(),
             files: DwarfUnitFileIterator::default(),
+            symbolmap: self.bcsymbolmap.as_deref(),
             finished: false,
         }
     }
@@ -1247,15 +2624,55 @@ impl<'data> DwarfDebugSession<'data> {
             units: self.cell.get().units(),
             functions: Vec::new().into_iter(),
             range_buf: Vec::new(),
+            symbolmap: self.bcsymbolmap.as_deref(),
+            finished: false,
+        }
+    }
+
+    /// Returns an iterator over all structures, classes, unions, enumerations and typedefs.
+    ///
+    /// Types are yielded once for every compilation unit that defines them. Declarations without
+    /// a definition are skipped.
+    pub fn types(&self) -> DwarfTypeIterator<'_> {
+        DwarfTypeIterator {
+            units: self.cell.get().units(),
+            types: Vec::new().into_iter(),
+            finished: false,
+        }
+    }
+
+    /// Returns an iterator over parameters and local variables of all functions.
+    ///
+    /// Variables are yielded in the order they are declared in the debug information. Use
+    /// [`Variable::function_address`] to associate them with functions.
+    ///
+    /// [`Variable::function_address`]: ../struct.Variable.html#structfield.function_address
+    pub fn variables(&self) -> DwarfVariableIterator<'_> {
+        DwarfVariableIterator {
+            units: self.cell.get().units(),
+            variables: Vec::new().into_iter(),
+            range_buf: Vec::new(),
             finished: false,
         }
     }
 
     /// Looks up a file's source contents by its full canonicalized path.
     ///
-    /// The given path must be canonicalized.
-    pub fn source_by_path(&self, _path: &str) -> Result<Option<Cow<'_, str>>, DwarfError> {
-        Ok(None)
+    /// This returns source code that was embedded into DWARF 5 line tables, for instance with
+    /// `clang -gembed-source`. The given path must be canonicalized.
+    pub fn source_by_path(&self, path: &str) -> Result<Option<Cow<'_, str>>, DwarfError> {
+        let sources = self.cell.get().sources()?;
+        Ok(sources
+            .get(path)
+            .map(|source| Cow::Borrowed(source.as_ref())))
+    }
+
+    /// Determines whether any line program embeds the contents of source files.
+    pub(crate) fn has_sources(&self) -> bool {
+        self.cell
+            .get()
+            .sources()
+            .map_or(false, |sources| !sources.is_empty())
     }
 }
 
@@ -1296,6 +2713,7 @@ impl<'s> Iterator for DwarfUnitFileIterator<'s> {
         Some(FileEntry {
             compilation_dir: unit.compilation_dir(),
             info: unit.file_info(line_program, file),
+            checksum: file_checksum(line_program, file),
         })
     }
 }
@@ -1304,6 +2722,7 @@ impl<'s> Iterator for DwarfUnitFileIterator<'s> {
 pub struct DwarfFileIterator<'s> {
     units: DwarfUnitIterator<'s>,
     files: DwarfUnitFileIterator<'s>,
+    symbolmap: Option<&'s BcSymbolMap<'s>>,
     finished: bool,
 }
 
@@ -1316,7 +2735,11 @@ impl<'s> Iterator for DwarfFileIterator<'s> {
         }
 
         loop {
-            if let Some(file_entry) = self.files.next() {
+            if let Some(mut file_entry) = self.files.next() {
+                if let Some(symbolmap) = self.symbolmap {
+                    symbolmap.resolve_file_entry(&mut file_entry);
+                }
+
                 return Some(Ok(file_entry));
             }
 
@@ -1342,6 +2765,7 @@ pub struct DwarfFunctionIterator<'s> {
     units: DwarfUnitIterator<'s>,
     functions: std::vec::IntoIter<Function<'s>>,
     range_buf: Vec<Range>,
+    symbolmap: Option<&'s BcSymbolMap<'s>>,
     finished: bool,
 }
 
@@ -1354,7 +2778,11 @@ impl<'s> Iterator for DwarfFunctionIterator<'s> {
         }
 
         loop {
-            if let Some(func) = self.functions.next() {
+            if let Some(mut func) = self.functions.next() {
+                if let Some(symbolmap) = self.symbolmap {
+                    symbolmap.resolve_function(&mut func);
+                }
+
                 return Some(Ok(func));
             }
 
@@ -1364,10 +2792,22 @@ impl<'s> Iterator for DwarfFunctionIterator<'s> {
                 None => break,
             };
 
-            self.functions = match unit.functions(&mut self.range_buf) {
-                Ok(functions) => functions.into_iter(),
+            let mut functions = match unit.functions(&mut self.range_buf) {
+                Ok(functions) => functions,
                 Err(error) => return Some(Err(error)),
             };
+
+            // Code is only folded by the linker, after which folded subprograms claim the same
+            // code. Since folding spans compilation units, this requires a pass over all units.
+            let info = self.units.info;
+            if !is_relocatable(info.kind) && !functions.is_empty() {
+                match info.folded_addresses() {
+                    Ok(folded) => mark_folded_functions(&mut functions, folded),
+                    Err(error) => return Some(Err(error)),
+                }
+            }
+
+            self.functions = functions.into_iter();
         }
 
         self.finished = true;
@@ -1376,3 +2816,137 @@ impl<'s> Iterator for DwarfFunctionIterator<'s> {
 }
 
 impl std::iter::FusedIterator for DwarfFunctionIterator<'_> {}
+
+/// An iterator over parameters and local variables in a debug session.
+pub struct DwarfVariableIterator<'s> {
+    units: DwarfUnitIterator<'s>,
+    variables: std::vec::IntoIter<Variable<'s>>,
+    range_buf: Vec<Range>,
+    finished: bool,
+}
+
+impl<'s> Iterator for DwarfVariableIterator<'s> {
+    type Item = Result<Variable<'s>, DwarfError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        loop {
+            if let Some(variable) = self.variables.next() {
+                return Some(Ok(variable));
+            }
+
+            let unit = match self.units.next() {
+                Some(Ok(unit)) => unit,
+                Some(Err(error)) => return Some(Err(error)),
+                None => break,
+            };
+
+            self.variables = match unit.variables(&mut self.range_buf) {
+                Ok(variables) => variables.into_iter(),
+                Err(error) => return Some(Err(error)),
+            };
+        }
+
+        self.finished = true;
+        None
+    }
+}
+
+impl std::iter::FusedIterator for DwarfVariableIterator<'_> {}
+
+/// An iterator over user-defined types in a debug session.
+pub struct DwarfTypeIterator<'s> {
+    units: DwarfUnitIterator<'s>,
+    types: std::vec::IntoIter<TypeInfo<'s>>,
+    finished: bool,
+}
+
+impl<'s> Iterator for DwarfTypeIterator<'s> {
+    type Item = Result<TypeInfo<'s>, DwarfError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        loop {
+            if let Some(info) = self.types.next() {
+                return Some(Ok(info));
+            }
+
+            let unit = match self.units.next() {
+                Some(Ok(unit)) => unit,
+                Some(Err(error)) => return Some(Err(error)),
+                None => break,
+            };
+
+            self.types = match unit.types() {
+                Ok(types) => types.into_iter(),
+                Err(error) => return Some(Err(error)),
+            };
+        }
+
+        self.finished = true;
+        None
+    }
+}
+
+impl std::iter::FusedIterator for DwarfTypeIterator<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cu_index(header: &[u32], body: &[u8]) -> Vec<u8> {
+        let mut data = vec![5, 0, 0, 0];
+        for value in header {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(body);
+        data
+    }
+
+    #[test]
+    fn test_parse_cu_index() {
+        let mut body = Vec::new();
+        body.extend_from_slice(&0x1234_u64.to_le_bytes()); // hash
+        body.extend_from_slice(&1_u32.to_le_bytes()); // index
+        body.extend_from_slice(&1_u32.to_le_bytes()); // DW_SECT_INFO
+        body.extend_from_slice(&0x10_u32.to_le_bytes()); // offset
+        body.extend_from_slice(&0x20_u32.to_le_bytes()); // size
+
+        let data = cu_index(&[1, 1, 1], &body);
+        let entries = parse_cu_index(&data, Endian::Little).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].dwo_id, 0x1234);
+
+        let info = entries[0].info.unwrap();
+        assert_eq!((info.offset, info.size), (0x10, 0x20));
+
+        // The same index cut off in the middle of the size table.
+        assert!(parse_cu_index(&data[..data.len() - 2], Endian::Little).is_err());
+    }
+
+    #[test]
+    fn test_parse_cu_index_truncated() {
+        let data = cu_index(&[1, 1, 16], &[0; 8]);
+        assert!(parse_cu_index(&data, Endian::Little).is_err());
+
+        let data = cu_index(&[u32::MAX, u32::MAX, u32::MAX], &[]);
+        assert!(parse_cu_index(&data, Endian::Little).is_err());
+
+        assert!(parse_cu_index(&[5, 0], Endian::Little).is_err());
+
+        // A slot referring to a row past the unit count.
+        let mut body = Vec::new();
+        body.extend_from_slice(&0x1234_u64.to_le_bytes()); // hash
+        body.extend_from_slice(&2_u32.to_le_bytes()); // index
+        body.extend_from_slice(&1_u32.to_le_bytes()); // DW_SECT_INFO
+        body.extend_from_slice(&[0; 16]); // offsets and sizes
+        let data = cu_index(&[1, 1, 1], &body);
+        assert!(parse_cu_index(&data, Endian::Little).is_err());
+    }
+}
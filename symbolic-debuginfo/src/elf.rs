@@ -3,14 +3,15 @@
 use std::borrow::Cow;
 use std::error::Error;
 use std::fmt;
-use std::io::Cursor;
+use std::io::{self, Cursor, Write};
 
-use flate2::{Decompress, FlushDecompress};
+use flate2::{Crc, Decompress, FlushDecompress};
 use goblin::elf::compression_header::{CompressionHeader, ELFCOMPRESS_ZLIB};
 use goblin::{container::Ctx, elf, strtab};
+use lazycell::AtomicLazyCell;
 use thiserror::Error;
 
-use symbolic_common::{Arch, AsSelf, CodeId, DebugId, Uuid};
+use symbolic_common::{Arch, AsSelf, CodeId, DebugId, SelfCell, Uuid};
 
 use crate::base::*;
 use crate::dwarf::{Dwarf, DwarfDebugSession, DwarfError, DwarfSection, Endian};
@@ -19,6 +20,9 @@ use crate::private::Parse;
 const UUID_SIZE: usize = 16;
 const PAGE_SIZE: usize = 4096;
 
+/// The maximum decompressed size of MiniDebugInfo, which only carries a symbol table.
+const MAX_MINI_DEBUG_INFO_SIZE: usize = 64 * 1024 * 1024;
+
 const SHN_UNDEF: usize = elf::section_header::SHN_UNDEF as usize;
 const SHF_COMPRESSED: u64 = elf::section_header::SHF_COMPRESSED as u64;
 
@@ -55,10 +59,72 @@ impl ElfError {
     }
 }
 
+/// A reference to a separate debug file, declared in the `.gnu_debuglink` section.
+///
+/// The debug file is usually located next to the object, in a `.debug` subdirectory, or in a global
+/// debug directory such as `/usr/lib/debug`. Use [`matches`](struct.ElfDebugLink.html#method.matches)
+/// to verify that a candidate file is the one this object was linked to.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ElfDebugLink<'data> {
+    /// The file name of the debug file, without any directory components.
+    pub filename: Cow<'data, str>,
+    /// The CRC32 checksum of the entire debug file.
+    pub crc: u32,
+}
+
+impl ElfDebugLink<'_> {
+    /// Checks whether the contents of a candidate debug file match the declared checksum.
+    pub fn matches(&self, data: &[u8]) -> bool {
+        let mut crc = Crc::new();
+        crc.update(data);
+        crc.sum() == self.crc
+    }
+}
+
+/// A reference to a supplementary debug file, declared in the `.gnu_debugaltlink` section.
+///
+/// Supplementary files are created by `dwz` and contain debug information shared between multiple
+/// debug files. See [`ElfObject::set_supplementary_dwarf`] on how to use them.
+///
+/// [`ElfObject::set_supplementary_dwarf`]: struct.ElfObject.html#method.set_supplementary_dwarf
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ElfDebugAltLink<'data> {
+    /// The path to the supplementary file, which is either absolute or relative to the debug file.
+    pub path: Cow<'data, str>,
+    /// The GNU build identifier of the supplementary file.
+    pub build_id: &'data [u8],
+}
+
+impl ElfDebugAltLink<'_> {
+    /// The code identifier of the supplementary file.
+    ///
+    /// This can be compared to the [`code_id`](struct.ElfObject.html#method.code_id) of candidate
+    /// files.
+    pub fn code_id(&self) -> CodeId {
+        CodeId::from_binary(self.build_id)
+    }
+}
+
+/// Metadata of a Linux kernel module, declared in the `.modinfo` section.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ElfModuleInfo<'data> {
+    /// The name of the module, which is used to refer to it in kernel logs.
+    pub name: Option<&'data str>,
+    /// The version of the module, if declared by its author.
+    pub version: Option<&'data str>,
+    /// The version magic, which contains the release of the kernel the module was built for.
+    pub vermagic: Option<&'data str>,
+}
+
 /// Executable and Linkable Format, used for executables and libraries on Linux.
 pub struct ElfObject<'data> {
     elf: elf::Elf<'data>,
     data: &'data [u8],
+    split_dwarf: Vec<ElfObject<'data>>,
+    sup_dwarf: Option<Box<ElfObject<'data>>>,
+    mini_debug_info: AtomicLazyCell<Option<Box<SelfCell<Vec<u8>, ElfObject<'static>>>>>,
+    kind: ObjectKind,
+    section_addresses: Vec<u64>,
 }
 
 impl<'data> ElfObject<'data> {
@@ -72,9 +138,22 @@ impl<'data> ElfObject<'data> {
 
     /// Tries to parse an ELF object from the given slice.
     pub fn parse(data: &'data [u8]) -> Result<Self, ElfError> {
-        elf::Elf::parse(data)
-            .map(|elf| ElfObject { elf, data })
-            .map_err(ElfError::new)
+        let elf = elf::Elf::parse(data).map_err(ElfError::new)?;
+        let mut object = ElfObject {
+            elf,
+            data,
+            split_dwarf: Vec::new(),
+            sup_dwarf: None,
+            mini_debug_info: AtomicLazyCell::new(),
+            kind: ObjectKind::None,
+            section_addresses: Vec::new(),
+        };
+
+        // Detecting kernel images and modules requires scanning section headers, so the kind and
+        // the section layout are computed once upfront.
+        object.kind = object.detect_kind();
+        object.section_addresses = object.compute_section_addresses();
+        Ok(object)
     }
 
     /// The container file format, which is always `FileFormat::Elf`.
@@ -94,8 +173,12 @@ impl<'data> ElfObject<'data> {
     }
 
     /// The binary's soname, if any.
+    ///
+    /// For kernel modules, this is the module name declared in `.modinfo`.
     pub fn name(&self) -> Option<&'data str> {
-        self.elf.soname
+        self.elf
+            .soname
+            .or_else(|| self.module_info().and_then(|info| info.name))
     }
 
     /// The debug information identifier of an ELF object.
@@ -159,6 +242,11 @@ impl<'data> ElfObject<'data> {
 
     /// The kind of this object, as specified in the ELF header.
     pub fn kind(&self) -> ObjectKind {
+        self.kind
+    }
+
+    /// Determines the kind of this object from the ELF header and its sections.
+    fn detect_kind(&self) -> ObjectKind {
         let kind = match self.elf.header.e_type {
             goblin::elf::header::ET_NONE => ObjectKind::None,
             goblin::elf::header::ET_REL => ObjectKind::Relocatable,
@@ -168,6 +256,18 @@ impl<'data> ElfObject<'data> {
             _ => ObjectKind::Other,
         };
 
+        // Kernel modules are relocatable objects that are linked by the kernel. Unlike regular
+        // object files, they always contain module information.
+        if kind == ObjectKind::Relocatable && self.find_section_header(".modinfo").is_some() {
+            return ObjectKind::KernelModule;
+        }
+
+        // The kernel image is a static executable without interpreter. It can be told apart from
+        // a debug companion by its exception table, which is stripped from the latter.
+        if kind == ObjectKind::Executable && self.is_kernel_image() {
+            return ObjectKind::KernelImage;
+        }
+
         // When stripping debug information into a separate file with objcopy,
         // the eh_type field still reads ET_EXEC. However, the interpreter is
         // removed. Since an executable without interpreter does not make any
@@ -197,7 +297,21 @@ impl<'data> ElfObject<'data> {
     /// Addresses used in `symbols` or `debug_session` have already been rebased relative to that
     /// load address, so that the caller only has to deal with addresses relative to the actual
     /// start of the image.
+    ///
+    /// For the kernel image, this is the start of the kernel text (`_text`). With KASLR, the
+    /// kernel is relocated by a random offset at boot, so runtime addresses have to be rebased
+    /// onto the runtime address of `_text` instead.
     pub fn load_address(&self) -> u64 {
+        // The kernel image starts with segments that are not part of the kernel text, such as the
+        // real mode setup on some architectures. Use the first executable segment instead.
+        if self.kind() == ObjectKind::KernelImage {
+            for phdr in &self.elf.program_headers {
+                if phdr.p_type == elf::program_header::PT_LOAD && phdr.is_executable() {
+                    return phdr.p_vaddr;
+                }
+            }
+        }
+
         // For non-PIC executables (e_type == ET_EXEC), the load address is
         // the start address of the first PT_LOAD segment.  (ELF requires
         // the segments to be sorted by load address.)  For PIC executables
@@ -213,17 +327,51 @@ impl<'data> ElfObject<'data> {
     }
 
     /// Determines whether this object exposes a public symbol table.
+    ///
+    /// This includes the symbol table of an embedded MiniDebugInfo object.
     pub fn has_symbols(&self) -> bool {
         !self.elf.syms.is_empty()
+            || self
+                .mini_debug_info()
+                .map_or(false, |mini| !mini.elf.syms.is_empty())
     }
 
     /// Returns an iterator over symbols in the public symbol table.
+    ///
+    /// If the object carries MiniDebugInfo in the `.gnu_debugdata` section, the symbols of the
+    /// embedded object are yielded after the symbols of this object.
     pub fn symbols(&self) -> ElfSymbolIterator<'data, '_> {
+        let load_addr = self.load_address();
+
+        // MiniDebugInfo is stripped from a debug file of the same image, so its symbols use the
+        // same addresses as this object and can be rebased with our load address.
+        let mini_debug_info = self.mini_debug_info().map(|mini| {
+            let elf = &mini.elf;
+            Box::new(ElfSymbolIterator {
+                symbols: elf.syms.iter(),
+                strtab: &elf.strtab,
+                sections: &elf.section_headers,
+                section_addrs: &[],
+                load_addr,
+                mini_debug_info: None,
+            })
+        });
+
+        // Sections of kernel modules are laid out consecutively to give all symbols unique
+        // addresses, the same way as in the debug session.
+        let section_addrs = if self.kind() == ObjectKind::KernelModule {
+            self.section_addresses()
+        } else {
+            &[]
+        };
+
         ElfSymbolIterator {
             symbols: self.elf.syms.iter(),
             strtab: &self.elf.strtab,
             sections: &self.elf.section_headers,
-            load_addr: self.load_address(),
+            section_addrs,
+            load_addr,
+            mini_debug_info,
         }
     }
 
@@ -254,14 +402,107 @@ impl<'data> ElfObject<'data> {
         DwarfDebugSession::parse(self, symbols, self.load_address() as i64, self.kind())
     }
 
+    /// Attaches a split DWARF object (`.dwo` or `.dwp` file) to this object.
+    ///
+    /// When compiling with `-gsplit-dwarf`, the compilation units in this object are only
+    /// skeletons that point to split units via their `DW_AT_dwo_name` and DWO identifier. Attach
+    /// either the packaged `.dwp` file or all `.dwo` files to resolve functions, line records and
+    /// inlinees in the [`debug_session`](struct.ElfObject.html#method.debug_session). Skeleton
+    /// units without a matching split unit are processed as before.
+    pub fn add_split_dwarf(&mut self, split: ElfObject<'data>) {
+        self.split_dwarf.push(split);
+    }
+
+    /// Attaches the supplementary object file referenced by `.gnu_debugaltlink`.
+    ///
+    /// Debug files post-processed with `dwz` share debugging information entries and strings
+    /// with other objects through a supplementary file. Without it, names of functions and
+    /// inlinees referring to this file cannot be resolved in the
+    /// [`debug_session`](struct.ElfObject.html#method.debug_session).
+    pub fn set_supplementary_dwarf(&mut self, sup: ElfObject<'data>) {
+        self.sup_dwarf = Some(Box::new(sup));
+    }
+
+    /// Returns metadata of a kernel module from its `.modinfo` section.
+    ///
+    /// The section contains NUL-terminated `key=value` pairs. Returns `None` if this object is not
+    /// a kernel module.
+    pub fn module_info(&self) -> Option<ElfModuleInfo<'data>> {
+        let header = self.find_section_header(".modinfo")?;
+        let offset = header.sh_offset as usize;
+        let data = self.data.get(offset..offset + header.sh_size as usize)?;
+
+        let mut info = ElfModuleInfo::default();
+        for entry in data.split(|&b| b == 0) {
+            let entry = match std::str::from_utf8(entry) {
+                Ok(entry) => entry,
+                Err(_) => continue,
+            };
+
+            let mut parts = entry.splitn(2, '=');
+            match (parts.next(), parts.next()) {
+                (Some("name"), Some(value)) => info.name = Some(value),
+                (Some("version"), Some(value)) => info.version = Some(value),
+                (Some("vermagic"), Some(value)) => info.vermagic = Some(value.trim_end()),
+                _ => {}
+            }
+        }
+
+        Some(info)
+    }
+
+    /// Returns the address of a section, given by its full name including the leading dot.
+    ///
+    /// In kernel modules, executable sections such as `.text`, `.init.text` and `.exit.text` are
+    /// laid out consecutively, so that their functions have unique addresses in `symbols` and the
+    /// `debug_session`. Kernel logs specify addresses in modules relative to these sections, which
+    /// can be converted by adding the section address. For all other objects, this is the address
+    /// declared in the section header.
+    pub fn section_address(&self, name: &str) -> Option<u64> {
+        let addresses = if self.kind() == ObjectKind::KernelModule {
+            self.section_addresses()
+        } else {
+            &[]
+        };
+
+        for (index, header) in self.elf.section_headers.iter().enumerate() {
+            if self
+                .elf
+                .shdr_strtab
+                .get(header.sh_name)
+                .and_then(Result::ok)
+                == Some(name)
+            {
+                return Some(addresses.get(index).copied().unwrap_or(header.sh_addr));
+            }
+        }
+
+        None
+    }
+
     /// Determines whether this object contains stack unwinding information.
+    ///
+    /// Besides DWARF call frame information, this includes ARM exception handling tables in
+    /// `.ARM.exidx`, which are used by 32-bit ARM binaries instead of `.eh_frame`.
     pub fn has_unwind_info(&self) -> bool {
-        self.has_section("eh_frame") || self.has_section("debug_frame")
+        self.has_section("eh_frame")
+            || self.has_section("debug_frame")
+            || self.has_section("ARM.exidx")
     }
 
     /// Determines whether this object contains embedded source.
+    ///
+    /// This is the case for DWARF 5 line programs with sources embedded by `clang -gembed-source`.
+    /// Checking this requires reading the line programs of all compilation units.
     pub fn has_sources(&self) -> bool {
-        false
+        if !self.has_debug_info() {
+            return false;
+        }
+
+        // Embedded sources do not depend on function names, so skip loading the symbol table.
+        let symbols = SymbolMap::default();
+        DwarfDebugSession::parse(self, symbols, self.load_address() as i64, self.kind())
+            .map_or(false, |session| session.has_sources())
     }
 
     /// Returns the raw data of the ELF file.
@@ -304,12 +545,56 @@ impl<'data> ElfObject<'data> {
         Some(decompressed)
     }
 
+    /// Returns the MiniDebugInfo object in the `.gnu_debugdata` section, if any.
+    ///
+    /// The object is decompressed and parsed on first access. If multiple threads access it
+    /// concurrently, it may be parsed more than once, but only the first result is stored.
+    fn mini_debug_info(&self) -> Option<&ElfObject<'_>> {
+        if !self.mini_debug_info.filled() {
+            self.mini_debug_info.fill(self.parse_mini_debug_info()).ok();
+
+            // Another thread may still be storing its result, which takes only a moment.
+            while !self.mini_debug_info.filled() {
+                std::thread::yield_now();
+            }
+        }
+
+        self.mini_debug_info
+            .borrow()
+            .and_then(Option::as_ref)
+            .map(|mini| mini.get())
+    }
+
+    /// Decompresses and parses the MiniDebugInfo object in the `.gnu_debugdata` section.
+    ///
+    /// MiniDebugInfo is an xz-compressed ELF object that carries the symbol table of an otherwise
+    /// stripped binary. Since these symbols are supplementary, errors are swallowed silently. Only
+    /// the symbol table of the embedded object is used, so its own MiniDebugInfo is ignored.
+    fn parse_mini_debug_info(&self) -> Option<Box<SelfCell<Vec<u8>, ElfObject<'static>>>> {
+        let (_, _, section) = self.find_section("gnu_debugdata")?;
+
+        let mut writer = LimitedWriter::new(MAX_MINI_DEBUG_INFO_SIZE);
+        lzma_rs::xz_decompress(&mut section.data.as_ref(), &mut writer).ok()?;
+
+        SelfCell::try_new(writer.into_inner(), |data| {
+            ElfObject::parse(unsafe { &*data })
+        })
+        .ok()
+        .map(Box::new)
+    }
+
     /// Locates and reads a section in an ELF binary.
-    fn find_section(&self, name: &str) -> Option<(bool, DwarfSection<'data>)> {
-        for header in &self.elf.section_headers {
+    ///
+    /// Returns the index of the section header, whether the section is compressed, and the section.
+    fn find_section(&self, name: &str) -> Option<(usize, bool, DwarfSection<'data>)> {
+        for (index, header) in self.elf.section_headers.iter().enumerate() {
             const SHT_MIPS_DWARF: u32 = 0x7000_001e;
+            const SHT_ARM_EXIDX: u32 = 0x7000_0001;
             const SHT_PROGBITS: u32 = elf::section_header::SHT_PROGBITS;
-            if !matches!(header.sh_type, SHT_PROGBITS | SHT_MIPS_DWARF) {
+            if !matches!(
+                header.sh_type,
+                SHT_PROGBITS | SHT_MIPS_DWARF | SHT_ARM_EXIDX
+            ) {
                 continue;
             }
 
@@ -347,13 +632,194 @@ impl<'data> ElfObject<'data> {
                     align: header.sh_addralign,
                 };
 
-                return Some((compressed, section));
+                return Some((index, compressed, section));
             }
         }
 
         None
     }
 
+    /// Returns the reference to a separate debug file, if declared in `.gnu_debuglink`.
+    ///
+    /// The section contains a NUL-terminated file name, followed by padding to a 4-byte boundary
+    /// and the CRC32 checksum of the debug file in the byte order of this object.
+    pub fn debug_link(&self) -> Option<ElfDebugLink<'data>> {
+        // Debug links are never compressed, so their data is always borrowed from the file.
+        let data = match self.find_section("gnu_debuglink")? {
+            (
+                _,
+                false,
+                DwarfSection {
+                    data: Cow::Borrowed(data),
+                    ..
+                },
+            ) => data,
+            _ => return None,
+        };
+
+        let nul = data.iter().position(|&b| b == 0)?;
+        let crc_offset = (nul + 4) & !3;
+        let crc_bytes = data.get(crc_offset..crc_offset + 4)?;
+
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(crc_bytes);
+        let crc = if self.elf.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        };
+
+        Some(ElfDebugLink {
+            filename: String::from_utf8_lossy(&data[..nul]),
+            crc,
+        })
+    }
+
+    /// Returns the reference to a supplementary debug file, if declared in `.gnu_debugaltlink`.
+    ///
+    /// The section contains a NUL-terminated path, followed by the build identifier of the
+    /// supplementary file.
+    pub fn debug_alt_link(&self) -> Option<ElfDebugAltLink<'data>> {
+        let data = match self.find_section("gnu_debugaltlink")? {
+            (
+                _,
+                false,
+                DwarfSection {
+                    data: Cow::Borrowed(data),
+                    ..
+                },
+            ) => data,
+            _ => return None,
+        };
+
+        let nul = data.iter().position(|&b| b == 0)?;
+        let build_id = &data[nul + 1..];
+        if build_id.is_empty() {
+            return None;
+        }
+
+        Some(ElfDebugAltLink {
+            path: String::from_utf8_lossy(&data[..nul]),
+            build_id,
+        })
+    }
+
+    /// Locates a section header by its full name, including the leading dot.
+    ///
+    /// As opposed to `find_section`, this also returns sections without data.
+    fn find_section_header(&self, name: &str) -> Option<&elf::SectionHeader> {
+        self.elf.section_headers.iter().find(|header| {
+            self.elf
+                .shdr_strtab
+                .get(header.sh_name)
+                .and_then(Result::ok)
+                == Some(name)
+        })
+    }
+
+    /// Determines whether this object is the Linux kernel image.
+    fn is_kernel_image(&self) -> bool {
+        self.elf.interpreter.is_none()
+            && self
+                .find_section_header("__ex_table")
+                .map_or(false, |header| {
+                    header.sh_type != elf::section_header::SHT_NOBITS
+                })
+    }
+
+    /// Computes the addresses of all sections in a relocatable object.
+    ///
+    /// Sections in relocatable objects have not been placed by a linker, so they all start at
+    /// address zero. In kernel modules, executable sections are laid out consecutively in the order
+    /// of their headers and honoring their alignment, which gives all functions unique addresses.
+    /// All other sections keep the address declared in their header.
+    fn section_addresses(&self) -> &[u64] {
+        &self.section_addresses
+    }
+
+    /// Lays out the sections of this object, see `section_addresses`.
+    fn compute_section_addresses(&self) -> Vec<u64> {
+        let is_module = self.kind() == ObjectKind::KernelModule;
+        let mut next_address = 0u64;
+
+        self.elf
+            .section_headers
+            .iter()
+            .map(|header| {
+                if !is_module || !header.is_alloc() || !header.is_executable() {
+                    return header.sh_addr;
+                }
+
+                let align = header.sh_addralign.max(1);
+                let address = next_address.saturating_add(align - 1) / align * align;
+                next_address = address.saturating_add(header.sh_size);
+                address
+            })
+            .collect()
+    }
+
+    /// Applies relocations to the data of a section in a relocatable object (`ET_REL`).
+    ///
+    /// In object files and kernel modules, references from debug information to code and other
+    /// sections are only resolved by the linker, so the section data contains zeros or addends
+    /// until the entries of the matching relocation section (such as `.rela.debug_info`) are
+    /// applied. The relocated data is owned by the returned section, so a debug session applies
+    /// relocations only once when loading its sections.
+    ///
+    /// Absolute and PC-relative data relocations are supported, see `relocation_type`. Other
+    /// relocation types do not occur in debug sections and are skipped.
+    fn relocate_section(&self, index: usize, data: &mut Cow<'data, [u8]>) {
+        let section_addrs = self.section_addresses();
+        let base_addr = section_addrs.get(index).copied().unwrap_or(0);
+
+        for (reloc_index, relocs) in &self.elf.shdr_relocs {
+            match self.elf.section_headers.get(*reloc_index) {
+                Some(header) if header.sh_info as usize == index => (),
+                _ => continue,
+            }
+
+            let data = data.to_mut();
+            for reloc in relocs.iter() {
+                let reloc_type = match relocation_type(self.elf.header.e_machine, reloc.r_type) {
+                    Some(reloc_type) => reloc_type,
+                    None => continue,
+                };
+
+                let symbol = match self.elf.syms.get(reloc.r_sym) {
+                    Some(symbol) => symbol,
+                    None => continue,
+                };
+
+                let offset = reloc.r_offset as usize;
+                let target = match offset
+                    .checked_add(reloc_type.size)
+                    .and_then(|end| data.get_mut(offset..end))
+                {
+                    Some(target) => target,
+                    None => continue,
+                };
+
+                // REL relocations store their addend in the location to be relocated.
+                let addend = match reloc.r_addend {
+                    Some(addend) => addend as u64,
+                    None => read_uint(target, self.elf.little_endian),
+                };
+
+                let section_addr = section_addrs.get(symbol.st_shndx).copied().unwrap_or(0);
+                let mut value = section_addr
+                    .wrapping_add(symbol.st_value)
+                    .wrapping_add(addend);
+
+                // PC-relative relocations are computed relative to the relocated location.
+                if reloc_type.pc_relative {
+                    value = value.wrapping_sub(base_addr.wrapping_add(reloc.r_offset));
+                }
+
+                write_uint(target, value, self.elf.little_endian);
+            }
+        }
+    }
+
     /// Searches for a GNU build identifier node in an ELF file.
     ///
     /// Depending on the compiler and linker, the build ID can be declared in a
@@ -520,20 +986,137 @@ impl<'data> Dwarf<'data> for ElfObject<'data> {
     }
 
     fn raw_section(&self, name: &str) -> Option<DwarfSection<'data>> {
-        let (_, section) = self.find_section(name)?;
+        let (_, _, section) = self.find_section(name)?;
         Some(section)
     }
 
     fn section(&self, name: &str) -> Option<DwarfSection<'data>> {
-        let (compressed, mut section) = self.find_section(name)?;
+        let (index, compressed, mut section) = self.find_section(name)?;
 
         if compressed {
             let decompressed = self.decompress_section(&section.data)?;
             section.data = Cow::Owned(decompressed);
         }
 
+        if self.elf.header.e_type == elf::header::ET_REL {
+            self.relocate_section(index, &mut section.data);
+        }
+
         Some(section)
     }
+
+    fn split_dwarf(&self) -> Vec<&dyn Dwarf<'data>> {
+        self.split_dwarf
+            .iter()
+            .map(|split| split as &dyn Dwarf<'data>)
+            .collect()
+    }
+
+    fn supplementary_dwarf(&self) -> Option<&dyn Dwarf<'data>> {
+        self.sup_dwarf
+            .as_deref()
+            .map(|sup| sup as &dyn Dwarf<'data>)
+    }
+}
+
+/// A relocation type supported in debug sections of relocatable objects.
+struct RelocationType {
+    /// The number of bytes patched by the relocation.
+    size: usize,
+    /// Whether the value is relative to the address of the relocated location.
+    pc_relative: bool,
+}
+
+/// Returns how a relocation is applied, if the relocation type is supported.
+///
+/// Debug information uses absolute relocations to refer to code and other sections. PC-relative
+/// relocations occur in call frame information, such as `.eh_frame`. Other relocation types, such
+/// as those referring to the GOT or thread-local storage, are not supported.
+fn relocation_type(machine: u16, r_type: u32) -> Option<RelocationType> {
+    use goblin::elf::header::{EM_386, EM_AARCH64, EM_ARM, EM_X86_64};
+    use goblin::elf::reloc::*;
+
+    let (size, pc_relative) = match (machine, r_type) {
+        (EM_X86_64, R_X86_64_64) => (8, false),
+        (EM_X86_64, R_X86_64_32) | (EM_X86_64, R_X86_64_32S) => (4, false),
+        (EM_X86_64, R_X86_64_PC64) => (8, true),
+        (EM_X86_64, R_X86_64_PC32) => (4, true),
+        (EM_386, R_386_32) => (4, false),
+        (EM_386, R_386_PC32) => (4, true),
+        (EM_AARCH64, R_AARCH64_ABS64) => (8, false),
+        (EM_AARCH64, R_AARCH64_ABS32) => (4, false),
+        (EM_AARCH64, R_AARCH64_PREL64) => (8, true),
+        (EM_AARCH64, R_AARCH64_PREL32) => (4, true),
+        (EM_ARM, R_ARM_ABS32) => (4, false),
+        (EM_ARM, R_ARM_REL32) => (4, true),
+        _ => return None,
+    };
+
+    Some(RelocationType { size, pc_relative })
+}
+
+/// Reads a 4 or 8 byte unsigned integer in the given byte order.
+fn read_uint(data: &[u8], little_endian: bool) -> u64 {
+    let mut bytes = [0; 8];
+    match (data.len(), little_endian) {
+        (4, true) | (8, true) => bytes[..data.len()].copy_from_slice(data),
+        (4, false) | (8, false) => bytes[8 - data.len()..].copy_from_slice(data),
+        _ => return 0,
+    }
+
+    if little_endian {
+        u64::from_le_bytes(bytes)
+    } else {
+        u64::from_be_bytes(bytes)
+    }
+}
+
+/// Writes a 4 or 8 byte unsigned integer in the given byte order, truncating the value if needed.
+fn write_uint(data: &mut [u8], value: u64, little_endian: bool) {
+    match (data.len(), little_endian) {
+        (4, true) => data.copy_from_slice(&(value as u32).to_le_bytes()),
+        (4, false) => data.copy_from_slice(&(value as u32).to_be_bytes()),
+        (8, true) => data.copy_from_slice(&value.to_le_bytes()),
+        (8, false) => data.copy_from_slice(&value.to_be_bytes()),
+        _ => {}
+    }
+}
+
+/// A writer into a buffer that fails once the buffer would exceed a maximum size.
+struct LimitedWriter {
+    buffer: Vec<u8>,
+    limit: usize,
+}
+
+impl LimitedWriter {
+    fn new(limit: usize) -> Self {
+        LimitedWriter {
+            buffer: Vec::new(),
+            limit,
+        }
+    }
+
+    fn into_inner(self) -> Vec<u8> {
+        self.buffer
+    }
+}
+
+impl Write for LimitedWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.len() > self.limit - self.buffer.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "decompressed data exceeds size limit",
+            ));
+        }
+
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
 }
 
 /// An iterator over symbols in the ELF file.
@@ -543,7 +1126,9 @@ pub struct ElfSymbolIterator<'data, 'object> {
     symbols: elf::sym::SymIterator<'data>,
     strtab: &'object strtab::Strtab<'data>,
     sections: &'object [elf::SectionHeader],
+    section_addrs: &'object [u64],
     load_addr: u64,
+    mini_debug_info: Option<Box<ElfSymbolIterator<'object, 'object>>>,
 }
 
 impl<'data, 'object> Iterator for ElfSymbolIterator<'data, 'object> {
@@ -578,13 +1163,30 @@ impl<'data, 'object> Iterator for ElfSymbolIterator<'data, 'object> {
                 .and_then(Result::ok)
                 .map(Cow::Borrowed);
 
+            let section_addr = self
+                .section_addrs
+                .get(symbol.st_shndx)
+                .copied()
+                .unwrap_or(0);
+
             return Some(Symbol {
                 name,
-                address: symbol.st_value - self.load_addr,
+                address: section_addr + symbol.st_value - self.load_addr,
                 size: symbol.st_size,
+                multiple: false,
+                parameter_size: None,
             });
         }
 
-        None
+        // Names in the MiniDebugInfo object are only borrowed from this iterator, so they need to
+        // be copied.
+        let symbol = self.mini_debug_info.as_mut()?.next()?;
+        Some(Symbol {
+            name: symbol.name.map(|name| Cow::Owned(name.into_owned())),
+            address: symbol.address,
+            size: symbol.size,
+            multiple: symbol.multiple,
+            parameter_size: symbol.parameter_size,
+        })
     }
 }
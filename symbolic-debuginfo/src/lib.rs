@@ -45,7 +45,10 @@ pub mod elf;
 pub mod macho;
 pub mod pdb;
 pub mod pe;
+pub mod ppdb;
 pub mod sourcebundle;
+pub mod sourcelink;
+pub mod srcsrv;
 pub mod wasm;
 
 pub use crate::base::*;
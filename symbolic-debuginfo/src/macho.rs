@@ -4,6 +4,7 @@ use std::borrow::Cow;
 use std::error::Error;
 use std::fmt;
 use std::io::Cursor;
+use std::sync::Arc;
 
 use goblin::mach;
 use smallvec::SmallVec;
@@ -15,6 +16,10 @@ use crate::base::*;
 use crate::dwarf::{Dwarf, DwarfDebugSession, DwarfError, DwarfSection, Endian};
 use crate::private::{MonoArchive, MonoArchiveObjects, Parse};
 
+mod bcsymbolmap;
+
+pub use bcsymbolmap::*;
+
 /// An error when dealing with [`MachObject`](struct.MachObject.html).
 #[derive(Debug, Error)]
 #[error("invalid MachO file")]
@@ -38,6 +43,7 @@ impl MachError {
 pub struct MachObject<'d> {
     macho: mach::MachO<'d>,
     data: &'d [u8],
+    bcsymbolmap: Option<Arc<BcSymbolMap<'d>>>,
 }
 
 impl<'d> MachObject<'d> {
@@ -52,10 +58,26 @@ impl<'d> MachObject<'d> {
     /// Tries to parse a MachO from the given slice.
     pub fn parse(data: &'d [u8]) -> Result<Self, MachError> {
         mach::MachO::parse(data, 0)
-            .map(|macho| MachObject { macho, data })
+            .map(|macho| MachObject {
+                macho,
+                data,
+                bcsymbolmap: None,
+            })
             .map_err(MachError::new)
     }
 
+    /// Loads a [`BcSymbolMap`] to resolve hidden names in this object.
+    ///
+    /// Bitcode-enabled builds replace symbol names and paths with placeholders of the form
+    /// `__hidden#N_`. Once a symbol map is loaded, these placeholders are resolved by
+    /// [`symbols`](struct.MachObject.html#method.symbols) and in the
+    /// [`debug_session`](struct.MachObject.html#method.debug_session).
+    ///
+    /// [`BcSymbolMap`]: bcsymbolmap/struct.BcSymbolMap.html
+    pub fn load_symbolmap(&mut self, symbolmap: BcSymbolMap<'d>) {
+        self.bcsymbolmap = Some(Arc::new(symbolmap));
+    }
+
     /// The container file format, which is always `FileFormat::MachO`.
     pub fn file_format(&self) -> FileFormat {
         FileFormat::MachO
@@ -169,12 +191,19 @@ impl<'d> MachObject<'d> {
     }
 
     /// Returns an iterator over symbols in the public symbol table.
+    ///
+    /// If the object declares function starts in `LC_FUNCTION_STARTS`, the sizes of symbols are
+    /// bounded by the start of the next function. Functions without an entry in the symbol table,
+    /// which is common in stripped binaries, are returned as symbols without a name after all
+    /// symbols from the symbol table. This prevents unnamed functions from being attributed to the
+    /// preceding symbol.
     pub fn symbols(&self) -> MachOSymbolIterator<'d> {
         // Cache indices of code sections. These are either "__text" or "__stubs", always located in
         // the "__TEXT" segment. It looks like each of those sections only occurs once, but to be
         // safe they are collected into a vector.
         let mut sections = SmallVec::new();
         let mut section_index = 0;
+        let mut text_end = None;
 
         'outer: for segment in &self.macho.segments {
             if segment.name().ok() != Some("__TEXT") {
@@ -191,7 +220,11 @@ impl<'d> MachObject<'d> {
                 };
 
                 match section.name() {
-                    Ok("__text") | Ok("__stubs") => sections.push(section_index),
+                    Ok("__text") => {
+                        sections.push(section_index);
+                        text_end = Some(section.addr + section.size);
+                    }
+                    Ok("__stubs") => sections.push(section_index),
                     _ => (),
                 }
 
@@ -199,13 +232,44 @@ impl<'d> MachObject<'d> {
             }
         }
 
+        let vmaddr = self.load_address();
+        let function_starts = self.function_starts().collect::<Vec<_>>();
+
         MachOSymbolIterator {
             symbols: self.macho.symbols(),
             sections,
-            vmaddr: self.load_address(),
+            vmaddr,
+            text_end: text_end.map_or(0, |end| end.saturating_sub(vmaddr)),
+            named: vec![false; function_starts.len()],
+            function_starts,
+            next_placeholder: 0,
+            symbolmap: self.bcsymbolmap.clone(),
         }
     }
 
+    /// Returns an iterator over the start addresses of functions declared in `LC_FUNCTION_STARTS`.
+    ///
+    /// The linker records the start of every function in the `__TEXT` segment, including functions
+    /// that do not have an entry in the symbol table. Addresses are relative to the load address
+    /// and returned in ascending order. If the load command is missing, the iterator is empty.
+    pub fn function_starts(&self) -> MachOFunctionStartsIterator<'d> {
+        let data = self
+            .macho
+            .load_commands
+            .iter()
+            .find_map(|cmd| match cmd.command {
+                mach::load_command::CommandVariant::FunctionStarts(ref command) => {
+                    let start = command.dataoff as usize;
+                    let end = start.checked_add(command.datasize as usize)?;
+                    self.data.get(start..end)
+                }
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        MachOFunctionStartsIterator { data, address: 0 }
+    }
+
     /// Returns an ordered map of symbols in the symbol table.
     pub fn symbol_map(&self) -> SymbolMap<'d> {
         self.symbols().collect()
@@ -230,17 +294,32 @@ impl<'d> MachObject<'d> {
     /// [`has_debug_info`](struct.MachObject.html#method.has_debug_info).
     pub fn debug_session(&self) -> Result<DwarfDebugSession<'d>, DwarfError> {
         let symbols = self.symbol_map();
-        DwarfDebugSession::parse(self, symbols, self.load_address() as i64, self.kind())
+        let mut session =
+            DwarfDebugSession::parse(self, symbols, self.load_address() as i64, self.kind())?;
+        session.set_symbolmap(self.bcsymbolmap.clone());
+        Ok(session)
     }
 
     /// Determines whether this object contains stack unwinding information.
     pub fn has_unwind_info(&self) -> bool {
-        self.has_section("eh_frame") || self.has_section("debug_frame")
+        self.has_section("eh_frame")
+            || self.has_section("debug_frame")
+            || self.has_section("unwind_info")
     }
 
     /// Determines whether this object contains embedded source.
+    ///
+    /// This is the case for DWARF 5 line programs with sources embedded by `clang -gembed-source`.
+    /// Checking this requires reading the line programs of all compilation units.
     pub fn has_sources(&self) -> bool {
-        false
+        if !self.has_debug_info() {
+            return false;
+        }
+
+        // Embedded sources do not depend on function names, so skip loading the symbol table.
+        let symbols = SymbolMap::default();
+        DwarfDebugSession::parse(self, symbols, self.load_address() as i64, self.kind())
+            .map_or(false, |session| session.has_sources())
     }
 
     /// Returns the raw data of the ELF file.
@@ -252,8 +331,11 @@ impl<'d> MachObject<'d> {
     ///
     /// This is an indication that BCSymbolMaps are needed to symbolicate crash reports correctly.
     pub fn requires_symbolmap(&self) -> bool {
-        self.symbols()
-            .any(|s| s.name().map_or(false, |n| n.starts_with("__?hidden#")))
+        self.symbols().any(|s| {
+            s.name().map_or(false, |n| {
+                n.starts_with("__?hidden#") || n.starts_with("__hidden#")
+            })
+        })
     }
 }
 
@@ -389,6 +471,55 @@ impl<'data> Dwarf<'data> for MachObject<'data> {
     }
 }
 
+/// An iterator over function start addresses in the MachO file.
+///
+/// Returned by [`MachObject::function_starts`](struct.MachObject.html#method.function_starts).
+pub struct MachOFunctionStartsIterator<'data> {
+    data: &'data [u8],
+    address: u64,
+}
+
+impl<'data> Iterator for MachOFunctionStartsIterator<'data> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Function starts are encoded as ULEB128 deltas to the previous function, starting at the
+        // beginning of the `__TEXT` segment. The list is terminated by a zero delta.
+        let mut delta = 0u64;
+        let mut shift = 0;
+
+        loop {
+            let (&byte, rest) = self.data.split_first()?;
+            self.data = rest;
+
+            // Reject groups with bits that do not fit into 64 bits, which indicates corrupt data.
+            let bits = u64::from(byte & 0x7f);
+            match bits.checked_shl(shift) {
+                Some(shifted) if shifted >> shift == bits => delta |= shifted,
+                _ => {
+                    self.data = &[];
+                    return None;
+                }
+            }
+            shift += 7;
+
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+
+        if delta == 0 {
+            self.data = &[];
+            return None;
+        }
+
+        self.address = self.address.checked_add(delta)?;
+        Some(self.address)
+    }
+}
+
+impl std::iter::FusedIterator for MachOFunctionStartsIterator<'_> {}
+
 /// An iterator over symbols in the MachO file.
 ///
 /// Returned by [`MachObject::symbols`](struct.MachObject.html#method.symbols).
@@ -396,6 +527,70 @@ pub struct MachOSymbolIterator<'data> {
     symbols: mach::symbols::SymbolIterator<'data>,
     sections: SmallVec<[usize; 2]>,
     vmaddr: u64,
+    /// The end of the `__text` section relative to `vmaddr`, which bounds the last function.
+    text_end: u64,
+    /// Sorted function start addresses relative to `vmaddr`.
+    function_starts: Vec<u64>,
+    /// Whether a symbol has been returned for the function start at the same index.
+    named: Vec<bool>,
+    /// Index of the next function start to check for a placeholder symbol.
+    next_placeholder: usize,
+    /// An optional symbol map to resolve hidden symbol names.
+    symbolmap: Option<Arc<BcSymbolMap<'data>>>,
+}
+
+impl<'data> MachOSymbolIterator<'data> {
+    /// Returns the size of the function starting at the given index of `function_starts`.
+    fn function_size(&self, index: usize) -> u64 {
+        let start = self.function_starts[index];
+        let end = match self.function_starts.get(index + 1) {
+            Some(&next) => next,
+            None => self.text_end,
+        };
+
+        end.saturating_sub(start)
+    }
+
+    /// Computes the size of a symbol from the next function start.
+    ///
+    /// Returns `0` if the size is unknown.
+    fn symbol_size(&mut self, address: u64) -> u64 {
+        if self.function_starts.is_empty() {
+            return 0;
+        }
+
+        match self.function_starts.binary_search(&address) {
+            Ok(index) => {
+                self.named[index] = true;
+                self.function_size(index)
+            }
+            Err(index) => match self.function_starts.get(index) {
+                Some(&next) => next - address,
+                None => self.text_end.saturating_sub(address),
+            },
+        }
+    }
+
+    /// Returns a symbol without a name for the next function start not covered by the symbol
+    /// table.
+    fn next_placeholder(&mut self) -> Option<Symbol<'data>> {
+        while self.next_placeholder < self.function_starts.len() {
+            let index = self.next_placeholder;
+            self.next_placeholder += 1;
+
+            if !self.named[index] {
+                return Some(Symbol {
+                    name: None,
+                    address: self.function_starts[index],
+                    size: self.function_size(index),
+                    multiple: false,
+                    parameter_size: None,
+                });
+            }
+        }
+
+        None
+    }
 }
 
 impl<'data> Iterator for MachOSymbolIterator<'data> {
@@ -432,14 +627,23 @@ impl<'data> Iterator for MachOSymbolIterator<'data> {
                 name = &name[1..];
             }
 
+            // Resolve hidden names of bitcode builds if a symbol map is available.
+            if let Some(ref symbolmap) = self.symbolmap {
+                name = symbolmap.resolve(name);
+            }
+
+            let address = nlist.n_value - self.vmaddr;
             return Some(Symbol {
                 name: Some(Cow::Borrowed(name)),
-                address: nlist.n_value - self.vmaddr,
-                size: 0, // Computed in `SymbolMap`
+                address,
+                // Computed in `SymbolMap` if there are no function starts
+                size: self.symbol_size(address),
+                multiple: false,
+                parameter_size: None,
             });
         }
 
-        None
+        self.next_placeholder()
     }
 }
 
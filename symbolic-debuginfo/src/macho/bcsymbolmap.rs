@@ -0,0 +1,305 @@
+//! Support for BCSymbolMaps, which resolve hidden symbols in bitcode-enabled builds.
+//!
+//! When an application is compiled with bitcode and uploaded to the App Store without symbols, the
+//! final binary and its dSYM contain obfuscated names such as `__hidden#123_` in place of symbol
+//! names, function names and source file paths. The original names are stored in a separate text
+//! file with a `.bcsymbolmap` extension, which is named after the UUID of the object:
+//!
+//! ```txt
+//! BCSymbolMap Version: 2.0
+//! _main
+//! -[SentryClient captureEvent:]
+//! /Users/sentry/project/main.m
+//! ```
+//!
+//! After the header line, every line contains one name. A placeholder `__hidden#N_` refers to the
+//! name in line `N`, counting from zero after the header.
+//!
+//! A [`BcSymbolMap`] can be loaded into a [`MachObject`] or a [`DwarfDebugSession`] to resolve
+//! hidden names while reading symbols and debug information.
+//!
+//! [`BcSymbolMap`]: struct.BcSymbolMap.html
+//! [`MachObject`]: ../struct.MachObject.html
+//! [`DwarfDebugSession`]: ../../dwarf/struct.DwarfDebugSession.html
+
+use std::error::Error;
+use std::fmt;
+
+use thiserror::Error;
+
+use symbolic_common::Name;
+
+use crate::base::{FileEntry, FileInfo, Function};
+
+/// The start of the header line of a BCSymbolMap.
+const BC_SYMBOL_MAP_HEADER: &str = "BCSymbolMap Version: ";
+
+/// The prefix of hidden names that need to be resolved with a BCSymbolMap.
+const HIDDEN_PREFIX: &str = "__hidden#";
+
+/// The suffix of hidden names that need to be resolved with a BCSymbolMap.
+const HIDDEN_SUFFIX: &str = "_";
+
+/// The error type for [`BcSymbolMapError`].
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BcSymbolMapErrorKind {
+    /// The BCSymbolMap header line is missing or malformed.
+    InvalidHeader,
+
+    /// The BCSymbolMap declares a version that is not supported.
+    UnsupportedVersion,
+
+    /// The BCSymbolMap contains invalid UTF-8.
+    InvalidUtf8,
+}
+
+impl fmt::Display for BcSymbolMapErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidHeader => write!(f, "missing or invalid BCSymbolMap header"),
+            Self::UnsupportedVersion => write!(f, "unsupported BCSymbolMap version"),
+            Self::InvalidUtf8 => write!(f, "BCSymbolMap is not valid UTF-8"),
+        }
+    }
+}
+
+/// An error returned when handling [`BcSymbolMap`](struct.BcSymbolMap.html).
+#[derive(Debug, Error)]
+#[error("{kind}")]
+pub struct BcSymbolMapError {
+    kind: BcSymbolMapErrorKind,
+    #[source]
+    source: Option<Box<dyn Error + Send + Sync + 'static>>,
+}
+
+impl BcSymbolMapError {
+    /// Creates a new BCSymbolMap error from a known kind of error as well as an arbitrary error
+    /// payload.
+    fn new<E>(kind: BcSymbolMapErrorKind, source: E) -> Self
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        let source = Some(source.into());
+        Self { kind, source }
+    }
+
+    /// Returns the corresponding [`BcSymbolMapErrorKind`] for this error.
+    pub fn kind(&self) -> BcSymbolMapErrorKind {
+        self.kind
+    }
+}
+
+impl From<BcSymbolMapErrorKind> for BcSymbolMapError {
+    fn from(kind: BcSymbolMapErrorKind) -> Self {
+        Self { kind, source: None }
+    }
+}
+
+/// A map of hidden names in bitcode-enabled builds to their original names.
+///
+/// See the [module documentation](index.html) for a description of the format.
+#[derive(Clone, Debug)]
+pub struct BcSymbolMap<'d> {
+    names: Vec<&'d str>,
+}
+
+impl<'d> BcSymbolMap<'d> {
+    /// Tests whether the buffer could contain a BCSymbolMap.
+    pub fn test(data: &[u8]) -> bool {
+        data.starts_with(BC_SYMBOL_MAP_HEADER.as_bytes())
+    }
+
+    /// Parses a BCSymbolMap from the given slice.
+    ///
+    /// Versions `1.x` and `2.x` of the format are supported.
+    pub fn parse(data: &'d [u8]) -> Result<Self, BcSymbolMapError> {
+        let content = std::str::from_utf8(data)
+            .map_err(|e| BcSymbolMapError::new(BcSymbolMapErrorKind::InvalidUtf8, e))?;
+
+        let mut lines = content.lines();
+        let header = lines.next().unwrap_or_default();
+        let version = header
+            .strip_prefix(BC_SYMBOL_MAP_HEADER)
+            .ok_or(BcSymbolMapErrorKind::InvalidHeader)?;
+
+        let major = version
+            .trim()
+            .split('.')
+            .next()
+            .unwrap_or_default()
+            .parse::<u32>()
+            .map_err(|e| BcSymbolMapError::new(BcSymbolMapErrorKind::InvalidHeader, e))?;
+
+        if major != 1 && major != 2 {
+            return Err(BcSymbolMapErrorKind::UnsupportedVersion.into());
+        }
+
+        Ok(BcSymbolMap {
+            names: lines.collect(),
+        })
+    }
+
+    /// Returns the name at the given index, if any.
+    pub fn get(&self, index: usize) -> Option<&'d str> {
+        self.names.get(index).copied()
+    }
+
+    /// Returns the number of names in this map.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Returns `true` if this map does not contain any names.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Returns an iterator over all names in this map, ordered by their index.
+    pub fn iter(&self) -> BcSymbolMapIterator<'_, 'd> {
+        BcSymbolMapIterator {
+            iter: self.names.iter(),
+        }
+    }
+
+    /// Resolves a hidden name of the form `__hidden#N_`.
+    ///
+    /// Returns `None` if the name is not hidden or if this map does not contain its index.
+    pub fn resolve_opt(&self, name: &str) -> Option<&'d str> {
+        let index = name
+            .strip_prefix(HIDDEN_PREFIX)?
+            .strip_suffix(HIDDEN_SUFFIX)?
+            .parse()
+            .ok()?;
+
+        self.get(index)
+    }
+
+    /// Resolves a hidden name of the form `__hidden#N_`.
+    ///
+    /// If the name cannot be resolved, it is returned unchanged.
+    pub fn resolve<'a>(&self, name: &'a str) -> &'a str
+    where
+        'd: 'a,
+    {
+        self.resolve_opt(name).unwrap_or(name)
+    }
+
+    /// Resolves a hidden name stored as raw bytes, such as file names.
+    fn resolve_bytes<'a>(&self, name: &'a [u8]) -> &'a [u8]
+    where
+        'd: 'a,
+    {
+        std::str::from_utf8(name)
+            .ok()
+            .and_then(|name| self.resolve_opt(name))
+            .map_or(name, str::as_bytes)
+    }
+
+    /// Resolves hidden names of a file.
+    fn resolve_file_info<'a>(&self, info: &mut FileInfo<'a>)
+    where
+        'd: 'a,
+    {
+        info.name = self.resolve_bytes(info.name);
+        info.dir = self.resolve_bytes(info.dir);
+    }
+
+    /// Resolves hidden names of a file entry, including its compilation directory.
+    pub(crate) fn resolve_file_entry<'a>(&self, entry: &mut FileEntry<'a>)
+    where
+        'd: 'a,
+    {
+        entry.compilation_dir = self.resolve_bytes(entry.compilation_dir);
+        self.resolve_file_info(&mut entry.info);
+    }
+
+    /// Resolves hidden names of a function, its line records and all of its inlinees.
+    pub(crate) fn resolve_function<'a>(&self, function: &mut Function<'a>)
+    where
+        'd: 'a,
+    {
+        if let Some(resolved) = self.resolve_opt(function.name.as_str()) {
+            let name = &function.name;
+            function.name = Name::new(resolved, name.mangling(), name.language());
+        }
+
+        function.compilation_dir = self.resolve_bytes(function.compilation_dir);
+
+        for line in &mut function.lines {
+            self.resolve_file_info(&mut line.file);
+        }
+
+        for inlinee in &mut function.inlinees {
+            self.resolve_function(inlinee);
+        }
+    }
+}
+
+impl<'d, 'a> IntoIterator for &'a BcSymbolMap<'d> {
+    type Item = &'d str;
+    type IntoIter = BcSymbolMapIterator<'a, 'd>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the names in a [`BcSymbolMap`](struct.BcSymbolMap.html).
+///
+/// Returned by [`BcSymbolMap::iter`](struct.BcSymbolMap.html#method.iter).
+#[derive(Clone, Debug)]
+pub struct BcSymbolMapIterator<'a, 'd> {
+    iter: std::slice::Iter<'a, &'d str>,
+}
+
+impl<'a, 'd> Iterator for BcSymbolMapIterator<'a, 'd> {
+    type Item = &'d str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().copied()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl std::iter::FusedIterator for BcSymbolMapIterator<'_, '_> {}
+
+impl ExactSizeIterator for BcSymbolMapIterator<'_, '_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static MAP: &[u8] = b"BCSymbolMap Version: 2.0\n_main\n-[Foo bar:]\n/tmp/main.m\n";
+
+    #[test]
+    fn test_parse() {
+        let map = BcSymbolMap::parse(MAP).unwrap();
+        assert_eq!(map.len(), 3);
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            ["_main", "-[Foo bar:]", "/tmp/main.m"]
+        );
+    }
+
+    #[test]
+    fn test_resolve() {
+        let map = BcSymbolMap::parse(MAP).unwrap();
+        assert_eq!(map.resolve("__hidden#1_"), "-[Foo bar:]");
+        assert_eq!(map.resolve("__hidden#3_"), "__hidden#3_");
+        assert_eq!(map.resolve("__hidden#1"), "__hidden#1");
+        assert_eq!(map.resolve("main"), "main");
+    }
+
+    #[test]
+    fn test_invalid_header() {
+        let error = BcSymbolMap::parse(b"_main\n").unwrap_err();
+        assert_eq!(error.kind(), BcSymbolMapErrorKind::InvalidHeader);
+
+        let error = BcSymbolMap::parse(b"BCSymbolMap Version: 3.0\n").unwrap_err();
+        assert_eq!(error.kind(), BcSymbolMapErrorKind::UnsupportedVersion);
+    }
+}
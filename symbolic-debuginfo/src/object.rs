@@ -15,6 +15,7 @@ use crate::elf::*;
 use crate::macho::*;
 use crate::pdb::*;
 use crate::pe::*;
+use crate::ppdb::*;
 use crate::private::{MonoArchive, MonoArchiveObjects};
 use crate::sourcebundle::*;
 use crate::wasm::*;
@@ -27,6 +28,7 @@ macro_rules! match_inner {
             $ty::MachO($pat) => $expr,
             $ty::Pdb($pat) => $expr,
             $ty::Pe($pat) => $expr,
+            $ty::PortablePdb($pat) => $expr,
             $ty::SourceBundle($pat) => $expr,
             $ty::Wasm($pat) => $expr,
         }
@@ -41,6 +43,7 @@ macro_rules! map_inner {
             $from::MachO($pat) => $to::MachO($expr),
             $from::Pdb($pat) => $to::Pdb($expr),
             $from::Pe($pat) => $to::Pe($expr),
+            $from::PortablePdb($pat) => $to::PortablePdb($expr),
             $from::SourceBundle($pat) => $to::SourceBundle($expr),
             $from::Wasm($pat) => $to::Wasm($expr),
         }
@@ -55,6 +58,9 @@ macro_rules! map_result {
             $from::MachO($pat) => $expr.map($to::MachO).map_err(ObjectError::transparent),
             $from::Pdb($pat) => $expr.map($to::Pdb).map_err(ObjectError::transparent),
             $from::Pe($pat) => $expr.map($to::Pe).map_err(ObjectError::transparent),
+            $from::PortablePdb($pat) => $expr
+                .map($to::PortablePdb)
+                .map_err(ObjectError::transparent),
             $from::SourceBundle($pat) => $expr
                 .map($to::SourceBundle)
                 .map_err(ObjectError::transparent),
@@ -149,6 +155,8 @@ pub fn peek(data: &[u8], archive: bool) -> FileFormat {
         FileFormat::Breakpad
     } else if PdbObject::test(data) {
         FileFormat::Pdb
+    } else if PortablePdbObject::test(data) {
+        FileFormat::PortablePdb
     } else if WasmObject::test(data) {
         FileFormat::Wasm
     } else {
@@ -170,6 +178,8 @@ pub enum Object<'data> {
     Pdb(PdbObject<'data>),
     /// Portable Executable, an extension of COFF used on Windows.
     Pe(PeObject<'data>),
+    /// Portable PDB, the debug companion format of .NET assemblies.
+    PortablePdb(PortablePdbObject<'data>),
     /// A source bundle.
     SourceBundle(SourceBundle<'data>),
     /// A WASM file.
@@ -201,6 +211,7 @@ impl<'data> Object<'data> {
             FileFormat::MachO => parse_object!(MachO, MachObject, data),
             FileFormat::Pdb => parse_object!(Pdb, PdbObject, data),
             FileFormat::Pe => parse_object!(Pe, PeObject, data),
+            FileFormat::PortablePdb => parse_object!(PortablePdb, PortablePdbObject, data),
             FileFormat::SourceBundle => parse_object!(SourceBundle, SourceBundle, data),
             FileFormat::Wasm => parse_object!(Wasm, WasmObject, data),
             FileFormat::Unknown => {
@@ -219,6 +230,7 @@ impl<'data> Object<'data> {
             Object::MachO(_) => FileFormat::MachO,
             Object::Pdb(_) => FileFormat::Pdb,
             Object::Pe(_) => FileFormat::Pe,
+            Object::PortablePdb(_) => FileFormat::PortablePdb,
             Object::SourceBundle(_) => FileFormat::SourceBundle,
             Object::Wasm(_) => FileFormat::Wasm,
         }
@@ -311,6 +323,10 @@ impl<'data> Object<'data> {
                 .debug_session()
                 .map(ObjectDebugSession::Pe)
                 .map_err(ObjectError::transparent),
+            Object::PortablePdb(ref o) => o
+                .debug_session()
+                .map(ObjectDebugSession::PortablePdb)
+                .map_err(ObjectError::transparent),
             Object::SourceBundle(ref o) => o
                 .debug_session()
                 .map(ObjectDebugSession::SourceBundle)
@@ -412,6 +428,7 @@ pub enum ObjectDebugSession<'d> {
     Dwarf(DwarfDebugSession<'d>),
     Pdb(PdbDebugSession<'d>),
     Pe(PeDebugSession<'d>),
+    PortablePdb(PortablePdbDebugSession<'d>),
     SourceBundle(SourceBundleDebugSession<'d>),
 }
 
@@ -429,6 +446,9 @@ impl<'d> ObjectDebugSession<'d> {
             ObjectDebugSession::Dwarf(ref s) => ObjectFunctionIterator::Dwarf(s.functions()),
             ObjectDebugSession::Pdb(ref s) => ObjectFunctionIterator::Pdb(s.functions()),
             ObjectDebugSession::Pe(ref s) => ObjectFunctionIterator::Pe(s.functions()),
+            ObjectDebugSession::PortablePdb(ref s) => {
+                ObjectFunctionIterator::PortablePdb(s.functions())
+            }
             ObjectDebugSession::SourceBundle(ref s) => {
                 ObjectFunctionIterator::SourceBundle(s.functions())
             }
@@ -442,6 +462,7 @@ impl<'d> ObjectDebugSession<'d> {
             ObjectDebugSession::Dwarf(ref s) => ObjectFileIterator::Dwarf(s.files()),
             ObjectDebugSession::Pdb(ref s) => ObjectFileIterator::Pdb(s.files()),
             ObjectDebugSession::Pe(ref s) => ObjectFileIterator::Pe(s.files()),
+            ObjectDebugSession::PortablePdb(ref s) => ObjectFileIterator::PortablePdb(s.files()),
             ObjectDebugSession::SourceBundle(ref s) => ObjectFileIterator::SourceBundle(s.files()),
         }
     }
@@ -463,6 +484,9 @@ impl<'d> ObjectDebugSession<'d> {
             ObjectDebugSession::Pe(ref s) => {
                 s.source_by_path(path).map_err(ObjectError::transparent)
             }
+            ObjectDebugSession::PortablePdb(ref s) => {
+                s.source_by_path(path).map_err(ObjectError::transparent)
+            }
             ObjectDebugSession::SourceBundle(ref s) => {
                 s.source_by_path(path).map_err(ObjectError::transparent)
             }
@@ -495,6 +519,7 @@ pub enum ObjectFunctionIterator<'s> {
     Dwarf(DwarfFunctionIterator<'s>),
     Pdb(PdbFunctionIterator<'s>),
     Pe(PeFunctionIterator<'s>),
+    PortablePdb(PortablePdbFunctionIterator<'s>),
     SourceBundle(SourceBundleFunctionIterator<'s>),
 }
 
@@ -515,6 +540,9 @@ impl<'s> Iterator for ObjectFunctionIterator<'s> {
             ObjectFunctionIterator::Pe(ref mut i) => {
                 Some(i.next()?.map_err(ObjectError::transparent))
             }
+            ObjectFunctionIterator::PortablePdb(ref mut i) => {
+                Some(i.next()?.map_err(ObjectError::transparent))
+            }
             ObjectFunctionIterator::SourceBundle(ref mut i) => {
                 Some(i.next()?.map_err(ObjectError::transparent))
             }
@@ -530,6 +558,7 @@ pub enum ObjectFileIterator<'s> {
     Dwarf(DwarfFileIterator<'s>),
     Pdb(PdbFileIterator<'s>),
     Pe(PeFileIterator<'s>),
+    PortablePdb(PortablePdbFileIterator<'s>),
     SourceBundle(SourceBundleFileIterator<'s>),
 }
 
@@ -546,6 +575,9 @@ impl<'s> Iterator for ObjectFileIterator<'s> {
             }
             ObjectFileIterator::Pdb(ref mut i) => Some(i.next()?.map_err(ObjectError::transparent)),
             ObjectFileIterator::Pe(ref mut i) => Some(i.next()?.map_err(ObjectError::transparent)),
+            ObjectFileIterator::PortablePdb(ref mut i) => {
+                Some(i.next()?.map_err(ObjectError::transparent))
+            }
             ObjectFileIterator::SourceBundle(ref mut i) => {
                 Some(i.next()?.map_err(ObjectError::transparent))
             }
@@ -561,6 +593,7 @@ pub enum SymbolIterator<'data, 'object> {
     MachO(MachOSymbolIterator<'data>),
     Pdb(PdbSymbolIterator<'data, 'object>),
     Pe(PeSymbolIterator<'data, 'object>),
+    PortablePdb(PortablePdbSymbolIterator<'data>),
     SourceBundle(SourceBundleSymbolIterator<'data>),
     Wasm(WasmSymbolIterator<'data, 'object>),
 }
@@ -580,6 +613,7 @@ enum ArchiveInner<'d> {
     MachO(MachArchive<'d>),
     Pdb(MonoArchive<'d, PdbObject<'d>>),
     Pe(MonoArchive<'d, PeObject<'d>>),
+    PortablePdb(MonoArchive<'d, PortablePdbObject<'d>>),
     SourceBundle(MonoArchive<'d, SourceBundle<'d>>),
     Wasm(MonoArchive<'d, WasmObject<'d>>),
 }
@@ -616,6 +650,7 @@ impl<'d> Archive<'d> {
             }
             FileFormat::Pdb => Archive(ArchiveInner::Pdb(MonoArchive::new(data))),
             FileFormat::Pe => Archive(ArchiveInner::Pe(MonoArchive::new(data))),
+            FileFormat::PortablePdb => Archive(ArchiveInner::PortablePdb(MonoArchive::new(data))),
             FileFormat::SourceBundle => Archive(ArchiveInner::SourceBundle(MonoArchive::new(data))),
             FileFormat::Wasm => Archive(ArchiveInner::Wasm(MonoArchive::new(data))),
             FileFormat::Unknown => {
@@ -634,6 +669,7 @@ impl<'d> Archive<'d> {
             ArchiveInner::MachO(_) => FileFormat::MachO,
             ArchiveInner::Pdb(_) => FileFormat::Pdb,
             ArchiveInner::Pe(_) => FileFormat::Pe,
+            ArchiveInner::PortablePdb(_) => FileFormat::PortablePdb,
             ArchiveInner::Wasm(_) => FileFormat::Wasm,
             ArchiveInner::SourceBundle(_) => FileFormat::SourceBundle,
         }
@@ -676,6 +712,10 @@ impl<'d> Archive<'d> {
                 .object_by_index(index)
                 .map(|opt| opt.map(Object::Pe))
                 .map_err(ObjectError::transparent),
+            ArchiveInner::PortablePdb(ref a) => a
+                .object_by_index(index)
+                .map(|opt| opt.map(Object::PortablePdb))
+                .map_err(ObjectError::transparent),
             ArchiveInner::SourceBundle(ref a) => a
                 .object_by_index(index)
                 .map(|opt| opt.map(Object::SourceBundle))
@@ -710,6 +750,7 @@ enum ObjectIteratorInner<'d, 'a> {
     MachO(MachObjectIterator<'d, 'a>),
     Pdb(MonoArchiveObjects<'d, PdbObject<'d>>),
     Pe(MonoArchiveObjects<'d, PeObject<'d>>),
+    PortablePdb(MonoArchiveObjects<'d, PortablePdbObject<'d>>),
     SourceBundle(MonoArchiveObjects<'d, SourceBundle<'d>>),
     Wasm(MonoArchiveObjects<'d, WasmObject<'d>>),
 }
@@ -4,6 +4,8 @@ use std::borrow::Cow;
 use std::cell::{RefCell, RefMut};
 use std::cmp::Ordering;
 use std::collections::btree_map::{BTreeMap, Entry};
+use std::collections::BTreeSet;
+use std::convert::TryInto;
 use std::error::Error;
 use std::fmt;
 use std::io::Cursor;
@@ -23,12 +25,32 @@ use symbolic_common::{
 };
 
 use crate::base::*;
-use crate::private::{FunctionStack, Parse};
+use crate::private::{decompress_embedded_source, FunctionStack, Parse};
+use crate::sourcelink::{SourceLinkError, SourceLinkMappings};
+use crate::srcsrv::SrcSrvStream;
 
 type Pdb<'data> = pdb::PDB<'data, Cursor<&'data [u8]>>;
 
 const MAGIC_BIG: &[u8] = b"Microsoft C/C++ MSF 7.00\r\n\x1a\x44\x53\x00\x00\x00";
 
+/// Named stream listing source files injected into the PDB.
+const SOURCE_HEADER_BLOCK_STREAM: &[u8] = b"/src/headerblock";
+
+/// Prefix of named streams containing the contents of injected source files.
+const SOURCE_FILES_STREAM_PREFIX: &str = "/src/files/";
+
+/// Size of the header of the injected source header block.
+const SOURCE_HEADER_BLOCK_SIZE: usize = 64;
+
+/// Size of an injected source entry in the header block.
+const SOURCE_HEADER_ENTRY_SIZE: usize = 40;
+
+/// Injected source files are stored uncompressed.
+const SOURCE_COMPRESSION_NONE: u8 = 0;
+
+/// Injected source files are deflate compressed with a size prefix, as written by .NET compilers.
+const SOURCE_COMPRESSION_DOTNET: u8 = 101;
+
 // Used for CFI, remove once abstraction is complete
 #[doc(hidden)]
 pub use pdb;
@@ -45,6 +67,9 @@ pub enum PdbErrorKind {
 
     /// Formatting of a type name failed.
     FormattingFailed,
+
+    /// The embedded SourceLink mappings are invalid.
+    BadSourceLink,
 }
 
 impl fmt::Display for PdbErrorKind {
@@ -53,6 +78,7 @@ impl fmt::Display for PdbErrorKind {
             Self::BadObject => write!(f, "invalid pdb file"),
             Self::UnexpectedInline => write!(f, "unexpected inline function without parent"),
             Self::FormattingFailed => write!(f, "failed to format type name"),
+            Self::BadSourceLink => write!(f, "invalid source link mappings"),
         }
     }
 }
@@ -101,6 +127,12 @@ impl From<fmt::Error> for PdbError {
     }
 }
 
+impl From<SourceLinkError> for PdbError {
+    fn from(e: SourceLinkError) -> Self {
+        Self::new(PdbErrorKind::BadSourceLink, e)
+    }
+}
+
 /// Program Database, the debug companion format on Windows.
 ///
 /// This object is a sole debug companion to [`PeObject`](../pdb/struct.PdbObject.html).
@@ -210,9 +242,18 @@ impl<'data> PdbObject<'data> {
 
     /// Returns an iterator over symbols in the public symbol table.
     pub fn symbols(&self) -> PdbSymbolIterator<'data, '_> {
+        let address_map = self.pdb.write().address_map().ok();
+        let folded = match address_map {
+            Some(ref address_map) => {
+                folded_functions(&self.public_syms, address_map).unwrap_or_default()
+            }
+            None => BTreeSet::new(),
+        };
+
         PdbSymbolIterator {
             symbols: self.public_syms.iter(),
-            address_map: self.pdb.write().address_map().ok(),
+            address_map,
+            folded,
         }
     }
 
@@ -231,8 +272,16 @@ impl<'data> PdbObject<'data> {
     }
 
     /// Determines whether this object contains embedded source.
+    ///
+    /// This is the case for PDBs with source files injected into the `/src/files/` streams. Only
+    /// the header of the injected sources is checked, and the sources are not read.
     pub fn has_sources(&self) -> bool {
-        false
+        match self.pdb.write().named_stream(SOURCE_HEADER_BLOCK_STREAM) {
+            Ok(stream) => {
+                read_u32(stream.as_slice(), SOURCE_HEADER_BLOCK_SIZE).map_or(false, |n| n > 0)
+            }
+            Err(_) => false,
+        }
     }
 
     /// Constructs a debugging session.
@@ -249,6 +298,35 @@ impl<'data> PdbObject<'data> {
         self.arch().cpu_family() == CpuFamily::Intel32
     }
 
+    /// Returns the source server stream, if the PDB has been source indexed.
+    pub fn srcsrv(&self) -> Result<Option<SrcSrvStream>, PdbError> {
+        Ok(self
+            .named_stream(b"srcsrv")?
+            .map(|data| SrcSrvStream::parse(&data)))
+    }
+
+    /// Returns the SourceLink mappings embedded with the `/SOURCELINK` linker flag, if any.
+    pub fn source_link(&self) -> Result<Option<SourceLinkMappings>, PdbError> {
+        match self.named_stream(b"sourcelink")? {
+            Some(data) => Ok(Some(SourceLinkMappings::parse(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns a resolver for the URLs of source files based on the source server stream and
+    /// SourceLink mappings.
+    pub fn source_resolver(&self) -> Result<PdbSourceResolver, PdbError> {
+        Ok(PdbSourceResolver {
+            srcsrv: self.srcsrv()?,
+            source_link: self.source_link()?,
+        })
+    }
+
+    /// Reads the contents of a named stream, if it exists.
+    fn named_stream(&self, name: &[u8]) -> Result<Option<Vec<u8>>, PdbError> {
+        read_named_stream(&self.pdb, name)
+    }
+
     /// Returns the raw data of the ELF file.
     pub fn data(&self) -> &'data [u8] {
         self.data
@@ -362,12 +440,57 @@ pub(crate) fn arch_from_machine(machine: MachineType) -> Arch {
     }
 }
 
+/// Resolves paths of source files in a PDB to URLs.
+///
+/// Returned by [`PdbObject::source_resolver`](struct.PdbObject.html#method.source_resolver).
+#[derive(Clone, Debug, Default)]
+pub struct PdbSourceResolver {
+    srcsrv: Option<SrcSrvStream>,
+    source_link: Option<SourceLinkMappings>,
+}
+
+impl PdbSourceResolver {
+    /// Returns the source server stream of the PDB, if any.
+    pub fn srcsrv(&self) -> Option<&SrcSrvStream> {
+        self.srcsrv.as_ref()
+    }
+
+    /// Returns the SourceLink mappings of the PDB, if any.
+    pub fn source_link(&self) -> Option<&SourceLinkMappings> {
+        self.source_link.as_ref()
+    }
+
+    /// Returns `true` if the PDB contains neither a source server stream nor SourceLink mappings.
+    pub fn is_empty(&self) -> bool {
+        self.srcsrv.is_none() && self.source_link.is_none()
+    }
+
+    /// Resolves the URL of a source file by its path at build time.
+    ///
+    /// SourceLink mappings take precedence over the source server stream. Source server targets
+    /// are only returned if they are HTTP URLs.
+    pub fn resolve_path(&self, path: &str) -> Option<String> {
+        let source_link = self.source_link.as_ref();
+        let srcsrv = self.srcsrv.as_ref();
+
+        source_link
+            .and_then(|mappings| mappings.resolve(path))
+            .or_else(|| srcsrv.and_then(|stream| stream.url(path)))
+    }
+
+    /// Resolves the URL of a source file entry.
+    pub fn resolve_file(&self, file: &FileEntry<'_>) -> Option<String> {
+        self.resolve_path(&file.abs_path_str())
+    }
+}
+
 /// An iterator over symbols in the PDB file.
 ///
 /// Returned by [`PdbObject::symbols`](struct.PdbObject.html#method.symbols).
 pub struct PdbSymbolIterator<'data, 'object> {
     symbols: pdb::SymbolIter<'object>,
     address_map: Option<AddressMap<'data>>,
+    folded: BTreeSet<u64>,
 }
 
 impl<'data, 'object> Iterator for PdbSymbolIterator<'data, 'object> {
@@ -397,10 +520,13 @@ impl<'data, 'object> Iterator for PdbSymbolIterator<'data, 'object> {
                 };
                 let name = Cow::from(String::from(base));
 
+                let address = u64::from(address.0);
                 return Some(Symbol {
                     name: Some(name),
-                    address: u64::from(address.0),
+                    address,
                     size: 0, // Computed in `SymbolMap`
+                    multiple: self.folded.contains(&address),
+                    parameter_size: None,
                 });
             }
         }
@@ -409,6 +535,45 @@ impl<'data, 'object> Iterator for PdbSymbolIterator<'data, 'object> {
     }
 }
 
+/// Collects the addresses of functions merged by identical code folding.
+///
+/// When the linker folds identical functions, it retains a public symbol for each of them, all
+/// pointing to the same code. Such addresses are detected by differently named public function
+/// symbols.
+fn folded_functions(
+    symbols: &pdb::SymbolTable<'_>,
+    address_map: &AddressMap<'_>,
+) -> Result<BTreeSet<u64>, PdbError> {
+    let mut names = BTreeMap::new();
+    let mut folded = BTreeSet::new();
+
+    let mut symbols = symbols.iter();
+    while let Some(symbol) = symbols.next()? {
+        let public = match symbol.parse() {
+            Ok(SymbolData::Public(public)) if public.function => public,
+            _ => continue,
+        };
+
+        let address = match public.offset.to_rva(address_map) {
+            Some(address) => u64::from(address.0),
+            None => continue,
+        };
+
+        match names.entry(address) {
+            Entry::Vacant(entry) => {
+                entry.insert(public.name);
+            }
+            Entry::Occupied(entry) => {
+                if *entry.get() != public.name {
+                    folded.insert(address);
+                }
+            }
+        }
+    }
+
+    Ok(folded)
+}
+
 struct ItemMap<'s, I: ItemIndex> {
     iter: pdb::ItemIter<'s, I>,
     finder: pdb::ItemFinder<'s, I>,
@@ -488,6 +653,12 @@ struct PdbDebugInfo<'d> {
     type_map: RefCell<TypeMap<'d>>,
     /// Lazy loaded map of the IPI stream.
     id_map: RefCell<IdMap<'d>>,
+    /// Lazy loaded global symbol table for user-defined type names.
+    global_symbols: LazyCell<pdb::SymbolTable<'d>>,
+    /// Lazy loaded injected source files by normalized path.
+    sources: LazyCell<BTreeMap<String, InjectedSource>>,
+    /// Lazy loaded addresses of functions merged by identical code folding.
+    folded_functions: LazyCell<BTreeSet<u64>>,
 }
 
 impl<'d> PdbDebugInfo<'d> {
@@ -522,6 +693,9 @@ impl<'d> PdbDebugInfo<'d> {
             string_table,
             type_map,
             id_map,
+            global_symbols: LazyCell::new(),
+            sources: LazyCell::new(),
+            folded_functions: LazyCell::new(),
         })
     }
 
@@ -548,6 +722,84 @@ impl<'d> PdbDebugInfo<'d> {
         Ok(module_opt.as_ref())
     }
 
+    fn global_symbols(&self) -> Result<&pdb::SymbolTable<'d>, PdbError> {
+        self.global_symbols
+            .try_borrow_with(|| Ok(self.pdb.write().global_symbols()?))
+    }
+
+    /// Returns the addresses of functions that share their code with other functions.
+    ///
+    /// If the global symbols cannot be read, no functions are considered folded.
+    fn folded_functions(&self) -> &BTreeSet<u64> {
+        self.folded_functions.borrow_with(|| {
+            self.global_symbols()
+                .and_then(|symbols| folded_functions(symbols, &self.address_map))
+                .unwrap_or_default()
+        })
+    }
+
+    /// Returns injected source files keyed by their normalized original and virtual paths.
+    fn sources(&self) -> Result<&BTreeMap<String, InjectedSource>, PdbError> {
+        self.sources.try_borrow_with(|| {
+            let mut sources = BTreeMap::new();
+
+            let string_table = match self.string_table {
+                Some(ref string_table) => string_table,
+                None => return Ok(sources),
+            };
+
+            let data = match read_named_stream(&self.pdb, SOURCE_HEADER_BLOCK_STREAM)? {
+                Some(data) => data,
+                None => return Ok(sources),
+            };
+
+            let entries = parse_source_header_block(&data)
+                .ok_or_else(|| PdbError::from(PdbErrorKind::BadObject))?;
+
+            for entry in entries {
+                let virtual_name = string_table
+                    .get(pdb::StringRef(entry.virtual_name))?
+                    .to_string()
+                    .into_owned();
+                let file_name = string_table
+                    .get(pdb::StringRef(entry.file_name))?
+                    .to_string();
+
+                let source = InjectedSource {
+                    stream_name: format!("{}{}", SOURCE_FILES_STREAM_PREFIX, virtual_name),
+                    compression: entry.compression,
+                };
+
+                sources.insert(normalize_source_path(&file_name), source.clone());
+                sources.insert(normalize_source_path(&virtual_name), source);
+            }
+
+            Ok(sources)
+        })
+    }
+
+    /// Reads and decompresses the contents of an injected source file.
+    fn source_by_path(&self, path: &str) -> Result<Option<String>, PdbError> {
+        let source = match self.sources()?.get(&normalize_source_path(path)) {
+            Some(source) => source,
+            None => return Ok(None),
+        };
+
+        let data = match read_named_stream(&self.pdb, source.stream_name.as_bytes())? {
+            Some(data) => data,
+            None => return Ok(None),
+        };
+
+        let contents = match source.compression {
+            SOURCE_COMPRESSION_NONE => data,
+            SOURCE_COMPRESSION_DOTNET => decompress_embedded_source(&data)
+                .ok_or_else(|| PdbError::from(PdbErrorKind::BadObject))?,
+            _ => return Ok(None),
+        };
+
+        Ok(Some(String::from_utf8_lossy(&contents).into_owned()))
+    }
+
     fn file_info(&self, file_info: pdb::FileInfo<'d>) -> Result<FileInfo<'_>, PdbError> {
         let file_path = match self.string_table {
             Some(ref string_table) => file_info.name.to_raw_string(string_table)?,
@@ -644,11 +896,45 @@ impl<'d> PdbDebugSession<'d> {
         }
     }
 
+    /// Returns an iterator over all structures, classes, unions, enumerations and typedefs.
+    ///
+    /// Types are read from the TPI stream, skipping forward references. Typedefs are read from
+    /// user-defined type symbols in the global symbol table.
+    pub fn types(&self) -> PdbTypeIterator<'_> {
+        PdbTypeIterator {
+            debug_info: self.cell.get(),
+            types: self.cell.owner().type_info.iter(),
+            symbols: None,
+            finished: false,
+        }
+    }
+
+    /// Returns an iterator over parameters and local variables of all functions.
+    ///
+    /// Variables are yielded in the order they are declared in the debug information. Use
+    /// [`Variable::function_address`] to associate them with functions.
+    ///
+    /// Locations are only available for variables stored relative to a register or in a register
+    /// throughout their scope. Variables of optimized code, which are declared by `S_LOCAL` records,
+    /// always have an unknown location, since their location is described per address range by
+    /// subsequent `S_DEFRANGE_*` records, which are not supported.
+    ///
+    /// [`Variable::function_address`]: ../struct.Variable.html#structfield.function_address
+    pub fn variables(&self) -> PdbVariableIterator<'_> {
+        PdbVariableIterator {
+            units: self.cell.get().units(),
+            variables: Vec::new().into_iter(),
+            finished: false,
+        }
+    }
+
     /// Looks up a file's source contents by its full canonicalized path.
     ///
-    /// The given path must be canonicalized.
-    pub fn source_by_path(&self, _path: &str) -> Result<Option<Cow<'_, str>>, PdbError> {
-        Ok(None)
+    /// The given path must be canonicalized. Paths of injected sources are compared case
+    /// insensitively and regardless of the path separator.
+    pub fn source_by_path(&self, path: &str) -> Result<Option<Cow<'_, str>>, PdbError> {
+        let source = self.cell.get().source_by_path(path)?;
+        Ok(source.map(Cow::Owned))
     }
 }
 
@@ -670,6 +956,103 @@ impl<'session> DebugSession<'session> for PdbDebugSession<'_> {
     }
 }
 
+/// Reads the contents of a named stream, if it exists.
+fn read_named_stream(pdb: &RwLock<Pdb<'_>>, name: &[u8]) -> Result<Option<Vec<u8>>, PdbError> {
+    match pdb.write().named_stream(name) {
+        Ok(stream) => Ok(Some(stream.as_slice().to_vec())),
+        Err(pdb::Error::StreamNameNotFound) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Reads a little-endian `u32` at the given offset.
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+/// Location of an injected source file.
+#[derive(Clone, Debug)]
+struct InjectedSource {
+    /// Name of the stream containing the file contents.
+    stream_name: String,
+    /// Compression of the stream contents.
+    compression: u8,
+}
+
+/// An entry of the injected source header block.
+#[derive(Clone, Copy, Debug)]
+struct SourceHeaderEntry {
+    /// String table index of the original file path.
+    file_name: u32,
+    /// String table index of the virtual file path, which names the contents stream.
+    virtual_name: u32,
+    /// Compression of the contents stream.
+    compression: u8,
+}
+
+/// Parses the entries of the `/src/headerblock` stream.
+///
+/// The stream starts with a fixed-size header, followed by a serialized hash table mapping virtual
+/// file names to entries.
+fn parse_source_header_block(data: &[u8]) -> Option<Vec<SourceHeaderEntry>> {
+    let mut offset = SOURCE_HEADER_BLOCK_SIZE;
+    let size = read_u32(data, offset)? as usize;
+    let capacity = read_u32(data, offset + 4)? as usize;
+    offset += 8;
+
+    // The present bit vector determines which buckets are serialized.
+    let present_words = read_u32(data, offset)? as usize;
+    let present = data
+        .get(offset + 4..)?
+        .get(..present_words.checked_mul(4)?)?
+        .chunks_exact(4)
+        .map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect::<Vec<_>>();
+    offset += 4 + 4 * present_words;
+
+    // Deleted buckets are not serialized and can be skipped.
+    let deleted_words = read_u32(data, offset)? as usize;
+    offset = offset.checked_add(deleted_words.checked_mul(4)?.checked_add(4)?)?;
+
+    // The header fields cannot be trusted. Only buckets covered by the present bit vector can hold
+    // entries, and every entry occupies space in the stream.
+    let capacity = capacity.min(present.len() * 32);
+    let max_entries = data.len().saturating_sub(offset) / (4 + SOURCE_HEADER_ENTRY_SIZE);
+    if size > max_entries {
+        return None;
+    }
+
+    let mut entries = Vec::with_capacity(size);
+    for bucket in 0..capacity {
+        let word = present.get(bucket / 32).copied().unwrap_or(0);
+        if word & (1 << (bucket % 32)) == 0 {
+            continue;
+        }
+
+        // Skip the key, which is the virtual name repeated in the entry.
+        let entry = data.get(offset + 4..offset + 4 + SOURCE_HEADER_ENTRY_SIZE)?;
+        offset += 4 + SOURCE_HEADER_ENTRY_SIZE;
+
+        entries.push(SourceHeaderEntry {
+            file_name: read_u32(entry, 16)?,
+            virtual_name: read_u32(entry, 24)?,
+            compression: entry[28],
+        });
+    }
+
+    if entries.len() != size {
+        return None;
+    }
+
+    Some(entries)
+}
+
+/// Normalizes a path for case and separator insensitive lookups of injected sources.
+fn normalize_source_path(path: &str) -> String {
+    path.replace('/', "\\").to_lowercase()
+}
+
 /// Checks whether the given name declares an anonymous namespace.
 ///
 /// ID records specify the mangled format for anonymous namespaces: `?A0x<id>`, where `id` is a hex
@@ -679,23 +1062,141 @@ fn is_anonymous_namespace(name: &str) -> bool {
         .map_or(false, |rest| u32::from_str_radix(rest, 16).is_ok())
 }
 
-/// Formatter for function types.
+/// Converts a file checksum from the C13 file checksums subsection.
+///
+/// Returns `None` if there is no checksum or its length does not match the algorithm.
+fn file_checksum(checksum: &pdb::FileChecksum<'_>) -> Option<FileChecksum> {
+    match *checksum {
+        pdb::FileChecksum::Md5(bytes) => bytes.try_into().ok().map(FileChecksum::Md5),
+        pdb::FileChecksum::Sha1(bytes) => bytes.try_into().ok().map(FileChecksum::Sha1),
+        pdb::FileChecksum::Sha256(bytes) => bytes.try_into().ok().map(FileChecksum::Sha256),
+        pdb::FileChecksum::None => None,
+    }
+}
+
+/// Converts the value of an enumerator to a signed integer.
+fn variant_value(variant: pdb::Variant) -> i64 {
+    match variant {
+        pdb::Variant::U8(value) => value.into(),
+        pdb::Variant::U16(value) => value.into(),
+        pdb::Variant::U32(value) => value.into(),
+        pdb::Variant::U64(value) => value as i64,
+        pdb::Variant::I8(value) => value.into(),
+        pdb::Variant::I16(value) => value.into(),
+        pdb::Variant::I32(value) => value.into(),
+        pdb::Variant::I64(value) => value,
+    }
+}
+
+/// Determines the kind of a register-based variable.
+///
+/// These symbols do not flag parameters. Instead, parameters are always listed first within the
+/// procedure, so the first variables up to the procedure's parameter count are parameters.
+fn next_variable_kind(remaining_params: &mut usize) -> VariableKind {
+    if *remaining_params > 0 {
+        *remaining_params -= 1;
+        VariableKind::Parameter
+    } else {
+        VariableKind::Local
+    }
+}
+
+/// Returns the C name of a primitive type.
+fn primitive_name(kind: pdb::PrimitiveKind) -> &'static str {
+    use pdb::PrimitiveKind as K;
+
+    match kind {
+        K::NoType => "<no type>",
+        K::Void => "void",
+        K::Char | K::RChar | K::I8 => "char",
+        K::UChar | K::U8 => "unsigned char",
+        K::WChar => "wchar_t",
+        K::RChar16 => "char16_t",
+        K::RChar32 => "char32_t",
+        K::Short | K::I16 => "short",
+        K::UShort | K::U16 => "unsigned short",
+        K::Long => "long",
+        K::ULong => "unsigned long",
+        K::I32 => "int",
+        K::U32 => "unsigned int",
+        K::Quad | K::I64 => "long long",
+        K::UQuad | K::U64 => "unsigned long long",
+        K::Octa | K::I128 => "__int128",
+        K::UOcta | K::U128 => "unsigned __int128",
+        K::F16 => "_Float16",
+        K::F32 | K::F32PP => "float",
+        K::F48 => "__float48",
+        K::F64 => "double",
+        K::F80 => "long double",
+        K::F128 => "__float128",
+        K::Complex32 => "_Complex float",
+        K::Complex64 => "_Complex double",
+        K::Complex80 => "_Complex long double",
+        K::Complex128 => "_Complex __float128",
+        K::Bool8 | K::Bool16 | K::Bool32 | K::Bool64 => "bool",
+        _ => "<unknown>",
+    }
+}
+
+/// Returns the size of an integral primitive type in bytes.
+///
+/// Returns `None` for all other primitive types, which cannot underlie enumerations.
+fn primitive_size(kind: pdb::PrimitiveKind) -> Option<u64> {
+    use pdb::PrimitiveKind as K;
+
+    match kind {
+        K::Char | K::UChar | K::RChar | K::I8 | K::U8 | K::Bool8 => Some(1),
+        K::WChar | K::RChar16 | K::Short | K::UShort | K::I16 | K::U16 | K::Bool16 => Some(2),
+        K::RChar32 | K::Long | K::ULong | K::I32 | K::U32 | K::Bool32 => Some(4),
+        K::Quad | K::UQuad | K::I64 | K::U64 | K::Bool64 => Some(8),
+        K::Octa | K::UOcta | K::I128 | K::U128 => Some(16),
+        _ => None,
+    }
+}
+
+/// Formatter for function and type names.
 ///
 /// This formatter currently only contains the minimum implementation requried to format inline
-/// function names without parameters.
+/// function names without parameters, and the names of variable and member types.
 struct TypeFormatter<'u, 'd> {
-    unit: &'u Unit<'d>,
+    unit: Option<&'u Unit<'d>>,
     type_map: RefMut<'u, TypeMap<'d>>,
     id_map: RefMut<'u, IdMap<'d>>,
+    /// Whether primitive and array types are written. Function names omit them, so that names
+    /// remain the same as in previous versions.
+    full_types: bool,
 }
 
 impl<'u, 'd> TypeFormatter<'u, 'd> {
     /// Creates a new `TypeFormatter`.
     pub fn new(unit: &'u Unit<'d>) -> Self {
         Self {
-            unit,
+            unit: Some(unit),
             type_map: unit.debug_info.type_map.borrow_mut(),
             id_map: unit.debug_info.id_map.borrow_mut(),
+            full_types: false,
+        }
+    }
+
+    /// Creates a `TypeFormatter` for types outside of a module, which cannot be cross-module
+    /// references.
+    pub fn global(debug_info: &'u PdbDebugInfo<'d>) -> Self {
+        Self {
+            unit: None,
+            type_map: debug_info.type_map.borrow_mut(),
+            id_map: debug_info.id_map.borrow_mut(),
+            full_types: false,
+        }
+    }
+
+    /// Resolves cross-module references of the current module.
+    fn resolve_index<I>(&self, index: I) -> Result<Option<I>, PdbError>
+    where
+        I: ItemIndex,
+    {
+        match self.unit {
+            Some(unit) => unit.resolve_index(index),
+            None => Ok(Some(index)),
         }
     }
 
@@ -705,7 +1206,7 @@ impl<'u, 'd> TypeFormatter<'u, 'd> {
         target: &mut W,
         index: pdb::IdIndex,
     ) -> Result<(), PdbError> {
-        let index = match self.unit.resolve_index(index)? {
+        let index = match self.resolve_index(index)? {
             Some(index) => index,
             None => return Ok(write!(target, "<redacted>")?),
         };
@@ -764,15 +1265,21 @@ impl<'u, 'd> TypeFormatter<'u, 'd> {
         target: &mut W,
         index: pdb::TypeIndex,
     ) -> Result<(), PdbError> {
-        let index = match self.unit.resolve_index(index)? {
+        let index = match self.resolve_index(index)? {
             Some(index) => index,
             None => return Ok(write!(target, "<redacted>")?),
         };
 
         let ty = self.type_map.try_get(index)?;
         match ty.parse() {
-            Ok(pdb::TypeData::Primitive(_)) => {
-                // nothing to do
+            Ok(pdb::TypeData::Primitive(data)) => {
+                if self.full_types {
+                    write!(target, "{}", primitive_name(data.kind))?;
+
+                    if data.indirection.is_some() {
+                        write!(target, "*")?;
+                    }
+                }
             }
             Ok(pdb::TypeData::Class(data)) => {
                 write!(target, "{}", data.name.to_string())?;
@@ -864,8 +1371,11 @@ impl<'u, 'd> TypeFormatter<'u, 'd> {
             Ok(pdb::TypeData::Enumerate(data)) => {
                 write!(target, "{}", data.name.to_string())?;
             }
-            Ok(pdb::TypeData::Array(_)) => {
-                // nothing to do
+            Ok(pdb::TypeData::Array(data)) => {
+                if self.full_types {
+                    self.write_type(target, data.element_type)?;
+                    write!(target, "[]")?;
+                }
             }
             Ok(pdb::TypeData::Union(data)) => {
                 write!(target, "{}", data.name.to_string())?;
@@ -899,11 +1409,24 @@ impl<'u, 'd> TypeFormatter<'u, 'd> {
     }
 
     /// Formats the `Id` with the given index to a string.
+    ///
+    /// This is used for function names, which do not include primitive and array types.
     pub fn format_id(&mut self, index: pdb::IdIndex) -> Result<String, PdbError> {
         let mut string = String::new();
+        self.full_types = false;
         self.write_id(&mut string, index)?;
         Ok(string)
     }
+
+    /// Formats the `Type` with the given index to a string.
+    ///
+    /// This is used for the types of variables and members, and includes all types.
+    pub fn format_type(&mut self, index: pdb::TypeIndex) -> Result<String, PdbError> {
+        let mut string = String::new();
+        self.full_types = true;
+        self.write_type(&mut string, index)?;
+        Ok(string)
+    }
 }
 
 struct Unit<'s> {
@@ -963,6 +1486,10 @@ impl<'s> Unit<'s> {
                 size: line_info.length.map(u64::from),
                 file: self.debug_info.file_info(file_info)?,
                 line: line_info.line_start.into(),
+                column: line_info
+                    .column_start
+                    .filter(|&column| column != 0)
+                    .map(u64::from),
             });
         }
 
@@ -1003,6 +1530,8 @@ impl<'s> Unit<'s> {
             lines,
             inlinees: Vec::new(),
             inline: false,
+            multiple: self.debug_info.folded_functions().contains(&address),
+            parameter_size: None,
         }))
     }
 
@@ -1048,9 +1577,140 @@ impl<'s> Unit<'s> {
             lines,
             inlinees: Vec::new(),
             inline: true,
+            multiple: false,
+            parameter_size: None,
         }))
     }
 
+    /// Returns the number of parameters declared by a procedure's type, including `this`.
+    fn parameter_count(&self, type_index: pdb::TypeIndex) -> Result<usize, PdbError> {
+        let index = match self.resolve_index(type_index)? {
+            Some(index) => index,
+            None => return Ok(0),
+        };
+
+        let mut type_map = self.debug_info.type_map.borrow_mut();
+        let count = match type_map.try_get(index)?.parse() {
+            Ok(pdb::TypeData::Procedure(data)) => data.parameter_count.into(),
+            Ok(pdb::TypeData::MemberFunction(data)) => {
+                usize::from(data.parameter_count) + data.this_pointer_type.map_or(0, |_| 1)
+            }
+            _ => 0,
+        };
+
+        Ok(count)
+    }
+
+    fn variables(&self) -> Result<Vec<Variable<'s>>, PdbError> {
+        let address_map = &self.debug_info.address_map;
+        let mut symbols = self.module.symbols()?;
+
+        let mut depth = 0;
+        let mut inc_next = false;
+        let mut skipped_depth = None;
+        let mut variables = Vec::new();
+
+        // Stack of scopes with their depth, the address of the declaring function, the range of
+        // the scope itself and the number of register-based parameters not yet seen.
+        let mut scopes = Vec::<(usize, u64, Vec<std::ops::Range<u64>>, usize)>::new();
+
+        while let Some(symbol) = symbols.next()? {
+            if inc_next {
+                depth += 1;
+            }
+
+            inc_next = symbol.starts_scope();
+            if symbol.ends_scope() {
+                depth -= 1;
+            }
+
+            match skipped_depth {
+                Some(skipped) if depth > skipped => continue,
+                _ => skipped_depth = None,
+            }
+
+            while scopes.last().map_or(false, |&(d, ..)| d >= depth) {
+                scopes.pop();
+            }
+
+            // Variables are only considered if they are declared directly within a scope.
+            let parent = scopes.last_mut().filter(|scope| scope.0 + 1 == depth);
+
+            // We need to ignore errors here since the PDB crate does not yet implement all
+            // symbol types. Instead of erroring too often, it's better to swallow these.
+            let (name, type_index, kind, location) = match (symbol.parse(), parent) {
+                (Ok(SymbolData::Procedure(proc)), _) => {
+                    match proc.offset.to_rva(address_map) {
+                        Some(rva) => {
+                            let address = u64::from(rva.0);
+                            let range = address..address + u64::from(proc.len);
+                            let params = self.parameter_count(proc.type_index)?;
+                            scopes.push((depth, address, vec![range], params));
+                        }
+                        None => skipped_depth = Some(depth),
+                    }
+                    continue;
+                }
+                (Ok(SymbolData::Block(block)), Some(&mut (_, function_address, _, _))) => {
+                    match block.offset.to_rva(address_map) {
+                        Some(rva) => {
+                            let address = u64::from(rva.0);
+                            let range = address..address + u64::from(block.len);
+                            scopes.push((depth, function_address, vec![range], 0));
+                        }
+                        None => skipped_depth = Some(depth),
+                    }
+                    continue;
+                }
+                (Ok(SymbolData::Local(local)), Some(_)) => {
+                    // The location is given by `S_DEFRANGE_*` records following this symbol, which
+                    // are not parsed by the PDB crate and only apply to parts of the scope.
+                    let kind = if local.flags.isparam {
+                        VariableKind::Parameter
+                    } else {
+                        VariableKind::Local
+                    };
+
+                    (
+                        local.name,
+                        local.type_index,
+                        kind,
+                        VariableLocation::Unknown,
+                    )
+                }
+                (Ok(SymbolData::RegisterRelative(data)), Some(scope)) => {
+                    let location = VariableLocation::RegisterRelative {
+                        register: data.register.0,
+                        offset: data.offset,
+                    };
+
+                    let kind = next_variable_kind(&mut scope.3);
+                    (data.name, data.type_index, kind, location)
+                }
+                (Ok(SymbolData::RegisterVariable(data)), Some(scope)) => {
+                    let location = VariableLocation::Register(data.register.0);
+                    let kind = next_variable_kind(&mut scope.3);
+                    (data.name, data.type_index, kind, location)
+                }
+                _ => continue,
+            };
+
+            let (_, function_address, scope, _) = &scopes[scopes.len() - 1];
+            let type_name = TypeFormatter::new(self).format_type(type_index).ok();
+
+            variables.push(Variable {
+                name: name.to_string(),
+                kind,
+                type_name: type_name.map(Cow::Owned),
+                function_address: *function_address,
+                scope: scope.clone(),
+                location,
+            });
+        }
+
+        Ok(variables)
+    }
+
     fn functions(&self) -> Result<Vec<Function<'s>>, PdbError> {
         let program = self.module.line_program()?;
         let mut symbols = self.module.symbols()?;
@@ -1184,13 +1844,14 @@ impl<'s> Iterator for PdbFileIterator<'s> {
 
         loop {
             if let Some(file_result) = self.files.next().transpose() {
-                let result = file_result
-                    .map_err(|err| err.into())
-                    .and_then(|i| self.debug_info.file_info(i))
-                    .map(|info| FileEntry {
+                let result = file_result.map_err(|err| err.into()).and_then(|i| {
+                    let checksum = file_checksum(&i.checksum);
+                    self.debug_info.file_info(i).map(|info| FileEntry {
                         compilation_dir: &[],
                         info,
-                    });
+                        checksum,
+                    })
+                });
 
                 return Some(result);
             }
@@ -1252,3 +1913,228 @@ impl<'s> Iterator for PdbFunctionIterator<'s> {
 }
 
 impl std::iter::FusedIterator for PdbFunctionIterator<'_> {}
+
+/// An iterator over parameters and local variables in a PDB file.
+pub struct PdbVariableIterator<'s> {
+    units: PdbUnitIterator<'s>,
+    variables: std::vec::IntoIter<Variable<'s>>,
+    finished: bool,
+}
+
+impl<'s> Iterator for PdbVariableIterator<'s> {
+    type Item = Result<Variable<'s>, PdbError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        loop {
+            if let Some(variable) = self.variables.next() {
+                return Some(Ok(variable));
+            }
+
+            let unit = match self.units.next() {
+                Some(Ok(unit)) => unit,
+                Some(Err(error)) => return Some(Err(error)),
+                None => break,
+            };
+
+            self.variables = match unit.variables() {
+                Ok(variables) => variables.into_iter(),
+                Err(error) => return Some(Err(error)),
+            };
+        }
+
+        self.finished = true;
+        None
+    }
+}
+
+impl std::iter::FusedIterator for PdbVariableIterator<'_> {}
+
+/// An iterator over user-defined types in a PDB file.
+pub struct PdbTypeIterator<'s> {
+    debug_info: &'s PdbDebugInfo<'s>,
+    types: pdb::ItemIter<'s, pdb::TypeIndex>,
+    symbols: Option<pdb::SymbolIter<'s>>,
+    finished: bool,
+}
+
+impl<'s> PdbTypeIterator<'s> {
+    /// Converts a class, union or enumeration record into a type.
+    fn convert_type(&self, data: pdb::TypeData<'s>) -> Result<Option<TypeInfo<'s>>, PdbError> {
+        let mut formatter = TypeFormatter::global(self.debug_info);
+
+        let (mut info, fields) = match data {
+            pdb::TypeData::Class(data) => {
+                if data.properties.forward_reference() {
+                    return Ok(None);
+                }
+
+                let kind = match data.kind {
+                    pdb::ClassKind::Class => TypeKind::Class,
+                    pdb::ClassKind::Struct | pdb::ClassKind::Interface => TypeKind::Struct,
+                };
+
+                let size = Some(data.size.into());
+                (
+                    TypeInfo::new(kind, data.name.to_string(), size),
+                    data.fields,
+                )
+            }
+            pdb::TypeData::Union(data) => {
+                if data.properties.forward_reference() {
+                    return Ok(None);
+                }
+
+                let size = Some(data.size.into());
+                let info = TypeInfo::new(TypeKind::Union, data.name.to_string(), size);
+                (info, Some(data.fields))
+            }
+            pdb::TypeData::Enumeration(data) => {
+                if data.properties.forward_reference() {
+                    return Ok(None);
+                }
+
+                // Enumerations do not record their size, which is that of the underlying type.
+                let size = match formatter.type_map.try_get(data.underlying_type)?.parse() {
+                    Ok(pdb::TypeData::Primitive(primitive)) if primitive.indirection.is_none() => {
+                        primitive_size(primitive.kind)
+                    }
+                    _ => None,
+                };
+
+                let info = TypeInfo::new(TypeKind::Enum, data.name.to_string(), size);
+                (info, Some(data.fields))
+            }
+            _ => return Ok(None),
+        };
+
+        let mut next_fields = fields;
+
+        // Long field lists are split into multiple records linked by continuations.
+        while let Some(index) = next_fields.take() {
+            let fields = match formatter.type_map.try_get(index)?.parse() {
+                Ok(pdb::TypeData::FieldList(fields)) => fields,
+                _ => break,
+            };
+
+            for field in fields.fields {
+                match field {
+                    pdb::TypeData::Member(member) => {
+                        let mut type_index = member.field_type;
+                        let mut bit_size = None;
+                        let mut bit_offset = None;
+
+                        if let Ok(pdb::TypeData::Bitfield(bitfield)) =
+                            formatter.type_map.try_get(type_index)?.parse()
+                        {
+                            type_index = bitfield.underlying_type;
+                            bit_size = Some(bitfield.length.into());
+                            bit_offset = Some(bitfield.position.into());
+                        }
+
+                        info.members.push(TypeMember {
+                            name: member.name.to_string(),
+                            type_name: formatter.format_type(type_index).ok().map(Cow::Owned),
+                            offset: member.offset.into(),
+                            bit_size,
+                            bit_offset,
+                        });
+                    }
+                    pdb::TypeData::BaseClass(base) => {
+                        info.base_classes.push(BaseClass {
+                            type_name: Cow::Owned(formatter.format_type(base.base_class)?),
+                            offset: Some(base.offset.into()),
+                            is_virtual: false,
+                        });
+                    }
+                    pdb::TypeData::VirtualBaseClass(base) if base.direct => {
+                        info.base_classes.push(BaseClass {
+                            type_name: Cow::Owned(formatter.format_type(base.base_class)?),
+                            offset: None,
+                            is_virtual: true,
+                        });
+                    }
+                    pdb::TypeData::Enumerate(enumerate) => {
+                        info.enumerators.push(Enumerator {
+                            name: enumerate.name.to_string(),
+                            value: variant_value(enumerate.value),
+                        });
+                    }
+                    _ => {}
+                }
+            }
+
+            next_fields = fields.continuation;
+        }
+
+        Ok(Some(info))
+    }
+
+    /// Converts a user-defined type symbol into a typedef, unless it names a class, union or
+    /// enumeration with the same name.
+    fn convert_typedef(
+        debug_info: &'s PdbDebugInfo<'s>,
+        symbol: pdb::UserDefinedTypeSymbol<'s>,
+    ) -> Result<Option<TypeInfo<'s>>, PdbError> {
+        let aliased_type = TypeFormatter::global(debug_info).format_type(symbol.type_index)?;
+        let name = symbol.name.to_string();
+        if name == aliased_type {
+            return Ok(None);
+        }
+
+        let mut info = TypeInfo::new(TypeKind::Typedef, name, None);
+        info.aliased_type = Some(Cow::Owned(aliased_type));
+        Ok(Some(info))
+    }
+
+    fn next_type(&mut self) -> Result<Option<TypeInfo<'s>>, PdbError> {
+        while let Some(item) = self.types.next()? {
+            // We need to ignore errors here since the PDB crate does not yet implement all
+            // type kinds. Instead of erroring too often, it's better to swallow these.
+            if let Ok(data) = item.parse() {
+                if let Some(info) = self.convert_type(data)? {
+                    return Ok(Some(info));
+                }
+            }
+        }
+
+        if self.symbols.is_none() {
+            self.symbols = Some(self.debug_info.global_symbols()?.iter());
+        }
+
+        let debug_info = self.debug_info;
+        if let Some(ref mut symbols) = self.symbols {
+            while let Some(symbol) = symbols.next()? {
+                if let Ok(SymbolData::UserDefinedType(udt)) = symbol.parse() {
+                    if let Some(info) = Self::convert_typedef(debug_info, udt)? {
+                        return Ok(Some(info));
+                    }
+                }
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+impl<'s> Iterator for PdbTypeIterator<'s> {
+    type Item = Result<TypeInfo<'s>, PdbError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let result = self.next_type().transpose();
+        if result.is_none() {
+            self.finished = true;
+        }
+
+        result
+    }
+}
+
+impl std::iter::FusedIterator for PdbTypeIterator<'_> {}
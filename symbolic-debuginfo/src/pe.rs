@@ -1,22 +1,36 @@
 //! Support for Portable Executables, an extension of COFF used on Windows.
 
 use std::borrow::Cow;
+use std::convert::TryInto;
 use std::error::Error;
 use std::fmt;
 use std::io::Cursor;
-use std::marker::PhantomData;
 
 use goblin::pe;
 use thiserror::Error;
 
-use symbolic_common::{Arch, AsSelf, CodeId, DebugId, Uuid};
+use symbolic_common::{Arch, AsSelf, CodeId, CpuFamily, DebugId, Uuid};
 
 use crate::base::*;
+use crate::dwarf::{
+    Dwarf, DwarfDebugSession, DwarfFileIterator, DwarfFunctionIterator, DwarfSection, Endian,
+};
 use crate::private::Parse;
 
 pub use goblin::pe::exception::*;
 pub use goblin::pe::section_table::SectionTable;
 
+/// Size of an entry in the COFF symbol table, which precedes the string table.
+const COFF_SYMBOL_SIZE: usize = 18;
+
+/// Size of a delay-load import descriptor (`IMAGE_DELAYLOAD_DESCRIPTOR`).
+const DELAY_IMPORT_DESCRIPTOR_SIZE: u32 = 32;
+
+/// Flag in the attributes of a delay-load import descriptor indicating that it contains RVAs.
+///
+/// Descriptors written by older linkers contain virtual addresses instead.
+const DELAY_IMPORT_RVA_BASED: u32 = 0x1;
+
 /// An error when dealing with [`PEObject`](struct.PEObject.html).
 #[derive(Debug, Error)]
 #[error("invalid PE file")]
@@ -60,9 +74,11 @@ fn is_pe_stub(pe: &pe::PE<'_>) -> bool {
 /// container, [`PdbObject`]. The PE file contains a reference to the PDB and vice versa to verify
 /// that the files belong together.
 ///
-/// While in rare instances, PE files might contain debug information, this case is not supported.
+/// Executables built with MinGW or the `*-windows-gnu` toolchains embed DWARF debugging information
+/// in sections like `.debug_info` instead, which is exposed via [`debug_session`].
 ///
 /// [`PdbObject`]: ../pdb/struct.PdbObject.html
+/// [`debug_session`]: struct.PeObject.html#method.debug_session
 pub struct PeObject<'data> {
     pe: pe::PE<'data>,
     data: &'data [u8],
@@ -176,22 +192,77 @@ impl<'data> PeObject<'data> {
     }
 
     /// Returns an iterator over symbols in the public symbol table.
+    ///
+    /// Symbols are created from the export table, including forwarded exports. Since forwarded
+    /// exports are implemented in another module, their address points to the forwarder string.
+    /// Use [`exports`] to resolve their targets.
+    ///
+    /// [`exports`]: struct.PeObject.html#method.exports
     pub fn symbols(&self) -> PeSymbolIterator<'data, '_> {
         PeSymbolIterator {
             exports: self.pe.exports.iter(),
         }
     }
 
+    /// Returns an iterator over all exports, including forwarded exports and their targets.
+    pub fn exports(&self) -> PeExportIterator<'data, '_> {
+        PeExportIterator {
+            exports: self.pe.exports.iter(),
+        }
+    }
+
+    /// Returns an iterator over all functions imported from other modules.
+    ///
+    /// This includes functions from the regular import table, followed by functions that are
+    /// loaded lazily through the delay-load import table.
+    pub fn imports(&self) -> PeImportIterator<'data> {
+        let mut imports = Vec::new();
+        let thunk_size = if self.pe.is_64 { 8 } else { 4 };
+
+        if let Some(ref import_data) = self.pe.import_data {
+            for entry in &import_data.import_data {
+                let lookup_table = match entry.import_lookup_table {
+                    Some(ref lookup_table) => lookup_table,
+                    None => continue,
+                };
+
+                let iat_rva = entry.import_directory_entry.import_address_table_rva;
+                for (index, thunk) in lookup_table.iter().enumerate() {
+                    let name = match *thunk {
+                        pe::import::SyntheticImportLookupTableEntry::OrdinalNumber(ordinal) => {
+                            PeImportName::Ordinal(ordinal)
+                        }
+                        pe::import::SyntheticImportLookupTableEntry::HintNameTableRVA((
+                            _,
+                            ref hint,
+                        )) => PeImportName::Name(hint.name),
+                    };
+
+                    imports.push(PeImport {
+                        module: entry.name,
+                        name,
+                        iat_address: u64::from(iat_rva) + (index * thunk_size) as u64,
+                        delay_load: false,
+                    });
+                }
+            }
+        }
+
+        self.collect_delay_imports(&mut imports);
+
+        PeImportIterator {
+            imports: imports.into_iter(),
+        }
+    }
+
     /// Returns an ordered map of symbols in the symbol table.
     pub fn symbol_map(&self) -> SymbolMap<'data> {
         self.symbols().collect()
     }
 
-    /// Determines whether this object contains debug information.
-    ///
-    /// This is always `false`, as debug information is not supported for PE files.
+    /// Determines whether this object contains embedded DWARF debug information.
     pub fn has_debug_info(&self) -> bool {
-        false
+        self.has_section("debug_info")
     }
 
     /// Determines whether this object contains embedded source.
@@ -199,14 +270,28 @@ impl<'data> PeObject<'data> {
         false
     }
 
-    /// Constructs a no-op debugging session.
+    /// Constructs a debugging session.
+    ///
+    /// A debugging session loads certain information from the object file and creates caches for
+    /// efficient access to various records in the debug information. Since this can be quite a
+    /// costly process, try to reuse the debugging session as long as possible.
+    ///
+    /// PE files only contain debug information if it has been embedded as DWARF. Otherwise, the
+    /// session is empty and debug information has to be loaded from the PDB instead.
     pub fn debug_session(&self) -> Result<PeDebugSession<'data>, PeError> {
-        Ok(PeDebugSession { _ph: PhantomData })
+        let symbols = self.symbol_map();
+        let dwarf =
+            DwarfDebugSession::parse(self, symbols, self.load_address() as i64, self.kind())
+                .map_err(PeError::new)?;
+        Ok(PeDebugSession { dwarf })
     }
 
     /// Determines whether this object contains stack unwinding information.
     pub fn has_unwind_info(&self) -> bool {
-        !self.is_stub && self.exception_data().map_or(false, |e| !e.is_empty())
+        match self.arch().cpu_family() {
+            CpuFamily::Arm64 => self.exception_directory().map_or(false, |d| !d.is_empty()),
+            _ => !self.is_stub && self.exception_data().map_or(false, |e| !e.is_empty()),
+        }
     }
 
     /// Returns the raw data of the PE file.
@@ -227,6 +312,199 @@ impl<'data> PeObject<'data> {
             self.pe.exception_data.as_ref()
         }
     }
+
+    /// Returns the raw contents of the exception directory, usually the `.pdata` section.
+    ///
+    /// On x86_64, [`exception_data`] provides parsed unwind information instead. On ARM64, the
+    /// directory contains 8-byte entries with the start address of a function followed by either
+    /// packed unwind data or the address of an `.xdata` record, which can be read with
+    /// [`data_at_rva`].
+    ///
+    /// [`exception_data`]: struct.PeObject.html#method.exception_data
+    /// [`data_at_rva`]: struct.PeObject.html#method.data_at_rva
+    pub fn exception_directory(&self) -> Option<&'data [u8]> {
+        if self.is_stub {
+            return None;
+        }
+
+        let directory = self
+            .pe
+            .header
+            .optional_header
+            .and_then(|header| *header.data_directories.get_exception_table())?;
+
+        self.data_at_rva(directory.virtual_address)?
+            .get(..directory.size as usize)
+    }
+
+    /// Returns the data of the image starting at the given relative virtual address.
+    ///
+    /// The returned slice extends to the end of the raw data of the containing section.
+    pub fn data_at_rva(&self, rva: u32) -> Option<&'data [u8]> {
+        let section = self.pe.sections.iter().find(|section| {
+            let size = section.virtual_size.max(section.size_of_raw_data);
+            rva >= section.virtual_address && rva - section.virtual_address < size
+        })?;
+
+        let offset = rva - section.virtual_address;
+        if offset >= section.size_of_raw_data {
+            return None;
+        }
+
+        let start = section.pointer_to_raw_data as usize + offset as usize;
+        let end = section.pointer_to_raw_data as usize + section.size_of_raw_data as usize;
+        self.data.get(start..end)
+    }
+
+    /// Appends all functions from the delay-load import table to `imports`.
+    ///
+    /// Parsing stops silently at the first malformed descriptor or thunk.
+    fn collect_delay_imports(&self, imports: &mut Vec<PeImport<'data>>) {
+        let directory = match self
+            .pe
+            .header
+            .optional_header
+            .and_then(|header| *header.data_directories.get_delay_import_descriptor())
+        {
+            Some(directory) => directory,
+            None => return,
+        };
+
+        let thunk_size = if self.pe.is_64 { 8 } else { 4 };
+        let ordinal_flag = if self.pe.is_64 { 1 << 63 } else { 1 << 31 };
+
+        let mut descriptor_rva = directory.virtual_address;
+        loop {
+            let field = |index: u32| self.read_u32(descriptor_rva.checked_add(index * 4)?);
+            let (attributes, name_rva, iat_rva, int_rva) =
+                match (field(0), field(1), field(3), field(4)) {
+                    (Some(attributes), Some(name_rva), Some(iat_rva), Some(int_rva)) => {
+                        (attributes, name_rva, iat_rva, int_rva)
+                    }
+                    _ => return,
+                };
+
+            if name_rva == 0 {
+                return;
+            }
+
+            // Older linkers store virtual addresses instead of RVAs, which only occurs in 32-bit
+            // images and can be converted by subtracting the image base.
+            let to_rva = |address: u32| {
+                if attributes & DELAY_IMPORT_RVA_BASED != 0 {
+                    Some(address)
+                } else {
+                    address.checked_sub(self.pe.image_base as u32)
+                }
+            };
+
+            let module = match to_rva(name_rva).and_then(|rva| self.read_str(rva)) {
+                Some(module) => module,
+                None => return,
+            };
+
+            let (iat_rva, int_rva) = match (to_rva(iat_rva), to_rva(int_rva)) {
+                (Some(iat_rva), Some(int_rva)) => (iat_rva, int_rva),
+                _ => return,
+            };
+
+            for index in 0u32.. {
+                let offset = match index.checked_mul(thunk_size) {
+                    Some(offset) => offset,
+                    None => break,
+                };
+
+                let (thunk_rva, iat_address) =
+                    match (int_rva.checked_add(offset), iat_rva.checked_add(offset)) {
+                        (Some(thunk_rva), Some(iat_address)) => (thunk_rva, iat_address),
+                        _ => break,
+                    };
+
+                let thunk = if self.pe.is_64 {
+                    self.read_u64(thunk_rva)
+                } else {
+                    self.read_u32(thunk_rva).map(u64::from)
+                };
+
+                let name = match thunk {
+                    None | Some(0) => break,
+                    Some(thunk) if thunk & ordinal_flag != 0 => PeImportName::Ordinal(thunk as u16),
+                    // Skip the two-byte hint preceding the name.
+                    Some(thunk) => {
+                        let name = to_rva(thunk as u32)
+                            .and_then(|rva| rva.checked_add(2))
+                            .and_then(|rva| self.read_str(rva));
+                        match name {
+                            Some(name) => PeImportName::Name(name),
+                            None => break,
+                        }
+                    }
+                };
+
+                imports.push(PeImport {
+                    module,
+                    name,
+                    iat_address: u64::from(iat_address),
+                    delay_load: true,
+                });
+            }
+
+            descriptor_rva = match descriptor_rva.checked_add(DELAY_IMPORT_DESCRIPTOR_SIZE) {
+                Some(rva) => rva,
+                None => return,
+            };
+        }
+    }
+
+    fn read_u32(&self, rva: u32) -> Option<u32> {
+        let bytes = self.data_at_rva(rva)?.get(..4)?;
+        Some(u32::from_le_bytes(bytes.try_into().ok()?))
+    }
+
+    fn read_u64(&self, rva: u32) -> Option<u64> {
+        let bytes = self.data_at_rva(rva)?.get(..8)?;
+        Some(u64::from_le_bytes(bytes.try_into().ok()?))
+    }
+
+    fn read_str(&self, rva: u32) -> Option<&'data str> {
+        let data = self.data_at_rva(rva)?;
+        let len = data.iter().position(|&b| b == 0)?;
+        std::str::from_utf8(&data[..len]).ok()
+    }
+
+    /// Returns the name of a section.
+    ///
+    /// Section names longer than eight bytes, such as `.debug_info`, are stored in the COFF string
+    /// table, which follows the symbol table. The section header references them as `/<offset>`.
+    fn section_name<'s>(&'s self, section: &'s SectionTable) -> Option<&'s str> {
+        if let Some(ref name) = section.real_name {
+            return Some(name);
+        }
+
+        let offset = match section.name.strip_prefix(b"/") {
+            Some(digits) => {
+                let len = digits.iter().position(|&b| b == 0).unwrap_or(digits.len());
+                std::str::from_utf8(&digits[..len])
+                    .ok()?
+                    .parse::<usize>()
+                    .ok()?
+            }
+            None => return section.name().ok(),
+        };
+
+        // Stripped images do not have a symbol table, and therefore no string table either.
+        let coff_header = &self.pe.header.coff_header;
+        if coff_header.pointer_to_symbol_table == 0 {
+            return None;
+        }
+
+        let symbols_size = coff_header.number_of_symbol_table as usize * COFF_SYMBOL_SIZE;
+        let string_table = coff_header.pointer_to_symbol_table as usize + symbols_size;
+
+        let data = self.data.get(string_table.checked_add(offset)?..)?;
+        let len = data.iter().position(|&b| b == 0)?;
+        std::str::from_utf8(&data[..len]).ok()
+    }
 }
 
 impl fmt::Debug for PeObject<'_> {
@@ -323,6 +601,44 @@ impl<'data: 'object, 'object> ObjectLike<'data, 'object> for PeObject<'data> {
     }
 }
 
+impl<'data> Dwarf<'data> for PeObject<'data> {
+    fn endianity(&self) -> Endian {
+        // Windows only supports little-endian platforms.
+        Endian::Little
+    }
+
+    fn raw_section(&self, name: &str) -> Option<DwarfSection<'data>> {
+        let section = self.pe.sections.iter().find(|section| {
+            self.section_name(section)
+                .and_then(|n| n.strip_prefix('.'))
+                .map_or(false, |n| n == name)
+        })?;
+
+        // The raw data is padded to the file alignment, while the virtual size is exact. The
+        // virtual size is zero in object files.
+        let size = match section.virtual_size {
+            0 => section.size_of_raw_data,
+            virtual_size => virtual_size.min(section.size_of_raw_data),
+        };
+
+        let offset = section.pointer_to_raw_data as usize;
+        let data = self.data.get(offset..offset.checked_add(size as usize)?)?;
+
+        let align = self
+            .pe
+            .header
+            .optional_header
+            .map_or(0, |header| header.windows_fields.section_alignment);
+
+        Some(DwarfSection {
+            data: Cow::Borrowed(data),
+            address: self.load_address() + u64::from(section.virtual_address),
+            offset: offset as u64,
+            align: u64::from(align),
+        })
+    }
+}
+
 /// An iterator over symbols in the PE file.
 ///
 /// Returned by [`PeObject::symbols`](struct.PeObject.html#method.symbols).
@@ -338,35 +654,143 @@ impl<'data, 'object> Iterator for PeSymbolIterator<'data, 'object> {
             name: export.name.map(Cow::Borrowed),
             address: export.rva as u64,
             size: export.size as u64,
+            multiple: false,
+            parameter_size: None,
+        })
+    }
+}
+
+/// The name or ordinal by which a function is imported from another module.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum PeImportName<'data> {
+    /// The function is referenced by its exported name.
+    Name(&'data str),
+    /// The function is referenced by its ordinal in the export table of the module.
+    Ordinal(u16),
+}
+
+/// The target of a forwarded export.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PeForwarder<'data> {
+    /// The name of the module implementing the function, without file extension.
+    pub module: &'data str,
+    /// The name or ordinal of the function within that module.
+    pub name: PeImportName<'data>,
+}
+
+/// An entry in the export table of a PE file.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PeExport<'data> {
+    /// The exported name, or `None` if the function is only exported by ordinal.
+    pub name: Option<&'data str>,
+    /// The relative address of the exported function.
+    ///
+    /// For forwarded exports, this is the address of the forwarder string in the export table.
+    pub address: u64,
+    /// The function that this export is forwarded to, if it is implemented in another module.
+    pub forwarder: Option<PeForwarder<'data>>,
+}
+
+/// An iterator over exports in the PE file.
+///
+/// Returned by [`PeObject::exports`](struct.PeObject.html#method.exports).
+pub struct PeExportIterator<'data, 'object> {
+    exports: std::slice::Iter<'object, pe::export::Export<'data>>,
+}
+
+impl<'data, 'object> Iterator for PeExportIterator<'data, 'object> {
+    type Item = PeExport<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.exports.next().map(|export| {
+            let forwarder = export.reexport.as_ref().map(|reexport| match *reexport {
+                pe::export::Reexport::DLLName { export, lib } => PeForwarder {
+                    module: lib,
+                    name: PeImportName::Name(export),
+                },
+                pe::export::Reexport::DLLOrdinal { ordinal, lib } => PeForwarder {
+                    module: lib,
+                    name: PeImportName::Ordinal(ordinal as u16),
+                },
+            });
+
+            PeExport {
+                name: export.name,
+                address: export.rva as u64,
+                forwarder,
+            }
         })
     }
 }
 
+/// A function imported from another module.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PeImport<'data> {
+    /// The file name of the imported module, such as `KERNEL32.dll`.
+    pub module: &'data str,
+    /// The name or ordinal of the imported function.
+    pub name: PeImportName<'data>,
+    /// The relative address of the slot in the import address table (IAT).
+    ///
+    /// The loader writes the address of the imported function into this slot, and calls to the
+    /// function jump through it.
+    pub iat_address: u64,
+    /// Whether the function is resolved lazily on first call through the delay-load import table.
+    pub delay_load: bool,
+}
+
+/// An iterator over imports in the PE file.
+///
+/// Returned by [`PeObject::imports`](struct.PeObject.html#method.imports).
+pub struct PeImportIterator<'data> {
+    imports: std::vec::IntoIter<PeImport<'data>>,
+}
+
+impl<'data> Iterator for PeImportIterator<'data> {
+    type Item = PeImport<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.imports.next()
+    }
+}
+
 /// Debug session for PE objects.
 ///
-/// Since debug information in PE containers is not supported, this session consists of NoOps and
-/// always returns empty results.
-#[derive(Debug)]
+/// PE files only contain debug information if it has been embedded as DWARF by MinGW or the
+/// `*-windows-gnu` toolchains. Otherwise, this session always returns empty results.
 pub struct PeDebugSession<'data> {
-    _ph: PhantomData<&'data ()>,
+    dwarf: DwarfDebugSession<'data>,
 }
 
 impl<'data> PeDebugSession<'data> {
     /// Returns an iterator over all functions in this debug file.
     pub fn functions(&self) -> PeFunctionIterator<'_> {
-        std::iter::empty()
+        PeFunctionIterator(self.dwarf.functions())
     }
 
     /// Returns an iterator over all source files referenced by this debug file.
     pub fn files(&self) -> PeFileIterator<'_> {
-        std::iter::empty()
+        PeFileIterator(self.dwarf.files())
     }
 
     /// Looks up a file's source contents by its full canonicalized path.
     ///
     /// The given path must be canonicalized.
-    pub fn source_by_path(&self, _path: &str) -> Result<Option<Cow<'_, str>>, PeError> {
-        Ok(None)
+    pub fn source_by_path(&self, path: &str) -> Result<Option<Cow<'_, str>>, PeError> {
+        self.dwarf.source_by_path(path).map_err(PeError::new)
+    }
+
+    /// Returns the DWARF debugging session of this file.
+    ///
+    /// This provides access to information that is specific to DWARF, such as variables and types.
+    pub fn dwarf(&self) -> &DwarfDebugSession<'data> {
+        &self.dwarf
+    }
+}
+
+impl fmt::Debug for PeDebugSession<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PeDebugSession").finish()
     }
 }
 
@@ -389,7 +813,25 @@ impl<'session> DebugSession<'session> for PeDebugSession<'_> {
 }
 
 /// An iterator over functions in a PE file.
-pub type PeFunctionIterator<'s> = std::iter::Empty<Result<Function<'s>, PeError>>;
+pub struct PeFunctionIterator<'s>(DwarfFunctionIterator<'s>);
+
+impl<'s> Iterator for PeFunctionIterator<'s> {
+    type Item = Result<Function<'s>, PeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.0.next()?.map_err(PeError::new))
+    }
+}
+
+impl std::iter::FusedIterator for PeFunctionIterator<'_> {}
 
 /// An iterator over source files in a PE file.
-pub type PeFileIterator<'s> = std::iter::Empty<Result<FileEntry<'s>, PeError>>;
+pub struct PeFileIterator<'s>(DwarfFileIterator<'s>);
+
+impl<'s> Iterator for PeFileIterator<'s> {
+    type Item = Result<FileEntry<'s>, PeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.0.next()?.map_err(PeError::new))
+    }
+}
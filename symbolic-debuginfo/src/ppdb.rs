@@ -0,0 +1,1242 @@
+//! Support for Portable PDBs, the cross-platform debug companion format of .NET assemblies.
+//!
+//! Portable PDBs are stored in the ECMA-335 metadata format, which is also used for the metadata of
+//! .NET assemblies. Instead of type system tables, they contain a `#Pdb` stream and debugging
+//! tables, such as documents and sequence points of methods. See the [Portable PDB specification]
+//! for more information.
+//!
+//! Managed code is not identified by addresses, but by the metadata token of a method and an offset
+//! into its IL code. Use [`PortablePdbDebugSession::lookup_line`] to resolve source locations.
+//!
+//! [Portable PDB specification]: https://github.com/dotnet/runtime/blob/main/docs/design/specs/PortablePdb-Metadata.md
+//! [`PortablePdbDebugSession::lookup_line`]: struct.PortablePdbDebugSession.html#method.lookup_line
+
+use std::borrow::Cow;
+use std::convert::TryInto;
+use std::error::Error;
+use std::fmt;
+
+use thiserror::Error;
+
+use symbolic_common::{Arch, AsSelf, CodeId, DebugId, Language, Name, NameMangling};
+
+use crate::base::*;
+use crate::private::{decompress_embedded_source, Parse};
+use crate::sourcelink::{SourceLinkError, SourceLinkMappings};
+
+/// Signature at the start of the metadata root.
+const METADATA_SIGNATURE: &[u8] = b"BSJB";
+
+/// The number of metadata tables addressable by the table bit masks.
+const TABLE_COUNT: usize = 64;
+
+const TABLE_MODULE: usize = 0x00;
+const TABLE_TYPE_REF: usize = 0x01;
+const TABLE_TYPE_DEF: usize = 0x02;
+const TABLE_FIELD: usize = 0x04;
+const TABLE_METHOD_DEF: usize = 0x06;
+const TABLE_PARAM: usize = 0x08;
+const TABLE_INTERFACE_IMPL: usize = 0x09;
+const TABLE_MEMBER_REF: usize = 0x0a;
+const TABLE_DECL_SECURITY: usize = 0x0e;
+const TABLE_STAND_ALONE_SIG: usize = 0x11;
+const TABLE_EVENT: usize = 0x14;
+const TABLE_PROPERTY: usize = 0x17;
+const TABLE_MODULE_REF: usize = 0x1a;
+const TABLE_TYPE_SPEC: usize = 0x1b;
+const TABLE_ASSEMBLY: usize = 0x20;
+const TABLE_ASSEMBLY_REF: usize = 0x23;
+const TABLE_FILE: usize = 0x26;
+const TABLE_EXPORTED_TYPE: usize = 0x27;
+const TABLE_MANIFEST_RESOURCE: usize = 0x28;
+const TABLE_GENERIC_PARAM: usize = 0x2a;
+const TABLE_METHOD_SPEC: usize = 0x2b;
+const TABLE_GENERIC_PARAM_CONSTRAINT: usize = 0x2c;
+const TABLE_DOCUMENT: usize = 0x30;
+const TABLE_METHOD_DEBUG_INFORMATION: usize = 0x31;
+const TABLE_LOCAL_SCOPE: usize = 0x32;
+const TABLE_LOCAL_VARIABLE: usize = 0x33;
+const TABLE_LOCAL_CONSTANT: usize = 0x34;
+const TABLE_IMPORT_SCOPE: usize = 0x35;
+const TABLE_STATE_MACHINE_METHOD: usize = 0x36;
+const TABLE_CUSTOM_DEBUG_INFORMATION: usize = 0x37;
+
+/// Number of low address bits holding the IL offset within a method.
+///
+/// The `MethodDef` row is stored above these bits, so that addresses fit into 48 bits.
+const IL_OFFSET_BITS: u32 = 24;
+
+/// Tables referenced by the `HasCustomDebugInformation` coded index, in the order of their tags.
+const HAS_CUSTOM_DEBUG_INFORMATION: &[usize] = &[
+    TABLE_METHOD_DEF,
+    TABLE_FIELD,
+    TABLE_TYPE_REF,
+    TABLE_TYPE_DEF,
+    TABLE_PARAM,
+    TABLE_INTERFACE_IMPL,
+    TABLE_MEMBER_REF,
+    TABLE_MODULE,
+    TABLE_DECL_SECURITY,
+    TABLE_PROPERTY,
+    TABLE_EVENT,
+    TABLE_STAND_ALONE_SIG,
+    TABLE_MODULE_REF,
+    TABLE_TYPE_SPEC,
+    TABLE_ASSEMBLY,
+    TABLE_ASSEMBLY_REF,
+    TABLE_FILE,
+    TABLE_EXPORTED_TYPE,
+    TABLE_MANIFEST_RESOURCE,
+    TABLE_GENERIC_PARAM,
+    TABLE_GENERIC_PARAM_CONSTRAINT,
+    TABLE_METHOD_SPEC,
+    TABLE_DOCUMENT,
+    TABLE_LOCAL_SCOPE,
+    TABLE_LOCAL_VARIABLE,
+    TABLE_LOCAL_CONSTANT,
+    TABLE_IMPORT_SCOPE,
+];
+
+/// Number of tag bits in the `HasCustomDebugInformation` coded index.
+const HAS_CUSTOM_DEBUG_INFORMATION_BITS: u32 = 5;
+
+/// GUID of the SHA-1 document hash algorithm, `ff1816ec-aa5e-4d10-87f7-6f4963833460`.
+const GUID_SHA1: [u8; 16] = [
+    0xec, 0x16, 0x18, 0xff, 0x5e, 0xaa, 0x10, 0x4d, 0x87, 0xf7, 0x6f, 0x49, 0x63, 0x83, 0x34, 0x60,
+];
+
+/// GUID of the SHA-256 document hash algorithm, `8829d00f-11b8-4213-878b-770e8597ac16`.
+const GUID_SHA256: [u8; 16] = [
+    0x0f, 0xd0, 0x29, 0x88, 0xb8, 0x11, 0x13, 0x42, 0x87, 0x8b, 0x77, 0x0e, 0x85, 0x97, 0xac, 0x16,
+];
+
+/// GUID of embedded source custom debug information, `0e8a571b-6926-466e-b4ad-8ab04611f5fe`.
+const GUID_EMBEDDED_SOURCE: [u8; 16] = [
+    0x1b, 0x57, 0x8a, 0x0e, 0x26, 0x69, 0x6e, 0x46, 0xb4, 0xad, 0x8a, 0xb0, 0x46, 0x11, 0xf5, 0xfe,
+];
+
+/// GUID of SourceLink custom debug information, `cc110556-a091-4d38-9fec-25ab9a351a6a`.
+const GUID_SOURCE_LINK: [u8; 16] = [
+    0x56, 0x05, 0x11, 0xcc, 0x91, 0xa0, 0x38, 0x4d, 0x9f, 0xec, 0x25, 0xab, 0x9a, 0x35, 0x1a, 0x6a,
+];
+
+/// Line number of hidden sequence points.
+const HIDDEN_LINE: u32 = 0xfee_fee;
+
+/// The error type for [`PortablePdbError`].
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PortablePdbErrorKind {
+    /// The Portable PDB file is corrupted.
+    BadObject,
+
+    /// The embedded SourceLink mappings are invalid.
+    BadSourceLink,
+}
+
+impl fmt::Display for PortablePdbErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BadObject => write!(f, "invalid portable pdb file"),
+            Self::BadSourceLink => write!(f, "invalid source link mappings"),
+        }
+    }
+}
+
+/// An error when dealing with [`PortablePdbObject`](struct.PortablePdbObject.html).
+#[derive(Debug, Error)]
+#[error("{kind}")]
+pub struct PortablePdbError {
+    kind: PortablePdbErrorKind,
+    #[source]
+    source: Option<Box<dyn Error + Send + Sync + 'static>>,
+}
+
+impl PortablePdbError {
+    /// Creates a new Portable PDB error from a known kind of error as well as an arbitrary error
+    /// payload.
+    fn new<E>(kind: PortablePdbErrorKind, source: E) -> Self
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        let source = Some(source.into());
+        Self { kind, source }
+    }
+
+    /// Returns the corresponding [`PortablePdbErrorKind`] for this error.
+    pub fn kind(&self) -> PortablePdbErrorKind {
+        self.kind
+    }
+}
+
+impl From<PortablePdbErrorKind> for PortablePdbError {
+    fn from(kind: PortablePdbErrorKind) -> Self {
+        Self { kind, source: None }
+    }
+}
+
+impl From<SourceLinkError> for PortablePdbError {
+    fn from(e: SourceLinkError) -> Self {
+        Self::new(PortablePdbErrorKind::BadSourceLink, e)
+    }
+}
+
+/// Shorthand for a Portable PDB error caused by malformed data.
+fn bad_object() -> PortablePdbError {
+    PortablePdbErrorKind::BadObject.into()
+}
+
+/// A cursor for reading little-endian values and compressed integers.
+struct Reader<'data> {
+    data: &'data [u8],
+    offset: usize,
+}
+
+impl<'data> Reader<'data> {
+    fn new(data: &'data [u8]) -> Self {
+        Reader { data, offset: 0 }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.offset >= self.data.len()
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'data [u8], PortablePdbError> {
+        let end = self.offset.checked_add(len).ok_or_else(bad_object)?;
+        let bytes = self.data.get(self.offset..end).ok_or_else(bad_object)?;
+        self.offset = end;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, PortablePdbError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, PortablePdbError> {
+        Ok(u16::from_le_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, PortablePdbError> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, PortablePdbError> {
+        Ok(u64::from_le_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+
+    /// Reads a null-terminated string padded to a multiple of four bytes.
+    fn padded_str(&mut self) -> Result<&'data [u8], PortablePdbError> {
+        let rest = &self.data[self.offset.min(self.data.len())..];
+        let len = rest.iter().position(|&b| b == 0).ok_or_else(bad_object)?;
+        let padded = (len + 4) & !3;
+        Ok(&self.bytes(padded)?[..len])
+    }
+
+    /// Reads a compressed unsigned integer, returning the value and its encoded size.
+    fn compressed_raw(&mut self) -> Result<(u32, usize), PortablePdbError> {
+        let first = self.u8()?;
+        if first & 0x80 == 0 {
+            Ok((u32::from(first), 1))
+        } else if first & 0xc0 == 0x80 {
+            let second = self.u8()?;
+            Ok(((u32::from(first & 0x3f) << 8) | u32::from(second), 2))
+        } else if first & 0xe0 == 0xc0 {
+            let rest = self.bytes(3)?;
+            let value = (u32::from(first & 0x1f) << 24)
+                | (u32::from(rest[0]) << 16)
+                | (u32::from(rest[1]) << 8)
+                | u32::from(rest[2]);
+            Ok((value, 4))
+        } else {
+            Err(bad_object())
+        }
+    }
+
+    /// Reads a compressed unsigned integer.
+    fn compressed_u32(&mut self) -> Result<u32, PortablePdbError> {
+        Ok(self.compressed_raw()?.0)
+    }
+
+    /// Reads a compressed signed integer, which stores the sign in the least significant bit.
+    fn compressed_i32(&mut self) -> Result<i32, PortablePdbError> {
+        let (raw, size) = self.compressed_raw()?;
+        let value = (raw >> 1) as i32;
+        if raw & 1 == 0 {
+            return Ok(value);
+        }
+
+        let bias = match size {
+            1 => 0x40,
+            2 => 0x2000,
+            _ => 0x1000_0000,
+        };
+
+        Ok(value - bias)
+    }
+}
+
+/// The type of a column in a metadata table.
+#[derive(Clone, Copy, Debug)]
+enum Column {
+    /// A constant of the given size.
+    Fixed(usize),
+    /// An index into the `#Strings` heap.
+    String,
+    /// An index into the `#GUID` heap.
+    Guid,
+    /// An index into the `#Blob` heap.
+    Blob,
+    /// A row index into the given table.
+    Table(usize),
+    /// A `HasCustomDebugInformation` coded index.
+    HasCustomDebugInformation,
+}
+
+/// Returns the columns of a table, or `None` if the table is not supported in Portable PDBs.
+fn table_columns(table: usize) -> Option<&'static [Column]> {
+    use Column::*;
+
+    Some(match table {
+        TABLE_DOCUMENT => &[Blob, Guid, Blob, Guid],
+        TABLE_METHOD_DEBUG_INFORMATION => &[Table(TABLE_DOCUMENT), Blob],
+        TABLE_LOCAL_SCOPE => &[
+            Table(TABLE_METHOD_DEF),
+            Table(TABLE_IMPORT_SCOPE),
+            Table(TABLE_LOCAL_VARIABLE),
+            Table(TABLE_LOCAL_CONSTANT),
+            Fixed(4),
+            Fixed(4),
+        ],
+        TABLE_LOCAL_VARIABLE => &[Fixed(2), Fixed(2), String],
+        TABLE_LOCAL_CONSTANT => &[String, Blob],
+        TABLE_IMPORT_SCOPE => &[Table(TABLE_IMPORT_SCOPE), Blob],
+        TABLE_STATE_MACHINE_METHOD => &[Table(TABLE_METHOD_DEF), Table(TABLE_METHOD_DEF)],
+        TABLE_CUSTOM_DEBUG_INFORMATION => &[HasCustomDebugInformation, Guid, Blob],
+        _ => return None,
+    })
+}
+
+/// A metadata table with fixed-size rows.
+#[derive(Clone, Debug, Default)]
+struct Table<'data> {
+    rows: u32,
+    row_size: usize,
+    column_sizes: Vec<usize>,
+    data: &'data [u8],
+}
+
+impl<'data> Table<'data> {
+    /// Reads the value of a column in the given row, starting at 1.
+    fn get(&self, row: u32, column: usize) -> Result<u32, PortablePdbError> {
+        if row == 0 || row > self.rows {
+            return Err(bad_object());
+        }
+
+        let row_offset = (row - 1) as usize * self.row_size;
+        let offset = row_offset + self.column_sizes[..column].iter().sum::<usize>();
+        let mut reader = Reader::new(self.data);
+        reader.bytes(offset)?;
+
+        match self.column_sizes[column] {
+            2 => reader.u16().map(u32::from),
+            _ => reader.u32(),
+        }
+    }
+}
+
+/// The name and contents of a metadata stream.
+type Stream<'data> = (&'data [u8], &'data [u8]);
+
+/// The metadata streams and tables of a Portable PDB.
+#[derive(Clone, Debug)]
+struct Metadata<'data> {
+    pdb_id: &'data [u8],
+    guids: &'data [u8],
+    blobs: &'data [u8],
+    tables: Vec<Table<'data>>,
+}
+
+impl<'data> Metadata<'data> {
+    /// Returns the streams in the metadata root by name.
+    fn streams(data: &'data [u8]) -> Result<Vec<Stream<'data>>, PortablePdbError> {
+        let mut reader = Reader::new(data);
+        if reader.bytes(4)? != METADATA_SIGNATURE {
+            return Err(bad_object());
+        }
+
+        // Major and minor version and a reserved field.
+        reader.bytes(8)?;
+        let version_len = reader.u32()? as usize;
+        reader.bytes(version_len)?;
+        let _flags = reader.u16()?;
+        let count = reader.u16()?;
+
+        let mut streams = Vec::with_capacity(count.into());
+        for _ in 0..count {
+            let offset = reader.u32()? as usize;
+            let size = reader.u32()? as usize;
+            let name = reader.padded_str()?;
+            let end = offset.checked_add(size).ok_or_else(bad_object)?;
+            streams.push((name, data.get(offset..end).ok_or_else(bad_object)?));
+        }
+
+        Ok(streams)
+    }
+
+    fn parse(data: &'data [u8]) -> Result<Self, PortablePdbError> {
+        let mut pdb_stream = None;
+        let mut tables_stream = None;
+        let mut guids = &[][..];
+        let mut blobs = &[][..];
+
+        for (name, stream) in Self::streams(data)? {
+            match name {
+                b"#Pdb" => pdb_stream = Some(stream),
+                b"#~" => tables_stream = Some(stream),
+                b"#GUID" => guids = stream,
+                b"#Blob" => blobs = stream,
+                _ => {}
+            }
+        }
+
+        // The #Pdb stream contains the PDB id, the entry point and the row counts of type system
+        // tables in the corresponding assembly. These are needed to compute the size of indexes.
+        let mut reader = Reader::new(pdb_stream.ok_or_else(bad_object)?);
+        let pdb_id = reader.bytes(20)?;
+        let _entry_point = reader.u32()?;
+        let referenced = reader.u64()?;
+
+        let mut row_counts = [0u32; TABLE_COUNT];
+        for (table, count) in row_counts.iter_mut().enumerate() {
+            if referenced & (1 << table) != 0 {
+                *count = reader.u32()?;
+            }
+        }
+
+        let mut reader = Reader::new(tables_stream.ok_or_else(bad_object)?);
+        reader.bytes(6)?;
+        let heap_sizes = reader.u8()?;
+        reader.bytes(1)?;
+        let present = reader.u64()?;
+        let _sorted = reader.u64()?;
+
+        for (table, count) in row_counts.iter_mut().enumerate() {
+            if present & (1 << table) != 0 {
+                *count = reader.u32()?;
+            }
+        }
+
+        let index_size = |large: bool| if large { 4 } else { 2 };
+        let column_size = |column: Column| match column {
+            Column::Fixed(size) => size,
+            Column::String => index_size(heap_sizes & 0x01 != 0),
+            Column::Guid => index_size(heap_sizes & 0x02 != 0),
+            Column::Blob => index_size(heap_sizes & 0x04 != 0),
+            Column::Table(table) => index_size(row_counts[table] > 0xffff),
+            Column::HasCustomDebugInformation => {
+                let max_rows = HAS_CUSTOM_DEBUG_INFORMATION
+                    .iter()
+                    .map(|&table| row_counts[table])
+                    .max()
+                    .unwrap_or(0);
+                index_size(max_rows >= 1 << (16 - HAS_CUSTOM_DEBUG_INFORMATION_BITS))
+            }
+        };
+
+        let mut tables = vec![Table::default(); TABLE_COUNT];
+        for (index, table) in tables.iter_mut().enumerate() {
+            if present & (1 << index) == 0 {
+                continue;
+            }
+
+            // Type system tables are stored in the assembly, not in the Portable PDB.
+            let columns = table_columns(index).ok_or_else(bad_object)?;
+            let column_sizes = columns.iter().map(|&c| column_size(c)).collect::<Vec<_>>();
+            let row_size = column_sizes.iter().sum::<usize>();
+            let rows = row_counts[index];
+
+            let size = row_size.checked_mul(rows as usize).ok_or_else(bad_object)?;
+            table.data = reader.bytes(size)?;
+            table.rows = rows;
+            table.row_size = row_size;
+            table.column_sizes = column_sizes;
+        }
+
+        Ok(Metadata {
+            pdb_id,
+            guids,
+            blobs,
+            tables,
+        })
+    }
+
+    fn table(&self, table: usize) -> &Table<'data> {
+        &self.tables[table]
+    }
+
+    /// Reads a blob from the `#Blob` heap. Index zero refers to the empty blob.
+    fn blob(&self, index: u32) -> Result<&'data [u8], PortablePdbError> {
+        let mut reader = Reader::new(self.blobs);
+        reader.bytes(index as usize)?;
+        let len = reader.compressed_u32()?;
+        reader.bytes(len as usize)
+    }
+
+    /// Reads a GUID from the `#GUID` heap. Index zero refers to no GUID.
+    fn guid(&self, index: u32) -> Option<&'data [u8]> {
+        let start = (index.checked_sub(1)? as usize) * 16;
+        self.guids.get(start..start + 16)
+    }
+
+    /// Returns the values of custom debug information of the given kind.
+    ///
+    /// If `parent` is specified, only information attached to the given `HasCustomDebugInformation`
+    /// coded index is returned.
+    fn custom_debug_information(
+        &self,
+        parent: Option<u32>,
+        kind: &[u8],
+    ) -> Result<Vec<&'data [u8]>, PortablePdbError> {
+        let table = self.table(TABLE_CUSTOM_DEBUG_INFORMATION);
+        let mut values = Vec::new();
+
+        for row in 1..=table.rows {
+            if parent.map_or(false, |parent| table.get(row, 0).ok() != Some(parent)) {
+                continue;
+            }
+
+            if self.guid(table.get(row, 1)?) == Some(kind) {
+                values.push(self.blob(table.get(row, 2)?)?);
+            }
+        }
+
+        Ok(values)
+    }
+
+    /// Decodes the path of a document from its name blob.
+    ///
+    /// The blob starts with a separator character, followed by blob indexes of the path parts.
+    fn document_name(&self, index: u32) -> Result<String, PortablePdbError> {
+        let mut reader = Reader::new(self.blob(index)?);
+        let separator = match reader.u8()? {
+            0 => None,
+            separator => Some(char::from(separator)),
+        };
+
+        let mut name = String::new();
+        let mut first = true;
+        while !reader.is_empty() {
+            if let (false, Some(separator)) = (first, separator) {
+                name.push(separator);
+            }
+
+            let part = self.blob(reader.compressed_u32()?)?;
+            name.push_str(&String::from_utf8_lossy(part));
+            first = false;
+        }
+
+        Ok(name)
+    }
+
+    /// Decodes all documents from the document table.
+    fn documents(&self) -> Result<Vec<Document>, PortablePdbError> {
+        let table = self.table(TABLE_DOCUMENT);
+        let mut documents = Vec::with_capacity(table.rows as usize);
+
+        for row in 1..=table.rows {
+            let path = self.document_name(table.get(row, 0)?)?;
+            let hash = self.blob(table.get(row, 2)?)?;
+
+            let checksum = match self.guid(table.get(row, 1)?) {
+                Some(guid) if guid == GUID_SHA1 => hash.try_into().ok().map(FileChecksum::Sha1),
+                Some(guid) if guid == GUID_SHA256 => hash.try_into().ok().map(FileChecksum::Sha256),
+                _ => None,
+            };
+
+            documents.push(Document { path, checksum });
+        }
+
+        Ok(documents)
+    }
+
+    /// Decodes the sequence points of the method at the given row.
+    fn sequence_points(&self, row: u32) -> Result<Vec<SequencePoint>, PortablePdbError> {
+        let table = self.table(TABLE_METHOD_DEBUG_INFORMATION);
+        if row == 0 || row > table.rows {
+            return Ok(Vec::new());
+        }
+
+        let document = table.get(row, 0)?;
+        let blob = self.blob(table.get(row, 1)?)?;
+        parse_sequence_points(blob, document)
+    }
+}
+
+/// Decodes a sequence points blob.
+///
+/// The initial document is taken from the method's debug information row. If it is zero, the blob
+/// declares the initial document instead.
+fn parse_sequence_points(
+    blob: &[u8],
+    document: u32,
+) -> Result<Vec<SequencePoint>, PortablePdbError> {
+    if blob.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut document = document;
+    let mut reader = Reader::new(blob);
+    let _local_signature = reader.compressed_u32()?;
+    if document == 0 {
+        document = reader.compressed_u32()?;
+    }
+
+    let mut points = Vec::new();
+    let mut il_offset = 0u32;
+    let mut previous: Option<(u32, u32)> = None;
+
+    while !reader.is_empty() {
+        let delta_il_offset = reader.compressed_u32()?;
+
+        // A zero offset delta after the first record switches to another document.
+        if delta_il_offset == 0 && !points.is_empty() {
+            document = reader.compressed_u32()?;
+            continue;
+        }
+
+        il_offset = il_offset
+            .checked_add(delta_il_offset)
+            .ok_or_else(bad_object)?;
+
+        let delta_lines = reader.compressed_u32()?;
+        let delta_columns = match delta_lines {
+            0 => reader.compressed_u32()? as i64,
+            _ => reader.compressed_i32()?.into(),
+        };
+
+        if delta_lines == 0 && delta_columns == 0 {
+            points.push(SequencePoint {
+                il_offset,
+                document,
+                start_line: HIDDEN_LINE,
+                start_column: 0,
+                end_line: HIDDEN_LINE,
+                end_column: 0,
+            });
+            continue;
+        }
+
+        let (start_line, start_column) = match previous {
+            None => (reader.compressed_u32()?, reader.compressed_u32()?),
+            Some((line, column)) => {
+                let line = i64::from(line) + i64::from(reader.compressed_i32()?);
+                let column = i64::from(column) + i64::from(reader.compressed_i32()?);
+                let line = line.try_into().map_err(|_| bad_object())?;
+                let column = column.try_into().map_err(|_| bad_object())?;
+                (line, column)
+            }
+        };
+
+        previous = Some((start_line, start_column));
+        points.push(SequencePoint {
+            il_offset,
+            document,
+            start_line,
+            start_column,
+            end_line: start_line.checked_add(delta_lines).ok_or_else(bad_object)?,
+            end_column: (i64::from(start_column) + delta_columns)
+                .try_into()
+                .map_err(|_| bad_object())?,
+        });
+    }
+
+    Ok(points)
+}
+
+/// A source file referenced by a Portable PDB.
+#[derive(Clone, Debug)]
+struct Document {
+    path: String,
+    checksum: Option<FileChecksum>,
+}
+
+impl Document {
+    fn file_info(&self) -> FileInfo<'_> {
+        FileInfo::from_path(self.path.as_bytes())
+    }
+}
+
+/// A sequence point mapping an IL offset of a method to a source location.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SequencePoint {
+    /// The offset into the IL code of the method.
+    pub il_offset: u32,
+    /// The row of the document in the document table, starting at 1.
+    pub document: u32,
+    /// The line of the first character, starting at 1.
+    pub start_line: u32,
+    /// The column of the first character, starting at 1.
+    pub start_column: u32,
+    /// The line of the last character.
+    pub end_line: u32,
+    /// The column after the last character.
+    pub end_column: u32,
+}
+
+impl SequencePoint {
+    /// Returns `true` if this sequence point hides IL code from the debugger.
+    ///
+    /// Hidden sequence points do not correspond to any source location.
+    pub fn is_hidden(&self) -> bool {
+        self.start_line == HIDDEN_LINE
+    }
+}
+
+/// Returns the row of a `MethodDef` metadata token, or `None` for other tokens.
+fn method_row(token: u32) -> Option<u32> {
+    if token >> 24 == TABLE_METHOD_DEF as u32 {
+        Some(token & 0x00ff_ffff)
+    } else {
+        None
+    }
+}
+
+/// Portable PDB, the debug companion format of .NET assemblies.
+pub struct PortablePdbObject<'data> {
+    metadata: Metadata<'data>,
+    data: &'data [u8],
+}
+
+impl<'data> PortablePdbObject<'data> {
+    /// Tests whether the buffer could contain a Portable PDB.
+    pub fn test(data: &[u8]) -> bool {
+        data.starts_with(METADATA_SIGNATURE)
+            && Metadata::streams(data).map_or(false, |streams| {
+                streams.iter().any(|&(name, _)| name == b"#Pdb")
+            })
+    }
+
+    /// Tries to parse a Portable PDB from the given slice.
+    pub fn parse(data: &'data [u8]) -> Result<Self, PortablePdbError> {
+        let metadata = Metadata::parse(data)?;
+        Ok(PortablePdbObject { metadata, data })
+    }
+
+    /// The container file format, which is always `FileFormat::PortablePdb`.
+    pub fn file_format(&self) -> FileFormat {
+        FileFormat::PortablePdb
+    }
+
+    /// The code identifier of this object, always `None`.
+    ///
+    /// Portable PDBs do not contain sufficient information to infer the code identifier of their
+    /// assemblies.
+    pub fn code_id(&self) -> Option<CodeId> {
+        None
+    }
+
+    /// The debug information identifier of this Portable PDB.
+    ///
+    /// The PDB id consists of a GUID and a timestamp, which is used in place of the age. The
+    /// CodeView record of the corresponding assembly contains the same GUID.
+    pub fn debug_id(&self) -> DebugId {
+        let (guid, stamp) = self.metadata.pdb_id.split_at(16);
+        let stamp = u32::from_le_bytes(stamp.try_into().unwrap());
+        DebugId::from_guid_age(guid, stamp).unwrap_or_default()
+    }
+
+    /// The CPU architecture of this object, always `Arch::Unknown`.
+    ///
+    /// Managed code is compiled to architecture-independent IL.
+    pub fn arch(&self) -> Arch {
+        Arch::Unknown
+    }
+
+    /// The kind of this object, which is always `Debug`.
+    pub fn kind(&self) -> ObjectKind {
+        ObjectKind::Debug
+    }
+
+    /// The address at which the image prefers to be loaded into memory.
+    ///
+    /// This is always 0, since managed code is not addressed by instruction addresses.
+    pub fn load_address(&self) -> u64 {
+        0
+    }
+
+    /// Determines whether this object exposes a public symbol table.
+    ///
+    /// Portable PDBs never have symbols. Method names are stored in the metadata of the assembly.
+    pub fn has_symbols(&self) -> bool {
+        false
+    }
+
+    /// Returns an iterator over symbols in the public symbol table.
+    pub fn symbols(&self) -> PortablePdbSymbolIterator<'data> {
+        std::iter::empty()
+    }
+
+    /// Returns an ordered map of symbols in the symbol table.
+    pub fn symbol_map(&self) -> SymbolMap<'data> {
+        self.symbols().collect()
+    }
+
+    /// Determines whether this object contains debug information.
+    pub fn has_debug_info(&self) -> bool {
+        self.metadata.table(TABLE_METHOD_DEBUG_INFORMATION).rows > 0
+    }
+
+    /// Constructs a debugging session.
+    pub fn debug_session(&self) -> Result<PortablePdbDebugSession<'data>, PortablePdbError> {
+        PortablePdbDebugSession::build(&self.metadata)
+    }
+
+    /// Determines whether this object contains stack unwinding information.
+    pub fn has_unwind_info(&self) -> bool {
+        false
+    }
+
+    /// Determines whether this object contains embedded source.
+    pub fn has_sources(&self) -> bool {
+        self.metadata
+            .custom_debug_information(None, &GUID_EMBEDDED_SOURCE)
+            .map_or(false, |sources| !sources.is_empty())
+    }
+
+    /// Returns the SourceLink mappings embedded in the Portable PDB, if any.
+    pub fn source_link(&self) -> Result<Option<SourceLinkMappings>, PortablePdbError> {
+        let values = self
+            .metadata
+            .custom_debug_information(None, &GUID_SOURCE_LINK)?;
+
+        match values.first() {
+            Some(data) => Ok(Some(SourceLinkMappings::parse(data)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the raw data of the Portable PDB file.
+    pub fn data(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl fmt::Debug for PortablePdbObject<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PortablePdbObject")
+            .field("debug_id", &self.debug_id())
+            .field("arch", &self.arch())
+            .field("kind", &self.kind())
+            .field("load_address", &format_args!("{:#x}", self.load_address()))
+            .field("has_symbols", &self.has_symbols())
+            .field("has_debug_info", &self.has_debug_info())
+            .field("has_unwind_info", &self.has_unwind_info())
+            .field("has_sources", &self.has_sources())
+            .finish()
+    }
+}
+
+impl<'slf, 'data: 'slf> AsSelf<'slf> for PortablePdbObject<'data> {
+    type Ref = PortablePdbObject<'slf>;
+
+    fn as_self(&'slf self) -> &Self::Ref {
+        self
+    }
+}
+
+impl<'data> Parse<'data> for PortablePdbObject<'data> {
+    type Error = PortablePdbError;
+
+    fn test(data: &[u8]) -> bool {
+        Self::test(data)
+    }
+
+    fn parse(data: &'data [u8]) -> Result<Self, PortablePdbError> {
+        Self::parse(data)
+    }
+}
+
+impl<'data: 'object, 'object> ObjectLike<'data, 'object> for PortablePdbObject<'data> {
+    type Error = PortablePdbError;
+    type Session = PortablePdbDebugSession<'data>;
+    type SymbolIterator = PortablePdbSymbolIterator<'data>;
+
+    fn file_format(&self) -> FileFormat {
+        self.file_format()
+    }
+
+    fn code_id(&self) -> Option<CodeId> {
+        self.code_id()
+    }
+
+    fn debug_id(&self) -> DebugId {
+        self.debug_id()
+    }
+
+    fn arch(&self) -> Arch {
+        self.arch()
+    }
+
+    fn kind(&self) -> ObjectKind {
+        self.kind()
+    }
+
+    fn load_address(&self) -> u64 {
+        self.load_address()
+    }
+
+    fn has_symbols(&self) -> bool {
+        self.has_symbols()
+    }
+
+    fn symbols(&'object self) -> Self::SymbolIterator {
+        self.symbols()
+    }
+
+    fn symbol_map(&self) -> SymbolMap<'data> {
+        self.symbol_map()
+    }
+
+    fn has_debug_info(&self) -> bool {
+        self.has_debug_info()
+    }
+
+    fn debug_session(&self) -> Result<Self::Session, Self::Error> {
+        self.debug_session()
+    }
+
+    fn has_unwind_info(&self) -> bool {
+        self.has_unwind_info()
+    }
+
+    fn has_sources(&self) -> bool {
+        self.has_sources()
+    }
+}
+
+/// An iterator over symbols in a Portable PDB, which is always empty.
+pub type PortablePdbSymbolIterator<'data> = std::iter::Empty<Symbol<'data>>;
+
+/// Debug session for Portable PDB objects.
+///
+/// Since managed code has no instruction addresses, functions and lines are keyed by method token
+/// and IL offset: the address of a function is its `MethodDef` row shifted above the lower 24 bits,
+/// and line records are offset by the IL offset within the method.
+pub struct PortablePdbDebugSession<'data> {
+    metadata: Metadata<'data>,
+    documents: Vec<Document>,
+}
+
+impl<'data> PortablePdbDebugSession<'data> {
+    fn build(metadata: &Metadata<'data>) -> Result<Self, PortablePdbError> {
+        Ok(PortablePdbDebugSession {
+            metadata: metadata.clone(),
+            documents: metadata.documents()?,
+        })
+    }
+
+    /// Returns an iterator over all source files in this debug file.
+    pub fn files(&self) -> PortablePdbFileIterator<'_> {
+        PortablePdbFileIterator {
+            documents: self.documents.iter(),
+        }
+    }
+
+    /// Returns an iterator over all methods with sequence points in this debug file.
+    pub fn functions(&self) -> PortablePdbFunctionIterator<'_> {
+        PortablePdbFunctionIterator {
+            session: self,
+            row: 0,
+        }
+    }
+
+    /// Returns the sequence points of the method with the given `MethodDef` token.
+    ///
+    /// Returns an empty list if the token does not refer to a method or the method has no
+    /// sequence points.
+    pub fn sequence_points(&self, token: u32) -> Result<Vec<SequencePoint>, PortablePdbError> {
+        match method_row(token) {
+            Some(row) => self.metadata.sequence_points(row),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Resolves the source location of an IL offset in the method with the given token.
+    ///
+    /// Returns `None` if there is no sequence point at or before the offset, or if the closest
+    /// sequence point is hidden. The address of the returned line is the IL offset of the sequence
+    /// point.
+    pub fn lookup_line(
+        &self,
+        token: u32,
+        il_offset: u32,
+    ) -> Result<Option<LineInfo<'_>>, PortablePdbError> {
+        let points = self.sequence_points(token)?;
+        let index = points.iter().rposition(|p| p.il_offset <= il_offset);
+        let point = match index.map(|index| &points[index]) {
+            Some(point) if !point.is_hidden() => point,
+            _ => return Ok(None),
+        };
+
+        self.line_info(point, points.get(index.unwrap() + 1), 0)
+    }
+
+    /// Creates line information for a sequence point, if its document exists.
+    fn line_info(
+        &self,
+        point: &SequencePoint,
+        next: Option<&SequencePoint>,
+        base: u64,
+    ) -> Result<Option<LineInfo<'_>>, PortablePdbError> {
+        let document = match point
+            .document
+            .checked_sub(1)
+            .and_then(|index| self.documents.get(index as usize))
+        {
+            Some(document) => document,
+            None => return Ok(None),
+        };
+
+        let size = match next {
+            Some(next) => {
+                let size = next.il_offset.checked_sub(point.il_offset);
+                Some(u64::from(size.ok_or_else(bad_object)?))
+            }
+            None => None,
+        };
+
+        Ok(Some(LineInfo {
+            address: base + u64::from(point.il_offset),
+            size,
+            file: document.file_info(),
+            line: point.start_line.into(),
+            column: Some(point.start_column.into()),
+        }))
+    }
+
+    /// Looks up a file's source contents by its full canonicalized path.
+    ///
+    /// The given path must be canonicalized. Only sources embedded into the Portable PDB are
+    /// returned.
+    pub fn source_by_path(&self, path: &str) -> Result<Option<Cow<'_, str>>, PortablePdbError> {
+        let row = match self.documents.iter().position(|d| d.path == path) {
+            Some(index) => index as u32 + 1,
+            None => return Ok(None),
+        };
+
+        let tag = HAS_CUSTOM_DEBUG_INFORMATION
+            .iter()
+            .position(|&table| table == TABLE_DOCUMENT)
+            .unwrap() as u32;
+        let parent = (row << HAS_CUSTOM_DEBUG_INFORMATION_BITS) | tag;
+
+        let values = self
+            .metadata
+            .custom_debug_information(Some(parent), &GUID_EMBEDDED_SOURCE)?;
+
+        let data = match values.first() {
+            Some(data) => data,
+            None => return Ok(None),
+        };
+
+        let contents = decompress_embedded_source(data).ok_or_else(bad_object)?;
+        Ok(Some(Cow::Owned(
+            String::from_utf8_lossy(&contents).into_owned(),
+        )))
+    }
+}
+
+impl<'session> DebugSession<'session> for PortablePdbDebugSession<'_> {
+    type Error = PortablePdbError;
+    type FunctionIterator = PortablePdbFunctionIterator<'session>;
+    type FileIterator = PortablePdbFileIterator<'session>;
+
+    fn functions(&'session self) -> Self::FunctionIterator {
+        self.functions()
+    }
+
+    fn files(&'session self) -> Self::FileIterator {
+        self.files()
+    }
+
+    fn source_by_path(&self, path: &str) -> Result<Option<Cow<'_, str>>, Self::Error> {
+        self.source_by_path(path)
+    }
+}
+
+/// An iterator over source files in a Portable PDB.
+pub struct PortablePdbFileIterator<'s> {
+    documents: std::slice::Iter<'s, Document>,
+}
+
+impl<'s> Iterator for PortablePdbFileIterator<'s> {
+    type Item = Result<FileEntry<'s>, PortablePdbError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let document = self.documents.next()?;
+        Some(Ok(FileEntry {
+            compilation_dir: &[],
+            info: document.file_info(),
+            checksum: document.checksum,
+        }))
+    }
+}
+
+/// An iterator over methods in a Portable PDB.
+///
+/// Methods do not have names, since those are stored in the metadata of the assembly. The size of
+/// a function extends to the last sequence point, since Portable PDBs do not record the size of
+/// the IL code.
+pub struct PortablePdbFunctionIterator<'s> {
+    session: &'s PortablePdbDebugSession<'s>,
+    row: u32,
+}
+
+impl<'s> PortablePdbFunctionIterator<'s> {
+    fn convert_method(&self, row: u32) -> Result<Option<Function<'s>>, PortablePdbError> {
+        let points = self.session.metadata.sequence_points(row)?;
+        let last = match points.last() {
+            Some(last) => last.il_offset,
+            None => return Ok(None),
+        };
+
+        // Rows are at most 24 bits wide, so the address fits into 48 bits as long as IL offsets do
+        // not overflow into the row.
+        if last >> IL_OFFSET_BITS != 0 {
+            return Err(bad_object());
+        }
+        let address = u64::from(row) << IL_OFFSET_BITS;
+
+        let mut lines = Vec::new();
+        for (index, point) in points.iter().enumerate() {
+            if point.is_hidden() {
+                continue;
+            }
+
+            let next = points.get(index + 1);
+            if let Some(line) = self.session.line_info(point, next, address)? {
+                lines.push(line);
+            }
+        }
+
+        if lines.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(Function {
+            address,
+            size: u64::from(last) + 1,
+            name: Name::new("", NameMangling::Unmangled, Language::Unknown),
+            compilation_dir: &[],
+            lines,
+            inlinees: Vec::new(),
+            inline: false,
+            multiple: false,
+            parameter_size: None,
+        }))
+    }
+}
+
+impl<'s> Iterator for PortablePdbFunctionIterator<'s> {
+    type Item = Result<Function<'s>, PortablePdbError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rows = self
+            .session
+            .metadata
+            .table(TABLE_METHOD_DEBUG_INFORMATION)
+            .rows;
+
+        while self.row < rows {
+            self.row += 1;
+            match self.convert_method(self.row) {
+                Ok(Some(function)) => return Some(Ok(function)),
+                Ok(None) => continue,
+                Err(error) => return Some(Err(error)),
+            }
+        }
+
+        None
+    }
+}
+
+impl std::iter::FusedIterator for PortablePdbFunctionIterator<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use similar_asserts::assert_eq;
+
+    #[test]
+    fn test_compressed_integers() -> Result<(), PortablePdbError> {
+        let mut reader = Reader::new(&[0x03, 0x80, 0x80, 0xc0, 0x00, 0x40, 0x00]);
+        assert_eq!(reader.compressed_u32()?, 0x03);
+        assert_eq!(reader.compressed_u32()?, 0x80);
+        assert_eq!(reader.compressed_u32()?, 0x4000);
+
+        // Examples from ECMA-335 II.23.2.
+        let mut reader = Reader::new(&[
+            0x06, 0x7b, 0x80, 0x80, 0x01, 0xc0, 0x00, 0x40, 0x00, 0x80, 0x01,
+        ]);
+        assert_eq!(reader.compressed_i32()?, 3);
+        assert_eq!(reader.compressed_i32()?, -3);
+        assert_eq!(reader.compressed_i32()?, 64);
+        assert_eq!(reader.compressed_i32()?, -64);
+        assert_eq!(reader.compressed_i32()?, 8192);
+        assert_eq!(reader.compressed_i32()?, -8192);
+
+        Ok(())
+    }
+    #[test]
+    fn test_parse_sequence_points() -> Result<(), PortablePdbError> {
+        // Local signature, then a point at IL offset 0 spanning lines 5 to 6 and columns 9 to 10,
+        // followed by a hidden point at IL offset 4.
+        let blob = [0x00, 0x00, 0x01, 0x02, 0x05, 0x09, 0x04, 0x00, 0x00];
+        let points = parse_sequence_points(&blob, 1)?;
+
+        assert_eq!(
+            points,
+            [
+                SequencePoint {
+                    il_offset: 0,
+                    document: 1,
+                    start_line: 5,
+                    start_column: 9,
+                    end_line: 6,
+                    end_column: 10,
+                },
+                SequencePoint {
+                    il_offset: 4,
+                    document: 1,
+                    start_line: HIDDEN_LINE,
+                    start_column: 0,
+                    end_line: HIDDEN_LINE,
+                    end_column: 0,
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_sequence_points_malformed() {
+        // Truncated in the middle of the first sequence point.
+        let blob = [0x00, 0x00, 0x01];
+        assert!(parse_sequence_points(&blob, 1).is_err());
+
+        // IL offsets that overflow after adding up hidden points with maximum deltas.
+        let mut blob = vec![0x00];
+        for _ in 0..9 {
+            blob.extend_from_slice(&[0xdf, 0xff, 0xff, 0xff, 0x00, 0x00]);
+        }
+        assert!(parse_sequence_points(&blob, 1).is_err());
+
+        // A line delta that moves the start line below zero.
+        let blob = [
+            0x00, 0x00, 0x01, 0x02, 0x01, 0x01, 0x01, 0x01, 0x02, 0x7d, 0x00,
+        ];
+        assert!(parse_sequence_points(&blob, 1).is_err());
+    }
+}
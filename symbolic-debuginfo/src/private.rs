@@ -1,7 +1,10 @@
+use std::convert::TryInto;
 use std::fmt;
 use std::iter::FusedIterator;
 use std::marker::PhantomData;
 
+use flate2::{Decompress, FlushDecompress, Status};
+
 #[cfg(test)]
 use similar_asserts::assert_eq;
 
@@ -197,6 +200,77 @@ where
 impl<'d, P> FusedIterator for MonoArchiveObjects<'d, P> where P: Parse<'d> {}
 impl<'d, P> ExactSizeIterator for MonoArchiveObjects<'d, P> where P: Parse<'d> {}
 
+/// The assumed compression ratio of embedded sources used to limit preallocated memory.
+const EMBEDDED_SOURCE_RATIO: usize = 8;
+
+/// Decompresses a source file embedded by .NET compilers.
+///
+/// The data starts with the signed 32-bit size of the decompressed contents, followed by raw
+/// deflate data. A size of zero indicates that the remaining contents are stored uncompressed.
+/// Returns `None` if the data is corrupt or does not decompress to exactly the declared size.
+pub fn decompress_embedded_source(data: &[u8]) -> Option<Vec<u8>> {
+    let size = i32::from_le_bytes(data.get(..4)?.try_into().ok()?);
+    let compressed = &data[4..];
+
+    let size = match size {
+        0 => return Some(compressed.to_vec()),
+        size if size > 0 => size as usize,
+        _ => return None,
+    };
+
+    // The declared size cannot be trusted, so only preallocate a reasonable amount and grow the
+    // buffer as needed. Allow one additional byte to detect contents exceeding the declared size.
+    let capacity = size.min(compressed.len().saturating_mul(EMBEDDED_SOURCE_RATIO));
+    let mut decompressed = Vec::with_capacity(capacity);
+    let mut decompress = Decompress::new(false);
+
+    loop {
+        if decompressed.len() == decompressed.capacity() {
+            let limit = size + 1 - decompressed.len();
+            if limit == 0 {
+                return None;
+            }
+            decompressed.reserve_exact(decompressed.len().max(1024).min(limit));
+        }
+
+        let total_in = decompress.total_in();
+        let total_out = decompress.total_out();
+        let input = compressed.get(total_in as usize..)?;
+
+        match decompress
+            .decompress_vec(input, &mut decompressed, FlushDecompress::None)
+            .ok()?
+        {
+            Status::StreamEnd => break,
+            Status::Ok | Status::BufError => {
+                // Bail out if the decoder cannot make progress, which indicates truncated input.
+                if decompress.total_in() == total_in && decompress.total_out() == total_out {
+                    return None;
+                }
+            }
+        }
+    }
+
+    if decompressed.len() == size {
+        Some(decompressed)
+    } else {
+        None
+    }
+}
+
+/// Compares two paths case insensitively and regardless of the path separator.
+pub fn paths_equal(a: &str, b: &str) -> bool {
+    let normalize = |c: u8| match c {
+        b'\\' => b'/',
+        c => c.to_ascii_lowercase(),
+    };
+
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .all(|(a, b)| normalize(a) == normalize(b))
+}
+
 /// A stack for assembling function trees from lists of nested functions.
 pub struct FunctionStack<'a>(Vec<(isize, Function<'a>)>);
 
@@ -324,3 +398,49 @@ test_lineoffsets!(
     (7, b"world"),
     (13, b"yo")
 );
+
+#[cfg(test)]
+fn embedded_source(size: i32, contents: &[u8]) -> Vec<u8> {
+    use flate2::{Compress, Compression, FlushCompress};
+
+    let mut data = size.to_le_bytes().to_vec();
+    let mut compressed = Vec::with_capacity(contents.len() + 64);
+    Compress::new(Compression::default(), false)
+        .compress_vec(contents, &mut compressed, FlushCompress::Finish)
+        .unwrap();
+    data.extend_from_slice(&compressed);
+    data
+}
+
+#[test]
+fn test_decompress_embedded_source() {
+    let contents = b"class Program { static void Main() {} }\n".repeat(100);
+    let size = contents.len() as i32;
+
+    let data = embedded_source(size, &contents);
+    assert_eq!(decompress_embedded_source(&data), Some(contents.clone()));
+
+    let mut data = 0i32.to_le_bytes().to_vec();
+    data.extend_from_slice(&contents);
+    assert_eq!(decompress_embedded_source(&data), Some(contents));
+}
+
+#[test]
+fn test_decompress_embedded_source_invalid() {
+    let contents = b"class Program { static void Main() {} }\n".repeat(100);
+    let size = contents.len() as i32;
+
+    // Declared sizes that do not match the contents.
+    let data = embedded_source(size - 1, &contents);
+    assert_eq!(decompress_embedded_source(&data), None);
+    let data = embedded_source(size + 1, &contents);
+    assert_eq!(decompress_embedded_source(&data), None);
+    let data = embedded_source(i32::MAX, &contents);
+    assert_eq!(decompress_embedded_source(&data), None);
+
+    // Truncated or missing data.
+    let data = embedded_source(size, &contents);
+    assert_eq!(decompress_embedded_source(&data[..data.len() / 2]), None);
+    assert_eq!(decompress_embedded_source(&data[..2]), None);
+    assert_eq!(decompress_embedded_source(&(-1i32).to_le_bytes()), None);
+}
@@ -686,6 +686,7 @@ impl<'s> Iterator for SourceBundleFileIterator<'s> {
         Some(Ok(FileEntry {
             compilation_dir: &[],
             info: FileInfo::from_path(source_file.path.as_bytes()),
+            checksum: None,
         }))
     }
 }
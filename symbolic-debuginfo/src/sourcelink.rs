@@ -0,0 +1,165 @@
+//! Support for SourceLink mappings.
+//!
+//! [SourceLink] is a JSON document embedded into PDBs, which maps paths of source files at build
+//! time to URLs. Each key is either an exact path or a path prefix ending with a `*` wildcard. The
+//! part of a path matched by the wildcard is substituted into the `*` of the URL:
+//!
+//! ```json
+//! {
+//!   "documents": {
+//!     "C:\\build\\*": "https://raw.githubusercontent.com/org/repo/commit/*"
+//!   }
+//! }
+//! ```
+//!
+//! [SourceLink]: https://github.com/dotnet/designs/blob/main/accepted/2020/diagnostics/source-link.md
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::private::paths_equal;
+
+/// An error when parsing [`SourceLinkMappings`](struct.SourceLinkMappings.html).
+#[derive(Debug, Error)]
+#[error("invalid source link mappings")]
+pub struct SourceLinkError {
+    #[from]
+    source: serde_json::Error,
+}
+
+#[derive(Deserialize)]
+struct SourceLinkJson {
+    documents: BTreeMap<String, String>,
+}
+
+/// A single SourceLink mapping.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct SourceLinkMapping {
+    /// The path, or the path prefix if `wildcard` is set.
+    pattern: String,
+    /// The URL, containing a `*` placeholder if `wildcard` is set.
+    url: String,
+    /// Whether the pattern ended with a wildcard.
+    wildcard: bool,
+}
+
+/// Mappings from local source file paths to URLs.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SourceLinkMappings {
+    /// Mappings sorted by descending pattern length, so that the most specific pattern wins.
+    mappings: Vec<SourceLinkMapping>,
+}
+
+impl SourceLinkMappings {
+    /// Parses a SourceLink JSON document.
+    pub fn parse(data: &[u8]) -> Result<Self, SourceLinkError> {
+        let json: SourceLinkJson = serde_json::from_slice(data)?;
+
+        let mut mappings = json
+            .documents
+            .into_iter()
+            .map(|(pattern, url)| match pattern.strip_suffix('*') {
+                Some(prefix) => SourceLinkMapping {
+                    pattern: prefix.to_owned(),
+                    url,
+                    wildcard: true,
+                },
+                None => SourceLinkMapping {
+                    pattern,
+                    url,
+                    wildcard: false,
+                },
+            })
+            .collect::<Vec<_>>();
+
+        // Exact paths take precedence over wildcards of the same length.
+        mappings.sort_by_key(|m| std::cmp::Reverse((m.pattern.len(), !m.wildcard)));
+
+        Ok(SourceLinkMappings { mappings })
+    }
+
+    /// Returns `true` if there are no mappings.
+    pub fn is_empty(&self) -> bool {
+        self.mappings.is_empty()
+    }
+
+    /// Returns an iterator over all path patterns and URLs.
+    ///
+    /// Wildcard patterns and URLs are returned including their trailing `*`.
+    pub fn mappings(&self) -> impl Iterator<Item = (String, &str)> {
+        self.mappings.iter().map(|mapping| {
+            let pattern = if mapping.wildcard {
+                format!("{}*", mapping.pattern)
+            } else {
+                mapping.pattern.clone()
+            };
+
+            (pattern, mapping.url.as_str())
+        })
+    }
+
+    /// Resolves the URL of a source file.
+    ///
+    /// Paths are compared case insensitively and regardless of the path separator. Backslashes in
+    /// the portion of the path matched by a wildcard are converted to forward slashes.
+    pub fn resolve(&self, path: &str) -> Option<String> {
+        for mapping in &self.mappings {
+            if mapping.wildcard {
+                let prefix = match path.get(..mapping.pattern.len()) {
+                    Some(prefix) if paths_equal(prefix, &mapping.pattern) => prefix,
+                    _ => continue,
+                };
+
+                let rest = path[prefix.len()..].replace('\\', "/");
+                return Some(mapping.url.replacen('*', &rest, 1));
+            } else if paths_equal(path, &mapping.pattern) {
+                return Some(mapping.url.clone());
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use similar_asserts::assert_eq;
+
+    #[test]
+    fn test_resolve() -> Result<(), SourceLinkError> {
+        let mappings = SourceLinkMappings::parse(
+            br#"{
+                "documents": {
+                    "C:\\build\\*": "https://example.org/repo/abc/*",
+                    "C:\\build\\vendor\\*": "https://example.org/vendor/def/*",
+                    "C:\\build\\gen.h": "https://example.org/gen.h"
+                }
+            }"#,
+        )?;
+
+        assert_eq!(
+            mappings.resolve("c:/Build/src/main.cpp").as_deref(),
+            Some("https://example.org/repo/abc/src/main.cpp")
+        );
+        assert_eq!(
+            mappings.resolve("C:\\build\\vendor\\lib\\lib.c").as_deref(),
+            Some("https://example.org/vendor/def/lib/lib.c")
+        );
+        assert_eq!(
+            mappings.resolve("C:\\build\\gen.h").as_deref(),
+            Some("https://example.org/gen.h")
+        );
+        assert_eq!(mappings.resolve("D:\\other\\main.cpp"), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(SourceLinkMappings::parse(b"{\"documents\": []}").is_err());
+    }
+}
@@ -0,0 +1,331 @@
+//! Support for source server (`srcsrv`) streams in PDBs.
+//!
+//! Source indexing tools write a `srcsrv` stream into PDBs, which maps the paths of source files at
+//! build time to commands and locations for retrieving them from version control. The stream is a
+//! text file consisting of four sections:
+//!
+//! ```text
+//! SRCSRV: ini ------------------------------------------------
+//! VERSION=2
+//! VERCTRL=http
+//! SRCSRV: variables ------------------------------------------
+//! SRCSRVTRG=https://example.org/%var2%
+//! SRCSRV: source files ---------------------------------------
+//! c:\build\main.cpp*src/main.cpp
+//! SRCSRV: end ------------------------------------------------
+//! ```
+//!
+//! Each line in the source files section lists the local path as the first field, followed by
+//! arbitrary fields available as `%var2%`, `%var3%`, etc. when expanding variables.
+
+use std::collections::BTreeMap;
+
+use crate::private::paths_equal;
+
+/// Maximum depth of nested variable expansions, guarding against recursive definitions.
+const MAX_EXPANSION_DEPTH: usize = 16;
+
+/// The section of a `srcsrv` stream that is currently being parsed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Section {
+    None,
+    Ini,
+    Variables,
+    SourceFiles,
+}
+
+/// A parsed source server stream.
+///
+/// Variable names are case insensitive. Use [`target`] to resolve the location of a source file,
+/// and [`command`] to obtain the command that extracts it.
+///
+/// [`target`]: struct.SrcSrvStream.html#method.target
+/// [`command`]: struct.SrcSrvStream.html#method.command
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SrcSrvStream {
+    ini: BTreeMap<String, String>,
+    variables: BTreeMap<String, String>,
+    files: Vec<Vec<String>>,
+}
+
+impl SrcSrvStream {
+    /// Parses a source server stream.
+    ///
+    /// The format is line-based and parsed leniently. Lines that cannot be interpreted are skipped.
+    pub fn parse(data: &[u8]) -> Self {
+        let text = String::from_utf8_lossy(data);
+        let mut stream = SrcSrvStream::default();
+        let mut section = Section::None;
+
+        for line in text.lines() {
+            let line = line.trim_end_matches('\r');
+
+            if let Some(header) = line.strip_prefix("SRCSRV:") {
+                let header = header.trim_start().to_ascii_lowercase();
+                section = if header.starts_with("ini") {
+                    Section::Ini
+                } else if header.starts_with("variables") {
+                    Section::Variables
+                } else if header.starts_with("source files") {
+                    Section::SourceFiles
+                } else {
+                    Section::None
+                };
+                continue;
+            }
+
+            match section {
+                Section::Ini | Section::Variables => {
+                    let (key, value) = match line.find('=') {
+                        Some(index) => (&line[..index], &line[index + 1..]),
+                        None => continue,
+                    };
+
+                    let map = match section {
+                        Section::Ini => &mut stream.ini,
+                        _ => &mut stream.variables,
+                    };
+
+                    map.insert(key.trim().to_ascii_uppercase(), value.to_owned());
+                }
+                Section::SourceFiles if !line.is_empty() => {
+                    stream
+                        .files
+                        .push(line.split('*').map(str::to_owned).collect());
+                }
+                _ => {}
+            }
+        }
+
+        stream
+    }
+
+    /// Returns the version of the stream format declared in the `VERSION` entry.
+    pub fn version(&self) -> Option<u32> {
+        self.ini.get("VERSION")?.trim().parse().ok()
+    }
+
+    /// Returns the version control system declared in the `VERCTRL` entry, such as `http`.
+    pub fn version_control(&self) -> Option<&str> {
+        self.ini.get("VERCTRL").map(String::as_str)
+    }
+
+    /// Returns the raw value of a variable without expanding it.
+    pub fn variable(&self, name: &str) -> Option<&str> {
+        self.variables
+            .get(&name.to_ascii_uppercase())
+            .map(String::as_str)
+    }
+
+    /// Returns an iterator over all variables and their raw values.
+    ///
+    /// Names are returned in upper case.
+    pub fn variables(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.variables
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// Returns an iterator over the local paths of all indexed source files.
+    pub fn source_files(&self) -> impl Iterator<Item = &str> {
+        self.files
+            .iter()
+            .filter_map(|fields| fields.first().map(String::as_str))
+    }
+
+    /// Returns the fields of the source file entry for the given local path.
+    ///
+    /// The first field is the local path itself. Paths are compared case insensitively and
+    /// regardless of the path separator.
+    pub fn source_file(&self, path: &str) -> Option<&[String]> {
+        self.files
+            .iter()
+            .find(|fields| fields.first().map_or(false, |p| paths_equal(p, path)))
+            .map(Vec::as_slice)
+    }
+
+    /// Resolves the location of a source file by expanding `SRCSRVTRG` for its entry.
+    ///
+    /// Depending on the version control system, this is a URL or a path in the local cache.
+    pub fn target(&self, path: &str) -> Option<String> {
+        self.expand_variable("SRCSRVTRG", path)
+    }
+
+    /// Resolves the command that retrieves a source file by expanding `SRCSRVCMD` for its entry.
+    ///
+    /// Returns `None` if no command is needed, which is the case for `http` sources.
+    pub fn command(&self, path: &str) -> Option<String> {
+        self.expand_variable("SRCSRVCMD", path)
+    }
+
+    /// Resolves the URL of a source file if the target is an HTTP location.
+    pub fn url(&self, path: &str) -> Option<String> {
+        let target = self.target(path)?;
+        let lower = target.to_ascii_lowercase();
+        if lower.starts_with("http://") || lower.starts_with("https://") {
+            Some(target)
+        } else {
+            None
+        }
+    }
+
+    fn expand_variable(&self, name: &str, path: &str) -> Option<String> {
+        let fields = self.source_file(path)?;
+        let template = self.variable(name)?;
+        let expanded = self.expand(template, fields, 0);
+
+        if expanded.trim().is_empty() {
+            None
+        } else {
+            Some(expanded)
+        }
+    }
+
+    /// Expands all variables and functions in the template.
+    fn expand(&self, template: &str, fields: &[String], depth: usize) -> String {
+        if depth >= MAX_EXPANSION_DEPTH {
+            return template.to_owned();
+        }
+
+        let mut expanded = String::new();
+        let mut rest = template;
+
+        while let Some(start) = rest.find('%') {
+            expanded.push_str(&rest[..start]);
+
+            let after = &rest[start + 1..];
+            let end = match after.find('%') {
+                Some(end) => end,
+                None => {
+                    rest = &rest[start..];
+                    break;
+                }
+            };
+
+            let name = after[..end].to_ascii_lowercase();
+            rest = &after[end + 1..];
+
+            // Functions take a single argument in parentheses, which is expanded first.
+            let is_function = matches!(name.as_str(), "fnvar" | "fnbksl" | "fnfile");
+            if let (true, Some(argument)) = (is_function, function_argument(rest)) {
+                rest = &rest[argument.len() + 2..];
+                let argument = self.expand(argument, fields, depth + 1);
+
+                match name.as_str() {
+                    "fnvar" => {
+                        if let Some(value) = self.variable(&argument) {
+                            expanded.push_str(&self.expand(value, fields, depth + 1));
+                        }
+                    }
+                    "fnbksl" => expanded.push_str(&argument.replace('/', "\\")),
+                    _ => expanded.push_str(argument.rsplit(&['\\', '/'][..]).next().unwrap_or("")),
+                }
+
+                continue;
+            }
+
+            if let Some(index) = name
+                .strip_prefix("var")
+                .and_then(|i| i.parse::<usize>().ok())
+            {
+                if let Some(field) = index.checked_sub(1).and_then(|i| fields.get(i)) {
+                    expanded.push_str(field);
+                }
+            } else if let Some(value) = self.variable(&name) {
+                expanded.push_str(&self.expand(value, fields, depth + 1));
+            }
+        }
+
+        expanded.push_str(rest);
+        expanded
+    }
+}
+
+/// Returns the argument of a function call at the start of the input, excluding the parentheses.
+fn function_argument(input: &str) -> Option<&str> {
+    if !input.starts_with('(') {
+        return None;
+    }
+
+    let mut depth = 0;
+    for (index, c) in input.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&input[1..index]);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use similar_asserts::assert_eq;
+
+    const HTTP_STREAM: &str = "\
+SRCSRV: ini ------------------------------------------------\r
+VERSION=2\r
+VERCTRL=http\r
+SRCSRV: variables ------------------------------------------\r
+HTTP_ALIAS=https://raw.example.org/repo\r
+HTTP_EXTRACT_TARGET=%HTTP_ALIAS%/%var2%/%var3%\r
+SRCSRVTRG=%http_extract_target%\r
+SRCSRVCMD=\r
+SRCSRV: source files ---------------------------------------\r
+c:\\build\\src\\main.cpp*abc123*src/main.cpp\r
+SRCSRV: end ------------------------------------------------\r
+";
+
+    #[test]
+    fn test_http_target() {
+        let stream = SrcSrvStream::parse(HTTP_STREAM.as_bytes());
+
+        assert_eq!(stream.version(), Some(2));
+        assert_eq!(stream.version_control(), Some("http"));
+        assert_eq!(
+            stream.source_files().collect::<Vec<_>>(),
+            ["c:\\build\\src\\main.cpp"]
+        );
+
+        let url = stream.url("C:/Build/src/main.cpp");
+        assert_eq!(
+            url.as_deref(),
+            Some("https://raw.example.org/repo/abc123/src/main.cpp")
+        );
+        assert_eq!(stream.command("c:\\build\\src\\main.cpp"), None);
+        assert_eq!(stream.target("c:\\build\\src\\other.cpp"), None);
+    }
+
+    #[test]
+    fn test_command_functions() {
+        let stream = SrcSrvStream::parse(
+            b"SRCSRV: ini -----\n\
+              VERCTRL=Perforce\n\
+              SRCSRV: variables -----\n\
+              P4_EXTRACT_TARGET=%targ%\\%fnbksl%(%var2%)\\%fnfile%(%var1%)\n\
+              SRCSRVTRG=%fnvar%(%var3%)\n\
+              SRCSRVCMD=p4 print -o %srcsrvtrg% //%var2%#%var4%\n\
+              SRCSRV: source files -----\n\
+              c:\\src\\a.c*depot/a*P4_EXTRACT_TARGET*7\n\
+              SRCSRV: end -----\n",
+        );
+
+        assert_eq!(
+            stream.target("c:\\src\\a.c").as_deref(),
+            Some("\\depot\\a\\a.c")
+        );
+        assert_eq!(
+            stream.command("c:\\src\\a.c").as_deref(),
+            Some("p4 print -o \\depot\\a\\a.c //depot/a#7")
+        );
+        assert_eq!(stream.url("c:\\src\\a.c"), None);
+    }
+}
@@ -341,6 +341,8 @@ impl<'data, 'object> Iterator for WasmSymbolIterator<'data, 'object> {
                     name: func.name.as_ref().map(|x| Cow::Owned(x.clone())),
                     address,
                     size,
+                    multiple: false,
+                    parameter_size: None,
                 });
             }
         }
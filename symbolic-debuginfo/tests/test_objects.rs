@@ -1,7 +1,16 @@
 use std::fmt;
 
 use symbolic_common::ByteView;
-use symbolic_debuginfo::{FileEntry, Function, Object, SymbolMap};
+use symbolic_debuginfo::breakpad::{BreakpadObject, BreakpadStackRecord};
+use symbolic_debuginfo::elf::ElfObject;
+use symbolic_debuginfo::macho::{BcSymbolMap, MachObject};
+use symbolic_debuginfo::pdb::PdbObject;
+use symbolic_debuginfo::pe::{PeForwarder, PeImportName, PeObject};
+use symbolic_debuginfo::ppdb::PortablePdbObject;
+use symbolic_debuginfo::{
+    Archive, BaseClass, FileChecksum, FileEntry, FileFormat, Function, Object, ObjectKind,
+    SymbolMap, TypeKind, VariableKind, VariableLocation,
+};
 use symbolic_testutils::fixture;
 
 use similar_asserts::assert_eq;
@@ -139,6 +148,89 @@ fn test_breakpad_functions() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn test_breakpad_inlinees() -> Result<(), Error> {
+    let view = ByteView::open(fixture("linux/inline.sym"))?;
+    let object = Object::parse(&view)?;
+
+    let session = object.debug_session()?;
+    let functions = session.functions().collect::<Result<Vec<_>, _>>()?;
+    insta::assert_debug_snapshot!(FunctionsDebug(&functions, 0), @r###"
+    > 0x1000: main (0x40)
+      0x1000: main.c:9 (/src)
+      0x1008: main.c:10 (/src)
+      0x1020: main.c:11 (/src)
+
+      > 0x1008: util_add (0x18)
+        0x1008: util.h:21 (/src)
+        0x1010: util.h:20 (/src)
+        0x1018: util.h:22 (/src)
+
+        > 0x1010: util_inner (0x8)
+          0x1010: util.h:30 (/src)
+
+    > 0x1040: helper (0x20)
+      0x1040: main.c:14 (/src)
+      0x1044: main.c:15 (/src)
+      0x1048: main.c:16 (/src)
+      0x1050: main.c:15 (/src)
+      0x1058: main.c:17 (/src)
+
+      > 0x1044: util_add (0x14)
+        0x1044: util.h:21 (/src)
+        0x1050: util.h:23 (/src)
+    "###);
+
+    Ok(())
+}
+
+#[test]
+fn test_breakpad_stack_rules() -> Result<(), Error> {
+    let view = ByteView::open(fixture("linux/crash.sym"))?;
+    let object = BreakpadObject::parse(&view)?;
+
+    let mut init_records = 0;
+    for record in object.stack_records() {
+        match record? {
+            BreakpadStackRecord::Cfi(record) => {
+                let rules = record.rules()?;
+                if rules.is_init() {
+                    assert!(rules.cfa_rule.is_some());
+                    assert!(rules.register_rule(".ra").is_some());
+                    init_records += 1;
+                }
+            }
+            BreakpadStackRecord::Win(_) => panic!("unexpected STACK WIN record"),
+        }
+    }
+    assert_eq!(init_records, 162);
+
+    let view = ByteView::open(fixture("windows/crash.sym"))?;
+    let object = BreakpadObject::parse(&view)?;
+
+    let mut win_records = 0;
+    for record in object.stack_records() {
+        if let BreakpadStackRecord::Win(record) = record? {
+            record.frame_info()?;
+            win_records += 1;
+        }
+    }
+    assert_eq!(win_records, 154);
+
+    Ok(())
+}
+
+#[test]
+fn test_breakpad_validate() -> Result<(), Error> {
+    for path in &["linux/crash.sym", "linux/inline.sym", "windows/crash.sym"] {
+        let view = ByteView::open(fixture(path))?;
+        let object = BreakpadObject::parse(&view)?;
+        assert_eq!(object.validate(), []);
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_elf_executable() -> Result<(), Error> {
     let view = ByteView::open(fixture("linux/crash"))?;
@@ -167,6 +259,65 @@ fn test_elf_executable() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn test_elf_debug_link() -> Result<(), Error> {
+    let view = ByteView::open(fixture("linux/crash"))?;
+    let object = ElfObject::parse(&view)?;
+
+    let debug_link = object.debug_link().expect("debug link");
+    assert_eq!(debug_link.filename, "crash.debug");
+    assert_eq!(debug_link.crc, 0xa501_0837);
+
+    let debug_view = ByteView::open(fixture("linux/crash.debug"))?;
+    assert!(debug_link.matches(&debug_view));
+    assert!(!debug_link.matches(&view));
+
+    Ok(())
+}
+
+#[test]
+fn test_elf_debug_alt_link() -> Result<(), Error> {
+    let view = ByteView::open(fixture("linux/dwz/dwz"))?;
+    let object = ElfObject::parse(&view)?;
+
+    let alt_link = object.debug_alt_link().expect("debug alt link");
+    assert_eq!(alt_link.path, "dwz.alt");
+
+    let alt_view = ByteView::open(fixture("linux/dwz/dwz.alt"))?;
+    let alt_object = ElfObject::parse(&alt_view)?;
+    assert_eq!(Some(alt_link.code_id()), alt_object.code_id());
+    assert_eq!(alt_object.debug_alt_link(), None);
+
+    Ok(())
+}
+
+#[test]
+fn test_elf_mini_debug_info() -> Result<(), Error> {
+    let view = ByteView::open(fixture("linux/minidebuginfo"))?;
+    let object = Object::parse(&view)?;
+    assert!(object.has_symbols());
+
+    let symbols = object.symbol_map();
+    let names = symbols
+        .iter()
+        .filter_map(|symbol| symbol.name())
+        .collect::<Vec<_>>();
+    assert!(names.contains(&"compute"));
+    assert!(names.contains(&"main"));
+
+    Ok(())
+}
+
+#[test]
+fn test_objects_sync() {
+    // Parsed objects can be shared across threads, even if they cache data internally.
+    fn assert_sync<T: Send + Sync>() {}
+
+    assert_sync::<ElfObject<'_>>();
+    assert_sync::<Object<'_>>();
+    assert_sync::<Archive<'_>>();
+}
+
 #[test]
 fn test_elf_debug() -> Result<(), Error> {
     let view = ByteView::open(fixture("linux/crash.debug"))?;
@@ -192,6 +343,8 @@ fn test_elf_debug() -> Result<(), Error> {
    ⋮)
     "###);
 
+    assert!(!object.has_sources());
+
     Ok(())
 }
 
@@ -231,6 +384,332 @@ fn test_elf_functions() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn test_elf_aliases_not_multiple() -> Result<(), Error> {
+    let view = ByteView::open(fixture("linux/crash.debug"))?;
+    let object = Object::parse(&view)?;
+
+    // Constructor and destructor variants are aliases of the same code, not folded functions.
+    let symbols = object.symbol_map();
+    assert!(symbols.iter().any(|s| s
+        .name()
+        .map_or(false, |n| n.contains("MinidumpDescriptorD1Ev"))));
+    assert!(symbols.iter().all(|s| !s.multiple));
+
+    let session = object.debug_session()?;
+    let functions = session.functions().collect::<Result<Vec<_>, _>>()?;
+    assert!(functions
+        .iter()
+        .any(|f| f.name.as_str().contains("LinuxDumperC1EiPKc")));
+    assert!(functions.iter().all(|f| !f.multiple));
+
+    Ok(())
+}
+
+#[test]
+fn test_elf_folded_across_units() -> Result<(), Error> {
+    let view = ByteView::open(fixture("linux/icf"))?;
+    let object = Object::parse(&view)?;
+
+    // `add_a` and `add_b` are defined in separate compilation units and folded by the linker.
+    let session = object.debug_session()?;
+    let functions = session.functions().collect::<Result<Vec<_>, _>>()?;
+    let folded = functions
+        .iter()
+        .filter(|f| f.multiple)
+        .map(|f| f.address)
+        .collect::<Vec<_>>();
+    assert_eq!(folded, [0x556, 0x556]);
+
+    let main = functions.iter().find(|f| f.name == "main").expect("main");
+    assert!(!main.multiple);
+
+    Ok(())
+}
+
+#[test]
+fn test_elf_relocatable() -> Result<(), Error> {
+    let view = ByteView::open(fixture("linux/relocatable.o"))?;
+    let object = Object::parse(&view)?;
+    assert_eq!(object.kind(), ObjectKind::Relocatable);
+
+    let session = object.debug_session()?;
+    let functions = session.functions().collect::<Result<Vec<_>, _>>()?;
+
+    let names = functions
+        .iter()
+        .map(|f| f.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["main", "compute"]);
+    assert_eq!(functions[0].address, 0x7);
+    assert_eq!(functions[1].address, 0x0);
+    assert_eq!(functions[1].inlinees[0].name, "square");
+    assert_eq!(functions[0].lines[0].file.name_str(), "relocatable.c");
+    assert_eq!(functions[0].lines[0].file.dir_str(), "/build");
+
+    Ok(())
+}
+
+#[test]
+fn test_elf_kernel_module() -> Result<(), Error> {
+    let view = ByteView::open(fixture("linux/kernel/module.ko"))?;
+    let object = ElfObject::parse(&view)?;
+    assert_eq!(object.kind(), ObjectKind::KernelModule);
+    assert_eq!(object.name(), Some("module"));
+
+    let info = object.module_info().expect("module info");
+    assert_eq!(info.version, Some("1.2.3"));
+    assert_eq!(info.vermagic, Some("5.10.0 SMP mod_unload"));
+
+    assert_eq!(object.section_address(".text"), Some(0x0));
+    assert_eq!(object.section_address(".init.text"), Some(0x7));
+    assert_eq!(object.section_address(".exit.text"), Some(0xd));
+
+    let symbols = object.symbol_map();
+    assert_eq!(symbols.lookup(0x0).and_then(|s| s.name()), Some("compute"));
+    assert_eq!(
+        symbols.lookup(0x7).and_then(|s| s.name()),
+        Some("init_module")
+    );
+    assert_eq!(
+        symbols.lookup(0xd).and_then(|s| s.name()),
+        Some("cleanup_module")
+    );
+
+    let session = object.debug_session()?;
+    let functions = session.functions().collect::<Result<Vec<_>, _>>()?;
+    let init_module = functions
+        .iter()
+        .find(|f| f.name == "init_module")
+        .expect("init_module function");
+    assert_eq!(init_module.address, 0x7);
+
+    Ok(())
+}
+
+#[test]
+fn test_elf_kernel_image() -> Result<(), Error> {
+    let view = ByteView::open(fixture("linux/kernel/vmlinux"))?;
+    let object = ElfObject::parse(&view)?;
+    assert_eq!(object.kind(), ObjectKind::KernelImage);
+    assert_eq!(object.load_address(), 0xffff_ffff_8100_1000);
+
+    let symbols = object.symbol_map();
+    assert_eq!(
+        symbols.lookup(0x0).and_then(|s| s.name()),
+        Some("start_kernel")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_elf_embedded_sources() -> Result<(), Error> {
+    let view = ByteView::open(fixture("linux/embed/embed"))?;
+    let object = ElfObject::parse(&view)?;
+    assert!(object.has_sources());
+    let session = object.debug_session()?;
+
+    let checksum = FileChecksum::Md5([
+        0x3c, 0xa5, 0x95, 0x07, 0x9e, 0x3a, 0xb6, 0xbe, 0xb8, 0x78, 0x16, 0x84, 0xa6, 0x46, 0xa6,
+        0x17,
+    ]);
+    let files = session.files().collect::<Result<Vec<_>, _>>()?;
+    assert!(!files.is_empty());
+    for file in &files {
+        assert_eq!(file.abs_path_str(), "/build/embed.c");
+        assert_eq!(file.checksum, Some(checksum));
+    }
+
+    let expected = std::fs::read_to_string(fixture("linux/embed/embed.c"))?;
+    let source = session.source_by_path("/build/embed.c")?;
+    assert_eq!(source.as_deref(), Some(expected.as_str()));
+    assert_eq!(session.source_by_path("/build/other.c")?, None);
+
+    Ok(())
+}
+
+#[test]
+fn test_elf_line_columns() -> Result<(), Error> {
+    let view = ByteView::open(fixture("linux/embed/embed"))?;
+    let object = ElfObject::parse(&view)?;
+    let session = object.debug_session()?;
+    let functions = session.functions().collect::<Result<Vec<_>, _>>()?;
+
+    let lines = functions[0]
+        .lines
+        .iter()
+        .map(|line| (line.address, line.line, line.column))
+        .collect::<Vec<_>>();
+    assert_eq!(functions[0].name, "main");
+    assert_eq!(lines, [(0x112e, 6, Some(12)), (0x1132, 7, Some(1))]);
+
+    Ok(())
+}
+
+#[test]
+fn test_elf_variables() -> Result<(), Error> {
+    let view = ByteView::open(fixture("linux/embed/embed"))?;
+    let object = ElfObject::parse(&view)?;
+    let session = object.debug_session()?;
+    let variables = session.variables().collect::<Result<Vec<_>, _>>()?;
+
+    let summary = variables
+        .iter()
+        .map(|v| {
+            let type_name = v.type_name.as_deref().unwrap_or_default();
+            (v.name.as_ref(), v.kind, type_name, v.function_address)
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        summary,
+        [
+            ("argc", VariableKind::Parameter, "int", 0x112e),
+            ("argv", VariableKind::Parameter, "char**", 0x112e),
+            ("value", VariableKind::Parameter, "int", 0x112e),
+            ("value", VariableKind::Parameter, "int", 0x1129),
+        ]
+    );
+
+    // `answer` receives its parameter in `rdi`, encoded as `DW_OP_reg5`.
+    assert_eq!(variables[3].scope, [0x1129..0x112e]);
+    assert_eq!(variables[3].location, VariableLocation::Expression(&[0x55]));
+
+    Ok(())
+}
+
+#[test]
+fn test_elf_types() -> Result<(), Error> {
+    let view = ByteView::open(fixture("linux/types/types"))?;
+    let object = ElfObject::parse(&view)?;
+    let session = object.debug_session()?;
+    let types = session.types().collect::<Result<Vec<_>, _>>()?;
+
+    let find = |name: &str| types.iter().find(|t| t.name == name).unwrap();
+
+    let color = find("shapes::Color");
+    assert_eq!(color.kind, TypeKind::Enum);
+    assert_eq!(color.size, Some(4));
+    let enumerators = color
+        .enumerators
+        .iter()
+        .map(|e| (e.name.as_ref(), e.value))
+        .collect::<Vec<_>>();
+    assert_eq!(enumerators, [("Red", 1), ("Green", 2), ("Blue", -1)]);
+
+    let circle = find("shapes::Circle");
+    assert_eq!(circle.kind, TypeKind::Class);
+    assert_eq!(circle.size, Some(40));
+    assert_eq!(
+        circle.base_classes,
+        [BaseClass {
+            type_name: "shapes::Shape".into(),
+            offset: Some(0),
+            is_virtual: false,
+        }]
+    );
+    let members = circle
+        .members
+        .iter()
+        .map(|m| (m.name.as_ref(), m.type_name.as_deref(), m.offset))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        members,
+        [
+            ("center", Some("shapes::Point"), 12),
+            ("radius", Some("double"), 24),
+            ("flags", Some("shapes::Flags"), 32),
+        ]
+    );
+
+    let flags = find("shapes::Flags");
+    let bitfields = flags
+        .members
+        .iter()
+        .map(|m| (m.name.as_ref(), m.offset, m.bit_offset, m.bit_size))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        bitfields,
+        [
+            ("visible", 0, Some(0), Some(1)),
+            ("layer", 0, Some(1), Some(3)),
+        ]
+    );
+
+    let vector = find("shapes::Vector");
+    assert_eq!(vector.kind, TypeKind::Typedef);
+    assert_eq!(vector.aliased_type.as_deref(), Some("shapes::Point"));
+
+    Ok(())
+}
+
+#[test]
+fn test_elf_split_dwarf() -> Result<(), Error> {
+    let view = ByteView::open(fixture("linux/split/split"))?;
+    let dwo_view = ByteView::open(fixture("linux/split/split.dwo"))?;
+
+    let mut object = ElfObject::parse(&view)?;
+    object.add_split_dwarf(ElfObject::parse(&dwo_view)?);
+
+    let session = object.debug_session()?;
+    let functions = session.functions().collect::<Result<Vec<_>, _>>()?;
+
+    let names = functions
+        .iter()
+        .map(|f| f.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["main", "compute"]);
+    assert_eq!(functions[1].inlinees[0].name, "square");
+    assert!(!functions[1].lines.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_elf_split_dwarf_package() -> Result<(), Error> {
+    let view = ByteView::open(fixture("linux/split/split4"))?;
+    let dwp_view = ByteView::open(fixture("linux/split/split4.dwp"))?;
+
+    let mut object = ElfObject::parse(&view)?;
+    object.add_split_dwarf(ElfObject::parse(&dwp_view)?);
+
+    let session = object.debug_session()?;
+    let functions = session.functions().collect::<Result<Vec<_>, _>>()?;
+
+    let names = functions
+        .iter()
+        .map(|f| f.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["main", "compute"]);
+    assert_eq!(functions[1].inlinees[0].name, "square");
+    assert!(!functions[1].lines.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_elf_supplementary_dwarf() -> Result<(), Error> {
+    let view = ByteView::open(fixture("linux/dwz/dwz"))?;
+    let alt_view = ByteView::open(fixture("linux/dwz/dwz.alt"))?;
+
+    let mut object = ElfObject::parse(&view)?;
+    object.set_supplementary_dwarf(ElfObject::parse(&alt_view)?);
+
+    let session = object.debug_session()?;
+    let functions = session.functions().collect::<Result<Vec<_>, _>>()?;
+
+    let names = functions
+        .iter()
+        .map(|f| f.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["main", "compute"]);
+    assert_eq!(functions[0].inlinees[0].name, "compute");
+    assert_eq!(functions[0].inlinees[0].inlinees[0].name, "square");
+
+    Ok(())
+}
+
 #[test]
 fn test_mach_executable() -> Result<(), Error> {
     let view = ByteView::open(fixture("macos/crash"))?;
@@ -298,6 +777,66 @@ fn test_mach_symbols() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn test_mach_function_starts() -> Result<(), Error> {
+    let view = ByteView::open(fixture("macos/function_starts"))?;
+    let object = MachObject::parse(&view)?;
+
+    let starts = object.function_starts().collect::<Vec<_>>();
+    assert_eq!(starts, [0x1000, 0x1040, 0x1060, 0x1080]);
+
+    // Only `main` has an entry in the symbol table. All other functions are returned as unnamed
+    // symbols, which bound the size of `main`.
+    let symbols = object.symbol_map();
+    let symbols = symbols
+        .iter()
+        .map(|symbol| (symbol.name(), symbol.address, symbol.size))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        symbols,
+        [
+            (None, 0x1000, 0x40),
+            (Some("main"), 0x1040, 0x20),
+            (None, 0x1060, 0x20),
+            (None, 0x1080, 0x20),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_mach_bcsymbolmap() -> Result<(), Error> {
+    let view = ByteView::open(fixture("macos/hidden"))?;
+    let mut object = MachObject::parse(&view)?;
+    assert!(object.requires_symbolmap());
+
+    let map_view = ByteView::open(fixture("macos/hidden.bcsymbolmap"))?;
+    assert!(BcSymbolMap::test(&map_view));
+    object.load_symbolmap(BcSymbolMap::parse(&map_view)?);
+    assert!(!object.requires_symbolmap());
+
+    let symbols = object.symbol_map();
+    let names = symbols.iter().map(|s| s.name()).collect::<Vec<_>>();
+    assert_eq!(names, [Some("main")]);
+
+    let session = object.debug_session()?;
+    let functions = session.functions().collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(functions.len(), 1);
+
+    let function = &functions[0];
+    assert_eq!(function.name.as_str(), "main");
+    assert_eq!(function.compilation_dir, b"/Users/sentry/project");
+    assert_eq!(function.inlinees[0].name.as_str(), "increment");
+    assert_eq!(function.lines[0].file.name, b"main.c");
+
+    let files = session.files().collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(files[0].abs_path_str(), "/Users/sentry/project/main.c");
+
+    Ok(())
+}
+
 #[test]
 fn test_mach_files() -> Result<(), Error> {
     let view = ByteView::open(fixture("macos/crash.dSYM/Contents/Resources/DWARF/crash"))?;
@@ -385,8 +924,116 @@ fn test_pe_64() -> Result<(), Error> {
     Ok(())
 }
 
-// NB: No test for PE symbols because our executable does not export any symbols
-// NB: No test for PE functions because we can only read debug info from PDBs
+#[test]
+fn test_pe_dwarf() -> Result<(), Error> {
+    let view = ByteView::open(fixture("windows/mingw.exe"))?;
+    let object = Object::parse(&view)?;
+
+    assert_eq!(object.file_format(), FileFormat::Pe);
+    assert_eq!(object.load_address(), 0x1_4000_0000);
+    assert!(object.has_debug_info());
+
+    let session = object.debug_session()?;
+
+    let files = session.files().collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(files[0].abs_path_str(), "/build/hello.c");
+
+    let functions = session.functions().collect::<Result<Vec<_>, _>>()?;
+    let names = functions
+        .iter()
+        .map(|f| (f.name.as_str(), f.address, f.size))
+        .collect::<Vec<_>>();
+    assert_eq!(names, [("main", 0x1014, 0x1f), ("add", 0x1000, 0x14)]);
+
+    let lines = functions[0]
+        .lines
+        .iter()
+        .map(|line| (line.address, line.line))
+        .collect::<Vec<_>>();
+    assert_eq!(lines, [(0x1014, 7), (0x101c, 8), (0x102e, 9), (0x1031, 10)]);
+
+    Ok(())
+}
+
+#[test]
+fn test_pe_exports() -> Result<(), Error> {
+    let view = ByteView::open(fixture("windows/exports.dll"))?;
+    let object = PeObject::parse(&view)?;
+
+    let symbols = object
+        .symbols()
+        .map(|s| (s.name.unwrap_or_default(), s.address))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        symbols,
+        [
+            ("HeapAllocFwd".into(), 0x2034),
+            ("OrdinalFwd".into(), 0x2047),
+            ("add".into(), 0x1000),
+            ("sub".into(), 0x1010)
+        ]
+    );
+
+    let forwarders = object
+        .exports()
+        .filter_map(|e| Some((e.name?, e.forwarder?)))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        forwarders,
+        [
+            (
+                "HeapAllocFwd",
+                PeForwarder {
+                    module: "KERNEL32",
+                    name: PeImportName::Name("HeapAlloc"),
+                }
+            ),
+            (
+                "OrdinalFwd",
+                PeForwarder {
+                    module: "USER32",
+                    name: PeImportName::Ordinal(12),
+                }
+            ),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_pe_imports() -> Result<(), Error> {
+    let view = ByteView::open(fixture("windows/exports.dll"))?;
+    let object = PeObject::parse(&view)?;
+
+    let imports = object
+        .imports()
+        .map(|i| (i.module, i.name, i.iat_address, i.delay_load))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        imports,
+        [
+            (
+                "KERNEL32.dll",
+                PeImportName::Name("GetLastError"),
+                0x20d0,
+                false
+            ),
+            ("KERNEL32.dll", PeImportName::Name("Sleep"), 0x20d8, false),
+            ("WS2_32.dll", PeImportName::Ordinal(115), 0x20e8, false),
+            (
+                "USER32.dll",
+                PeImportName::Name("MessageBoxA"),
+                0x3008,
+                true
+            ),
+            ("USER32.dll", PeImportName::Ordinal(2), 0x3010, true),
+        ]
+    );
+
+    Ok(())
+}
 
 #[test]
 fn test_pdb() -> Result<(), Error> {
@@ -412,6 +1059,164 @@ fn test_pdb() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn test_pdb_variables() -> Result<(), Error> {
+    let view = ByteView::open(fixture("windows/crash.pdb"))?;
+    let object = PdbObject::parse(&view)?;
+    let session = object.debug_session()?;
+    let variables = session.variables().collect::<Result<Vec<_>, _>>()?;
+
+    let parameters = variables
+        .iter()
+        .filter(|v| v.function_address == 0x1000 && v.kind == VariableKind::Parameter)
+        .filter(|v| v.location != VariableLocation::Unknown)
+        .map(|v| {
+            (
+                v.name.as_ref(),
+                v.type_name.as_deref().unwrap_or_default(),
+                &v.location,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        parameters,
+        [
+            (
+                "this",
+                "google_breakpad::CrashGenerationClient* const",
+                &VariableLocation::Register(18)
+            ),
+            (
+                "ex_info",
+                "_EXCEPTION_POINTERS*",
+                &VariableLocation::RegisterRelative {
+                    register: 21,
+                    offset: 4
+                }
+            ),
+            (
+                "assert_info",
+                "MDRawAssertionInfo*",
+                &VariableLocation::RegisterRelative {
+                    register: 21,
+                    offset: 8
+                }
+            ),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_pdb_types() -> Result<(), Error> {
+    let view = ByteView::open(fixture("windows/crash.pdb"))?;
+    let object = PdbObject::parse(&view)?;
+    let session = object.debug_session()?;
+    let types = session.types().collect::<Result<Vec<_>, _>>()?;
+
+    let find = |name: &str| types.iter().find(|t| t.name == name).unwrap();
+
+    let app_memory = find("google_breakpad::AppMemory");
+    assert_eq!(app_memory.kind, TypeKind::Struct);
+    assert_eq!(app_memory.size, Some(16));
+    let members = app_memory
+        .members
+        .iter()
+        .map(|m| (m.name.as_ref(), m.type_name.as_deref(), m.offset))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        members,
+        [
+            ("ptr", Some("unsigned long long"), 0),
+            ("length", Some("unsigned long"), 8),
+        ]
+    );
+
+    let handler_type = find("google_breakpad::ExceptionHandler::HandlerType");
+    assert_eq!(handler_type.kind, TypeKind::Enum);
+    assert_eq!(handler_type.size, Some(4));
+    assert_eq!(handler_type.enumerators[4].name, "HANDLER_ALL");
+    assert_eq!(handler_type.enumerators[4].value, 7);
+
+    let uint = find("UINT");
+    assert_eq!(uint.kind, TypeKind::Typedef);
+    assert_eq!(uint.aliased_type.as_deref(), Some("unsigned int"));
+
+    Ok(())
+}
+
+#[test]
+fn test_pdb_source_resolver() -> Result<(), Error> {
+    let view = ByteView::open(fixture("windows/crash.pdb"))?;
+    let object = PdbObject::parse(&view)?;
+
+    // This PDB has not been source indexed.
+    let resolver = object.source_resolver()?;
+    assert!(resolver.is_empty());
+    assert_eq!(resolver.resolve_path("c:\\projects\\crash\\main.cpp"), None);
+
+    Ok(())
+}
+
+#[test]
+fn test_pdb_source_indexed() -> Result<(), Error> {
+    let view = ByteView::open(fixture("windows/source_indexed.pdb"))?;
+    let object = PdbObject::parse(&view)?;
+    let resolver = object.source_resolver()?;
+    assert!(!resolver.is_empty());
+
+    // Paths are looked up case insensitively and regardless of the path separator.
+    let session = object.debug_session()?;
+    let source = session.source_by_path("c:/src/main.c")?;
+    assert_eq!(source.as_deref(), Some("int main() {\n    return 0;\n}\n"));
+    assert_eq!(
+        resolver.resolve_path("c:/src/main.c").as_deref(),
+        Some("https://raw.example.org/repo/abc123/src/Main.c")
+    );
+
+    // SourceLink mappings take precedence over the source server stream.
+    assert!(session.source_by_path("C:\\src\\Util.h")?.is_some());
+    assert_eq!(
+        resolver.resolve_path("C:\\src\\Util.h").as_deref(),
+        Some("https://example.org/sourcelink/Util.h")
+    );
+
+    assert_eq!(session.source_by_path("C:\\src\\other.c")?, None);
+    assert_eq!(resolver.resolve_path("C:\\src\\other.c"), None);
+
+    Ok(())
+}
+
+#[test]
+fn test_pdb_embedded_sources() -> Result<(), Error> {
+    let view = ByteView::open(fixture("windows/embedded_sources.pdb"))?;
+    let object = PdbObject::parse(&view)?;
+    assert!(object.has_sources());
+
+    let session = object.debug_session()?;
+
+    // Stored uncompressed and looked up by its original path.
+    let source = session.source_by_path("C:\\src\\Main.c")?;
+    assert_eq!(source.as_deref(), Some("int main() {\n    return 0;\n}\n"));
+
+    // Stored with deflate compression.
+    let source = session.source_by_path("c:/src/util.h")?;
+    assert_eq!(
+        source.as_deref(),
+        Some("#define ANSWER 42\n".repeat(4).as_str())
+    );
+
+    assert_eq!(session.source_by_path("C:\\src\\other.c")?, None);
+
+    let view = ByteView::open(fixture("windows/crash.pdb"))?;
+    let object = PdbObject::parse(&view)?;
+    assert!(!object.has_sources());
+
+    Ok(())
+}
+
 #[test]
 fn test_pdb_symbols() -> Result<(), Error> {
     let view = ByteView::open(fixture("windows/crash.pdb"))?;
@@ -448,6 +1253,29 @@ fn test_pdb_functions() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn test_pdb_folded_functions() -> Result<(), Error> {
+    let view = ByteView::open(fixture("windows/crash.pdb"))?;
+    let object = Object::parse(&view)?;
+
+    // `_matherr` and several CRT stubs have been folded into the same code.
+    let symbols = object.symbol_map();
+    let symbol = symbols.lookup(0x3250).expect("symbol");
+    assert!(symbol.multiple);
+    assert!(!symbols.lookup(0x1000).expect("symbol").multiple);
+
+    let session = object.debug_session()?;
+    let functions = session.functions().collect::<Result<Vec<_>, _>>()?;
+    let matherr = functions
+        .iter()
+        .find(|f| f.name == "_matherr")
+        .expect("_matherr");
+    assert_eq!(matherr.address, 0x3250);
+    assert!(matherr.multiple);
+
+    Ok(())
+}
+
 #[test]
 fn test_pdb_anonymous_namespace() -> Result<(), Error> {
     // Regression test for ?A0x<hash> namespaces
@@ -474,6 +1302,137 @@ fn test_pdb_anonymous_namespace() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn test_ppdb() -> Result<(), Error> {
+    let view = ByteView::open(fixture("windows/portable.pdb"))?;
+    assert_eq!(Archive::peek(&view), FileFormat::PortablePdb);
+
+    let object = Object::parse(&view)?;
+    assert_eq!(object.file_format(), FileFormat::PortablePdb);
+    assert_eq!(
+        object.debug_id().to_string(),
+        "5e1b9d2a-7c4f-4b8e-9a3d-1f2e3d4c5b6a-12345678"
+    );
+    assert_eq!(object.code_id(), None);
+    assert_eq!(object.kind(), ObjectKind::Debug);
+    assert!(!object.has_symbols());
+    assert!(object.has_debug_info());
+    assert!(!object.has_unwind_info());
+    assert!(object.has_sources());
+
+    Ok(())
+}
+
+#[test]
+fn test_ppdb_files() -> Result<(), Error> {
+    let view = ByteView::open(fixture("windows/portable.pdb"))?;
+    let object = Object::parse(&view)?;
+
+    let session = object.debug_session()?;
+    let files = session.files().collect::<Result<Vec<_>, _>>()?;
+
+    let paths = files.iter().map(|f| f.abs_path_str()).collect::<Vec<_>>();
+    assert_eq!(paths, ["/app/src/Program.cs", "/app/src/Util.cs"]);
+    assert!(matches!(files[0].checksum, Some(FileChecksum::Sha256(_))));
+    assert!(matches!(files[1].checksum, Some(FileChecksum::Sha1(_))));
+
+    Ok(())
+}
+
+#[test]
+fn test_ppdb_functions() -> Result<(), Error> {
+    let view = ByteView::open(fixture("windows/portable.pdb"))?;
+    let object = Object::parse(&view)?;
+
+    let session = object.debug_session()?;
+    let functions = session.functions().collect::<Result<Vec<_>, _>>()?;
+
+    // The third method has no sequence points.
+    assert_eq!(functions.len(), 2);
+    assert_eq!(functions[0].address, 0x0100_0000);
+    assert_eq!(functions[0].size, 0x11);
+
+    // The hidden sequence point at IL offset 0xc is skipped.
+    let lines = functions[0]
+        .lines
+        .iter()
+        .map(|l| (l.address & 0xff_ffff, l.size, l.line, l.column))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        [
+            (0x0, Some(0x1), 6, Some(5)),
+            (0x1, Some(0xb), 7, Some(9)),
+            (0x10, None, 8, Some(5)),
+        ]
+    );
+
+    // The second method switches documents.
+    let files = functions[1]
+        .lines
+        .iter()
+        .map(|l| l.file.name_str())
+        .collect::<Vec<_>>();
+    assert_eq!(files, ["Program.cs", "Util.cs"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_ppdb_lookup_line() -> Result<(), Error> {
+    let view = ByteView::open(fixture("windows/portable.pdb"))?;
+    let object = PortablePdbObject::parse(&view)?;
+    let session = object.debug_session()?;
+
+    let line = session.lookup_line(0x0600_0001, 0x5)?.expect("line");
+    assert_eq!((line.address, line.line, line.column), (0x1, 7, Some(9)));
+    assert_eq!(line.file.name_str(), "Program.cs");
+
+    // Offsets covered by hidden sequence points have no source location.
+    assert!(session.lookup_line(0x0600_0001, 0xd)?.is_none());
+
+    let line = session.lookup_line(0x0600_0002, 0x8)?.expect("line");
+    assert_eq!((line.line, line.column), (3, Some(35)));
+    assert_eq!(line.file.name_str(), "Util.cs");
+
+    let points = session.sequence_points(0x0600_0002)?;
+    assert_eq!((points[1].end_line, points[1].end_column), (4, 25));
+
+    // Tokens of other tables do not refer to methods.
+    assert!(session.lookup_line(0x0200_0001, 0)?.is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_ppdb_sources() -> Result<(), Error> {
+    let view = ByteView::open(fixture("windows/portable.pdb"))?;
+    let object = PortablePdbObject::parse(&view)?;
+
+    let source_link = object.source_link()?.expect("source link");
+    assert_eq!(
+        source_link.resolve("/app/src/Program.cs").as_deref(),
+        Some("https://example.org/app/src/Program.cs")
+    );
+
+    let session = object.debug_session()?;
+
+    // Stored with deflate compression.
+    let source = session
+        .source_by_path("/app/src/Program.cs")?
+        .expect("source");
+    assert!(source.starts_with("using System;\n"));
+    assert_eq!(source.matches("class Program").count(), 3);
+
+    // Stored uncompressed.
+    let source = session.source_by_path("/app/src/Util.cs")?.expect("source");
+    assert!(source.contains("public static int Answer() => 42;"));
+
+    assert_eq!(session.source_by_path("/app/src/Other.cs")?, None);
+
+    Ok(())
+}
+
 #[test]
 fn test_wasm_symbols() -> Result<(), Error> {
     let view = ByteView::open(fixture("wasm/simple.wasm"))?;
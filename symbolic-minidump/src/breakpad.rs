@@ -0,0 +1,609 @@
+//! Writing of Breakpad ASCII symbol files.
+//!
+//! The root type exposed by this module is [`BreakpadWriter`], which converts any object file into
+//! the text-based symbol format used by the Breakpad and Crashpad libraries. The output is
+//! equivalent to what Breakpad's `dump_syms` tool generates and can be read again with
+//! [`BreakpadObject`].
+//!
+//! A symbol file consists of the following records, in this order:
+//!
+//!  - `MODULE`: Operating system, architecture, identifier and name of the object.
+//!  - `INFO CODE_ID`: The code identifier of the object, if available.
+//!  - `FILE`: Paths of source files referenced by line and inline records.
+//!  - `INLINE_ORIGIN`: Names of inlined functions referenced by inline records.
+//!  - `FUNC`: Functions, each followed by `INLINE` records for their inlined call sites and line
+//!    records for the innermost source locations.
+//!  - `PUBLIC`: Symbols from the symbol table that are not covered by a function.
+//!  - `STACK`: Unwind information, see [`AsciiCfiWriter`].
+//!
+//! `FUNC` and `PUBLIC` records are marked with the `m` flag if their code is shared with other
+//! functions, for instance after identical code folding.
+//!
+//! [`BreakpadWriter`]: struct.BreakpadWriter.html
+//! [`BreakpadObject`]: ../../symbolic_debuginfo/breakpad/struct.BreakpadObject.html
+//! [`AsciiCfiWriter`]: ../cfi/struct.AsciiCfiWriter.html
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Write};
+
+use thiserror::Error;
+
+use symbolic_common::{clean_path, join_path, Name};
+use symbolic_debuginfo::{DebugSession, FileFormat, Function, Object, ObjectLike};
+use symbolic_demangle::{Demangle, DemangleOptions};
+
+use crate::cfi::{AsciiCfiWriter, CfiError, CfiErrorKind};
+
+/// Placeholder used for call sites without a file.
+const UNKNOWN_FILE: &str = "<unknown>";
+
+/// Demangling options matching the names written by Breakpad's `dump_syms`.
+const DEMANGLE_OPTIONS: DemangleOptions = DemangleOptions::name_only().parameters(true);
+
+/// The error type for [`BreakpadWriterError`].
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BreakpadWriterErrorKind {
+    /// The `Object` contains invalid debug information and cannot be converted.
+    BadDebugFile,
+
+    /// The unwind information of the `Object` cannot be converted.
+    BadUnwindInfo,
+
+    /// Generic error when writing the symbol file, most likely IO.
+    WriteFailed,
+}
+
+impl fmt::Display for BreakpadWriterErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BadDebugFile => write!(f, "malformed debug info file"),
+            Self::BadUnwindInfo => write!(f, "failed to convert unwind information"),
+            Self::WriteFailed => write!(f, "failed to write breakpad symbols"),
+        }
+    }
+}
+
+/// An error returned by [`BreakpadWriter`](struct.BreakpadWriter.html).
+#[derive(Debug, Error)]
+#[error("{kind}")]
+pub struct BreakpadWriterError {
+    kind: BreakpadWriterErrorKind,
+    #[source]
+    source: Option<Box<dyn Error + Send + Sync + 'static>>,
+}
+
+impl BreakpadWriterError {
+    /// Creates a new Breakpad writer error from a known kind of error as well as an arbitrary
+    /// error payload.
+    fn new<E>(kind: BreakpadWriterErrorKind, source: E) -> Self
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        let source = Some(source.into());
+        Self { kind, source }
+    }
+
+    /// Returns the corresponding [`BreakpadWriterErrorKind`] for this error.
+    pub fn kind(&self) -> BreakpadWriterErrorKind {
+        self.kind
+    }
+}
+
+impl From<BreakpadWriterErrorKind> for BreakpadWriterError {
+    fn from(kind: BreakpadWriterErrorKind) -> Self {
+        Self { kind, source: None }
+    }
+}
+
+impl From<io::Error> for BreakpadWriterError {
+    fn from(e: io::Error) -> Self {
+        Self::new(BreakpadWriterErrorKind::WriteFailed, e)
+    }
+}
+
+impl From<CfiError> for BreakpadWriterError {
+    fn from(e: CfiError) -> Self {
+        let kind = match e.kind() {
+            CfiErrorKind::WriteFailed => BreakpadWriterErrorKind::WriteFailed,
+            _ => BreakpadWriterErrorKind::BadUnwindInfo,
+        };
+
+        Self::new(kind, e)
+    }
+}
+
+/// Returns the operating system name that `dump_syms` writes for the given file format.
+fn breakpad_os(format: FileFormat) -> &'static str {
+    match format {
+        FileFormat::Elf => "Linux",
+        FileFormat::MachO => "mac",
+        FileFormat::Pdb | FileFormat::Pe | FileFormat::PortablePdb => "windows",
+        _ => "unknown",
+    }
+}
+
+/// A line record of a function, referring to the innermost source location.
+#[derive(Clone, Copy, Debug)]
+struct LineRecord {
+    address: u64,
+    size: u64,
+    line: u64,
+    file_id: u64,
+}
+
+impl LineRecord {
+    fn end_address(&self) -> u64 {
+        self.address + self.size
+    }
+}
+
+/// An inlined call site within a function.
+#[derive(Debug)]
+struct InlineRecord {
+    depth: usize,
+    call_line: u64,
+    call_file_id: u64,
+    origin_id: u64,
+    ranges: Vec<(u64, u64)>,
+}
+
+/// A function with its inlinees and line records.
+#[derive(Debug)]
+struct FuncRecord {
+    address: u64,
+    size: u64,
+    name: String,
+    multiple: bool,
+    parameter_size: u64,
+    inlines: Vec<InlineRecord>,
+    lines: Vec<LineRecord>,
+}
+
+/// Assigns sequential identifiers to strings in the order they are first encountered.
+#[derive(Debug, Default)]
+struct Interner {
+    ids: HashMap<String, u64>,
+    values: Vec<String>,
+}
+
+impl Interner {
+    fn intern(&mut self, value: &str) -> u64 {
+        if let Some(&id) = self.ids.get(value) {
+            return id;
+        }
+
+        let id = self.values.len() as u64;
+        self.ids.insert(value.to_owned(), id);
+        self.values.push(value.to_owned());
+        id
+    }
+}
+
+/// Resolves the absolute path of a line record's file.
+fn line_path(compilation_dir: &[u8], line: &symbolic_debuginfo::LineInfo<'_>) -> String {
+    let compilation_dir = String::from_utf8_lossy(compilation_dir);
+    let path = join_path(&compilation_dir, &line.file.path_str());
+    clean_path(&path).into_owned()
+}
+
+/// Returns the name of a function as written by `dump_syms`.
+fn demangled_name(name: &Name<'_>) -> String {
+    name.try_demangle(DEMANGLE_OPTIONS).into_owned()
+}
+
+/// Replaces the parts of `base` that are covered by `top` with the records from `top`.
+///
+/// Both lists must be sorted by address. The result is sorted by address again.
+fn overlay_lines(base: Vec<LineRecord>, top: Vec<LineRecord>) -> Vec<LineRecord> {
+    if top.is_empty() {
+        return base;
+    }
+
+    let mut lines = Vec::with_capacity(base.len() + top.len());
+    let mut covered = top.iter().peekable();
+
+    for line in base {
+        let mut address = line.address;
+        let end = line.end_address();
+
+        while covered
+            .peek()
+            .map_or(false, |top| top.end_address() <= address)
+        {
+            covered.next();
+        }
+
+        for top in covered.clone() {
+            if top.address >= end {
+                break;
+            }
+
+            if top.address > address {
+                lines.push(LineRecord {
+                    address,
+                    size: top.address - address,
+                    ..line
+                });
+            }
+
+            address = address.max(top.end_address());
+            if address >= end {
+                break;
+            }
+        }
+
+        if address < end {
+            lines.push(LineRecord {
+                address,
+                size: end - address,
+                ..line
+            });
+        }
+    }
+
+    lines.extend(top);
+    lines.sort_by_key(|line| line.address);
+    lines
+}
+
+/// Collects state while converting functions into Breakpad records.
+#[derive(Debug, Default)]
+struct FunctionConverter {
+    files: Interner,
+    origins: Interner,
+}
+
+impl FunctionConverter {
+    /// Converts a function and all of its inlinees.
+    fn convert(&mut self, function: &Function<'_>) -> FuncRecord {
+        let mut inlines = Vec::new();
+        self.convert_inlinees(function, function.compilation_dir, 0, &mut inlines);
+
+        FuncRecord {
+            address: function.address,
+            size: function.size,
+            name: demangled_name(&function.name),
+            multiple: function.multiple,
+            parameter_size: function.parameter_size.unwrap_or(0),
+            inlines,
+            lines: self.convert_lines(function, function.compilation_dir),
+        }
+    }
+
+    /// Returns the innermost line records of a function, sorted by address.
+    fn convert_lines(
+        &mut self,
+        function: &Function<'_>,
+        compilation_dir: &[u8],
+    ) -> Vec<LineRecord> {
+        let compilation_dir = match function.compilation_dir {
+            [] => compilation_dir,
+            dir => dir,
+        };
+
+        let mut sorted = function.lines.iter().collect::<Vec<_>>();
+        sorted.sort_by_key(|line| line.address);
+
+        let end = function.end_address();
+        let mut lines = Vec::with_capacity(sorted.len());
+        for (index, line) in sorted.iter().enumerate() {
+            let next = sorted.get(index + 1).map_or(end, |next| next.address);
+            let size = line
+                .size
+                .unwrap_or_else(|| next.max(line.address) - line.address);
+
+            if size == 0 {
+                continue;
+            }
+
+            lines.push(LineRecord {
+                address: line.address,
+                size,
+                line: line.line,
+                file_id: self.files.intern(&line_path(compilation_dir, line)),
+            });
+        }
+
+        for inlinee in &function.inlinees {
+            let inlinee_lines = self.convert_lines(inlinee, compilation_dir);
+            lines = overlay_lines(lines, inlinee_lines);
+        }
+
+        lines
+    }
+
+    /// Converts the inlinees of a function recursively into inline records.
+    ///
+    /// Records are appended in depth-first order, so that each record follows its parent.
+    fn convert_inlinees(
+        &mut self,
+        function: &Function<'_>,
+        compilation_dir: &[u8],
+        depth: usize,
+        inlines: &mut Vec<InlineRecord>,
+    ) {
+        let compilation_dir = match function.compilation_dir {
+            [] => compilation_dir,
+            dir => dir,
+        };
+
+        for inlinee in &function.inlinees {
+            if inlinee.size == 0 {
+                continue;
+            }
+
+            // The lines of the parent function point to the call site of the inlinee. Fall back
+            // to the closest preceding line record if the start of the inlinee is not covered.
+            let call_site = function
+                .lines
+                .iter()
+                .filter(|line| line.address <= inlinee.address)
+                .max_by_key(|line| line.address);
+
+            let (call_line, call_file_id) = match call_site {
+                Some(line) => (
+                    line.line,
+                    self.files.intern(&line_path(compilation_dir, line)),
+                ),
+                None => (0, self.files.intern(UNKNOWN_FILE)),
+            };
+
+            inlines.push(InlineRecord {
+                depth,
+                call_line,
+                call_file_id,
+                origin_id: self.origins.intern(&demangled_name(&inlinee.name)),
+                ranges: inlinee_ranges(inlinee),
+            });
+
+            self.convert_inlinees(inlinee, compilation_dir, depth + 1, inlines);
+        }
+    }
+}
+
+/// Computes the address ranges of an inlinee from its line records.
+///
+/// Inlinees may cover multiple disjoint ranges, which are only visible from their line records. If
+/// there are no line records, the entire range of the inlinee is used.
+fn inlinee_ranges(inlinee: &Function<'_>) -> Vec<(u64, u64)> {
+    let mut lines = inlinee
+        .lines
+        .iter()
+        .filter_map(|line| Some((line.address, line.address + line.size?)))
+        .filter(|&(start, end)| start < end)
+        .collect::<Vec<_>>();
+    lines.sort_unstable();
+
+    let mut ranges: Vec<(u64, u64)> = Vec::new();
+    for (start, end) in lines {
+        match ranges.last_mut() {
+            Some(last) if last.1 >= start => last.1 = last.1.max(end),
+            _ => ranges.push((start, end)),
+        }
+    }
+
+    if ranges.is_empty() {
+        ranges.push((inlinee.address, inlinee.end_address()));
+    }
+
+    ranges
+}
+
+/// A writer for Breakpad ASCII symbol files.
+///
+/// The writer converts symbols, debug information and unwind information of an object file into
+/// the Breakpad symbol format. See the [module documentation](index.html) for the list of records.
+///
+/// Function and symbol names are demangled, and paths of source files are made absolute using the
+/// compilation directory of each function. Line records always refer to the innermost inlined
+/// function, while `INLINE` records describe the call sites.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// # use symbolic_common::ByteView;
+/// # use symbolic_debuginfo::Object;
+/// # use symbolic_minidump::breakpad::{BreakpadWriter, BreakpadWriterError};
+/// # fn main() -> Result<(), BreakpadWriterError> {
+/// # let byteview = ByteView::open("/path/to/object").unwrap();
+/// # let object = Object::parse(&byteview).unwrap();
+/// let mut writer = BreakpadWriter::new(Vec::new());
+/// writer.write_object(&object, "object")?;
+/// let buffer = writer.into_inner();
+/// # Ok(())
+/// # }
+/// ```
+pub struct BreakpadWriter<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> BreakpadWriter<W> {
+    /// Creates a new `BreakpadWriter` that outputs to a writer.
+    pub fn new(inner: W) -> Self {
+        BreakpadWriter { inner }
+    }
+
+    /// Writes all records for the given object, including unwind information.
+    ///
+    /// The `object_name` is written into the `MODULE` record. It is usually the file name of the
+    /// debug file, such as `crash.pdb` or `libfoo.so`.
+    pub fn write_object(
+        &mut self,
+        object: &Object<'_>,
+        object_name: &str,
+    ) -> Result<(), BreakpadWriterError> {
+        // Breakpad files keep the operating system of the object they were dumped from.
+        let os = match object {
+            Object::Breakpad(breakpad) => breakpad.os(),
+            _ => breakpad_os(object.file_format()),
+        };
+
+        self.write_records(object, object_name, os)?;
+        AsciiCfiWriter::new(&mut self.inner).process(object)?;
+        Ok(())
+    }
+
+    /// Writes all records for the given object, except for unwind information.
+    ///
+    /// This works with any implementation of `ObjectLike`. To also write `STACK` records, use
+    /// [`write_object`](struct.BreakpadWriter.html#method.write_object) instead. The operating
+    /// system is derived from the file format, so it is written as `unknown` for Breakpad objects.
+    /// `write_object` retains the operating system of Breakpad objects instead.
+    pub fn write_symbols<'d, 'o, O>(
+        &mut self,
+        object: &'o O,
+        object_name: &str,
+    ) -> Result<(), BreakpadWriterError>
+    where
+        O: ObjectLike<'d, 'o>,
+        O::Error: Error + Send + Sync + 'static,
+    {
+        self.write_records(object, object_name, breakpad_os(object.file_format()))
+    }
+
+    /// Writes all records except for unwind information with the given operating system.
+    fn write_records<'d, 'o, O>(
+        &mut self,
+        object: &'o O,
+        object_name: &str,
+        os: &str,
+    ) -> Result<(), BreakpadWriterError>
+    where
+        O: ObjectLike<'d, 'o>,
+        O::Error: Error + Send + Sync + 'static,
+    {
+        writeln!(
+            self.inner,
+            "MODULE {} {} {} {}",
+            os,
+            object.arch().name(),
+            object.debug_id().breakpad(),
+            object_name,
+        )?;
+
+        if let Some(code_id) = object.code_id() {
+            writeln!(
+                self.inner,
+                "INFO CODE_ID {}",
+                code_id.as_str().to_uppercase()
+            )?;
+        }
+
+        let session = object
+            .debug_session()
+            .map_err(|e| BreakpadWriterError::new(BreakpadWriterErrorKind::BadDebugFile, e))?;
+
+        let mut converter = FunctionConverter::default();
+        let mut functions = Vec::new();
+        for function in session.functions() {
+            let function = function
+                .map_err(|e| BreakpadWriterError::new(BreakpadWriterErrorKind::BadDebugFile, e))?;
+
+            if function.size > 0 {
+                functions.push(converter.convert(&function));
+            }
+        }
+
+        functions.sort_by_key(|function| function.address);
+
+        for (id, path) in converter.files.values.iter().enumerate() {
+            writeln!(self.inner, "FILE {} {}", id, path)?;
+        }
+
+        for (id, name) in converter.origins.values.iter().enumerate() {
+            writeln!(self.inner, "INLINE_ORIGIN {} {}", id, name)?;
+        }
+
+        for function in &functions {
+            self.write_function(function)?;
+        }
+
+        // Like `dump_syms`, only write public symbols that are not covered by a function.
+        for symbol in object.symbols() {
+            let name = match symbol.name {
+                Some(ref name) if !name.is_empty() => name,
+                _ => continue,
+            };
+
+            let covered = match functions.binary_search_by_key(&symbol.address, |f| f.address) {
+                Ok(_) => true,
+                Err(0) => false,
+                Err(index) => {
+                    let function = &functions[index - 1];
+                    symbol.address < function.address + function.size
+                }
+            };
+
+            if !covered {
+                let name = demangled_name(&Name::from(name.as_ref()));
+                writeln!(
+                    self.inner,
+                    "PUBLIC {}{:x} {:x} {}",
+                    if symbol.multiple { "m " } else { "" },
+                    symbol.address,
+                    symbol.parameter_size.unwrap_or(0),
+                    name
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes a function record along with its inline and line records.
+    fn write_function(&mut self, function: &FuncRecord) -> Result<(), BreakpadWriterError> {
+        write!(
+            self.inner,
+            "FUNC {}{:x} {:x} {:x}",
+            if function.multiple { "m " } else { "" },
+            function.address,
+            function.size,
+            function.parameter_size
+        )?;
+        if !function.name.is_empty() {
+            write!(self.inner, " {}", function.name)?;
+        }
+        writeln!(self.inner)?;
+
+        for inline in &function.inlines {
+            write!(
+                self.inner,
+                "INLINE {} {} {} {}",
+                inline.depth, inline.call_line, inline.call_file_id, inline.origin_id
+            )?;
+            for &(start, end) in &inline.ranges {
+                write!(self.inner, " {:x} {:x}", start, end - start)?;
+            }
+            writeln!(self.inner)?;
+        }
+
+        for line in &function.lines {
+            // Breakpad reads line numbers as signed 32-bit integers. Negative line numbers are
+            // read back as large unsigned values, so write those in their original form.
+            writeln!(
+                self.inner,
+                "{:x} {:x} {} {}",
+                line.address, line.size, line.line as u32 as i32, line.file_id
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the wrapped writer from this instance.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write + Default> BreakpadWriter<W> {
+    /// Writes all records for the given object to a new writer instance.
+    pub fn transform(object: &Object<'_>, object_name: &str) -> Result<W, BreakpadWriterError> {
+        let mut writer = BreakpadWriter::new(W::default());
+        writer.write_object(object, object_name)?;
+        Ok(writer.into_inner())
+    }
+}
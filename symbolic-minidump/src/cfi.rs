@@ -17,22 +17,26 @@
 //! [processor]: ../processor/index.html
 //! [`CfiCache`]: struct.CfiCache.html
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::convert::TryInto;
 use std::error::Error;
 use std::fmt;
 use std::io::{self, Write};
+use std::mem;
 use std::ops::Range;
 
 use thiserror::Error;
 
-use symbolic_common::{Arch, ByteView, UnknownArchError};
+use symbolic_common::{Arch, ByteView, CpuFamily, UnknownArchError};
 use symbolic_debuginfo::breakpad::{BreakpadError, BreakpadObject, BreakpadStackRecord};
 use symbolic_debuginfo::dwarf::gimli::{
-    BaseAddresses, CfaRule, CieOrFde, DebugFrame, EhFrame, Error as GimliError,
+    BaseAddresses, CfaRule, CieOrFde, DebugFrame, EhFrame, Endianity, Error as GimliError,
     FrameDescriptionEntry, Reader, Register, RegisterRule, UninitializedUnwindContext,
     UnwindSection,
 };
-use symbolic_debuginfo::dwarf::Dwarf;
+use symbolic_debuginfo::dwarf::{Dwarf, Endian};
+use symbolic_debuginfo::elf::ElfObject;
+use symbolic_debuginfo::macho::MachObject;
 use symbolic_debuginfo::pdb::pdb::{self, FallibleIterator, FrameData, Rva, StringTable};
 use symbolic_debuginfo::pdb::PdbObject;
 use symbolic_debuginfo::pe::{PeObject, RuntimeFunction, UnwindOperation};
@@ -48,6 +52,54 @@ const EMPTY_FUNCTION: RuntimeFunction = RuntimeFunction {
     unwind_info_address: 0,
 };
 
+/// Size of a runtime function entry in the exception directory of ARM64 PE files.
+const ARM64_RUNTIME_FUNCTION_SIZE: usize = 8;
+
+/// DWARF register number of the frame pointer `x29` on ARM64.
+const ARM64_FP: u16 = 29;
+
+/// DWARF register number of the link register `x30` on ARM64, which holds the return address.
+const ARM64_LR: u16 = 30;
+
+/// DWARF register number of the first SIMD and floating point register `v0` on ARM64.
+const ARM64_V0: u16 = 64;
+
+/// DWARF register number of the stack pointer `sp` on 32-bit ARM.
+const ARM_SP: u16 = 13;
+
+/// DWARF register number of the link register `lr` on 32-bit ARM.
+const ARM_LR: u16 = 14;
+
+/// DWARF register number of the program counter `pc` on 32-bit ARM.
+const ARM_PC: u16 = 15;
+
+/// Value of an `.ARM.exidx` entry for functions that cannot be unwound.
+const EXIDX_CANTUNWIND: u32 = 1;
+
+/// Mask of the mode in compact unwind encodings of Mach-O files.
+const UNWIND_MODE_MASK: u32 = 0x0f00_0000;
+
+/// x86_64 compact unwind mode for functions with a `rbp` frame.
+const UNWIND_X86_64_MODE_RBP_FRAME: u32 = 0x0100_0000;
+
+/// x86_64 compact unwind mode for frameless functions with a small constant stack size.
+const UNWIND_X86_64_MODE_STACK_IMMD: u32 = 0x0200_0000;
+
+/// x86_64 compact unwind mode for frameless functions with a large constant stack size.
+const UNWIND_X86_64_MODE_STACK_IND: u32 = 0x0300_0000;
+
+/// ARM64 compact unwind mode for frameless functions.
+const UNWIND_ARM64_MODE_FRAMELESS: u32 = 0x0200_0000;
+
+/// ARM64 compact unwind mode for functions with a `x29` frame.
+const UNWIND_ARM64_MODE_FRAME: u32 = 0x0400_0000;
+
+/// Second level page kind of compact unwind information with uncompressed entries.
+const UNWIND_SECOND_LEVEL_REGULAR: u32 = 2;
+
+/// Second level page kind of compact unwind information with compressed entries.
+const UNWIND_SECOND_LEVEL_COMPRESSED: u32 = 3;
+
 /// The error type for [`CfiError`].
 #[non_exhaustive]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -267,12 +319,12 @@ impl<W: Write> AsciiCfiWriter<W> {
     pub fn process(&mut self, object: &Object<'_>) -> Result<(), CfiError> {
         match object {
             Object::Breakpad(o) => self.process_breakpad(o),
-            Object::MachO(o) => self.process_dwarf(o),
-            Object::Elf(o) => self.process_dwarf(o),
+            Object::MachO(o) => self.process_macho(o),
+            Object::Elf(o) => self.process_elf(o),
             Object::Pdb(o) => self.process_pdb(o),
             Object::Pe(o) => self.process_pe(o),
             Object::Wasm(o) => self.process_dwarf(o),
-            Object::SourceBundle(_) => Ok(()),
+            Object::PortablePdb(_) | Object::SourceBundle(_) => Ok(()),
         }
     }
 
@@ -319,6 +371,203 @@ impl<W: Write> AsciiCfiWriter<W> {
         debug_frame_result
     }
 
+    fn process_macho(&mut self, object: &MachObject<'_>) -> Result<(), CfiError> {
+        self.process_dwarf(object)?;
+        self.process_compact_unwind(object)
+    }
+
+    /// Converts compact unwind information from the `__unwind_info` section.
+    ///
+    /// Compact unwind encodings only describe the state of the stack frame after the prolog, which
+    /// is applied to the entire function. Functions in DWARF mode refer to an FDE in `__eh_frame`,
+    /// which has already been converted by `process_dwarf`. FDEs are also preferred for functions
+    /// that have both, since they describe prologs and epilogs precisely.
+    fn process_compact_unwind(&mut self, object: &MachObject<'_>) -> Result<(), CfiError> {
+        let section = match object.section("unwind_info") {
+            Some(section) => section,
+            None => return Ok(()),
+        };
+
+        // Malformed compact unwind information is ignored, since the DWARF information of this
+        // object file may still be valid.
+        let entries = match parse_compact_unwind(&section.data) {
+            Some(entries) => entries,
+            None => return Ok(()),
+        };
+
+        let fde_addresses = Self::dwarf_fde_addresses(object);
+        let load_address = object.load_address();
+        let text = object.section("text");
+
+        for window in entries.windows(2) {
+            let (start, encoding) = window[0];
+            let end = window[1].0;
+
+            let address = load_address + u64::from(start);
+            if end <= start || fde_addresses.contains(&address) {
+                continue;
+            }
+
+            let rules = match object.arch().cpu_family() {
+                CpuFamily::Amd64 => {
+                    // Frameless functions with large stacks store the stack size in the immediate
+                    // operand of their `sub` instruction, so the function's code is needed.
+                    let code = text.as_ref().and_then(|text| {
+                        let offset = address.checked_sub(text.address)?;
+                        text.data.get(offset as usize..)
+                    });
+                    compact_unwind_rules_x86_64(encoding, code)
+                }
+                CpuFamily::Arm64 => compact_unwind_rules_arm64(encoding),
+                _ => return Ok(()),
+            };
+
+            if let Some(rules) = rules {
+                writeln!(
+                    self.inner,
+                    "STACK CFI INIT {:x} {:x} {}",
+                    start,
+                    end - start,
+                    rules
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn process_elf(&mut self, object: &ElfObject<'_>) -> Result<(), CfiError> {
+        self.process_dwarf(object)?;
+
+        if object.arch().cpu_family() == CpuFamily::Arm32 {
+            self.process_arm_exidx(object)?;
+        }
+
+        Ok(())
+    }
+
+    /// Converts ARM exception handling tables from the `.ARM.exidx` section.
+    ///
+    /// Each entry in the index table refers to unwind opcodes, which are either stored inline or in
+    /// `.ARM.extab`. Like compact unwind encodings, they describe the stack frame after the prolog
+    /// and are applied to the entire function. Functions that have an FDE in DWARF unwind
+    /// information have already been converted by `process_dwarf` and are skipped.
+    fn process_arm_exidx(&mut self, object: &ElfObject<'_>) -> Result<(), CfiError> {
+        let exidx = match object.section("ARM.exidx") {
+            Some(section) => section,
+            None => return Ok(()),
+        };
+
+        let endian = object.endianity();
+        let extab = object.section("ARM.extab");
+        let fde_addresses = Self::dwarf_fde_addresses(object);
+        let load_address = object.load_address();
+
+        // Entries are sorted by function address, and each function extends to the next entry.
+        // The last function ends with the code section.
+        let entries = exidx
+            .data
+            .chunks_exact(8)
+            .enumerate()
+            .map(|(index, entry)| {
+                let place = exidx.address + index as u64 * 8;
+                // Clear the Thumb bit from function addresses.
+                let function = prel31(endian.read_u32(&entry[..4]), place) & !1;
+                (function, place + 4, endian.read_u32(&entry[4..]))
+            })
+            .collect::<Vec<_>>();
+
+        let text_end = object
+            .section("text")
+            .map(|text| text.address + text.data.len() as u64);
+
+        for (index, &(start, place, value)) in entries.iter().enumerate() {
+            let end = match entries.get(index + 1) {
+                Some(&(next, _, _)) => next,
+                None => match text_end {
+                    Some(text_end) => text_end,
+                    None => continue,
+                },
+            };
+
+            if value == EXIDX_CANTUNWIND
+                || end <= start
+                || start < load_address
+                || fde_addresses.contains(&start)
+            {
+                continue;
+            }
+
+            let opcodes = if value & 0x8000_0000 != 0 {
+                // Entries using personality routine 0 store up to three opcodes inline.
+                if value & 0x7f00_0000 != 0 {
+                    continue;
+                }
+                value.to_be_bytes()[1..].to_vec()
+            } else {
+                let address = prel31(value, place);
+                match extab.as_ref().and_then(|extab| {
+                    read_arm_extab_opcodes(&extab.data, address.checked_sub(extab.address)?, endian)
+                }) {
+                    Some(opcodes) => opcodes,
+                    None => continue,
+                }
+            };
+
+            if let Some(rules) = arm_exidx_rules(&opcodes) {
+                writeln!(
+                    self.inner,
+                    "STACK CFI INIT {:x} {:x} {}",
+                    start - load_address,
+                    end - start,
+                    rules
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the start addresses of all functions described by FDEs in the DWARF `debug_frame`
+    /// and `eh_frame` sections.
+    fn dwarf_fde_addresses<'d: 'o, 'o, O>(object: &O) -> HashSet<u64>
+    where
+        O: ObjectLike<'d, 'o> + Dwarf<'o>,
+    {
+        let endian = object.endianity();
+        let mut addresses = HashSet::new();
+
+        if let Some(section) = object.section("debug_frame") {
+            let frame = DebugFrame::new(&section.data, endian);
+            let info = UnwindInfo::new(object, section.address, frame);
+            Self::fde_addresses(&info, &mut addresses);
+        }
+
+        if let Some(section) = object.section("eh_frame") {
+            let frame = EhFrame::new(&section.data, endian);
+            let info = UnwindInfo::new(object, section.address, frame);
+            Self::fde_addresses(&info, &mut addresses);
+        }
+
+        addresses
+    }
+
+    /// Collects the start addresses of all functions described by FDEs in the unwind section.
+    fn fde_addresses<U, R>(info: &UnwindInfo<U>, addresses: &mut HashSet<u64>)
+    where
+        R: Reader + Eq,
+        U: UnwindSection<R>,
+    {
+        let mut entries = info.section.entries(&info.bases);
+        while let Ok(Some(entry)) = entries.next() {
+            if let CieOrFde::Fde(partial_fde) = entry {
+                if let Ok(fde) = partial_fde.parse(U::cie_from_offset) {
+                    addresses.insert(fde.initial_address());
+                }
+            }
+        }
+    }
+
     fn read_cfi<U, R>(&mut self, info: &UnwindInfo<U>) -> Result<(), CfiError>
     where
         R: Reader + Eq,
@@ -642,6 +891,10 @@ impl<W: Write> AsciiCfiWriter<W> {
     }
 
     fn process_pe(&mut self, pe: &PeObject<'_>) -> Result<(), CfiError> {
+        if pe.arch().cpu_family() == CpuFamily::Arm64 {
+            return self.process_pe_arm64(pe);
+        }
+
         let sections = pe.sections();
         let exception_data = match pe.exception_data() {
             Some(data) => data,
@@ -729,6 +982,875 @@ impl<W: Write> AsciiCfiWriter<W> {
 
         Ok(())
     }
+
+    fn process_pe_arm64(&mut self, pe: &PeObject<'_>) -> Result<(), CfiError> {
+        let directory = match pe.exception_directory() {
+            Some(directory) => directory,
+            None => return Ok(()),
+        };
+
+        for entry in directory.chunks_exact(ARM64_RUNTIME_FUNCTION_SIZE) {
+            let begin = u32::from_le_bytes(entry[..4].try_into().unwrap());
+            let unwind_data = u32::from_le_bytes(entry[4..].try_into().unwrap());
+
+            // Skip zeroed out entries, see `EMPTY_FUNCTION`.
+            if begin == 0 {
+                continue;
+            }
+
+            // The two lowest bits specify whether the entry contains packed unwind data or points
+            // to an `.xdata` record. Unsupported records are skipped, since other functions in this
+            // object file can still be valid.
+            let info = match unwind_data & 0x3 {
+                0 => pe
+                    .data_at_rva(unwind_data)
+                    .and_then(Arm64UnwindInfo::parse_xdata),
+                1 | 2 => Some(Arm64UnwindInfo::from_packed(unwind_data)),
+                _ => None,
+            };
+
+            let info = match info {
+                Some(info) if info.length > 0 => info,
+                _ => continue,
+            };
+
+            // Malformed unwind codes are skipped, since other functions in this object file can
+            // still be valid.
+            if let Ok(records) = Self::arm64_records(begin, &info) {
+                self.inner.write_all(&records)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Formats the `STACK CFI` records of a single ARM64 function.
+    ///
+    /// Returns an error if the unwind codes are invalid, in which case no records should be written
+    /// for this function.
+    fn arm64_records(begin: u32, info: &Arm64UnwindInfo) -> Result<Vec<u8>, CfiError> {
+        let mut records = Vec::new();
+
+        // Fragments do not have a prolog and start in the state established by the prolog of
+        // their parent function.
+        let mut frame = Arm64Frame::default();
+        if info.fragment {
+            for &code in &info.prolog {
+                frame.apply(code)?;
+            }
+        }
+
+        write!(records, "STACK CFI INIT {:x} {:x}", begin, info.length)?;
+        Self::write_arm64_rules(&mut records, &frame, None)?;
+        writeln!(records)?;
+
+        if info.fragment {
+            return Ok(records);
+        }
+
+        // Every unwind code corresponds to one instruction in the prolog. Its effect applies
+        // starting at the subsequent instruction.
+        for (index, &code) in info.prolog.iter().enumerate() {
+            let previous = frame.clone();
+            frame.apply(code)?;
+
+            let mut line = Vec::new();
+            write!(line, "STACK CFI {:x}", begin as usize + 4 * (index + 1))?;
+            if Self::write_arm64_rules(&mut line, &frame, Some(&previous))? {
+                records.extend_from_slice(&line);
+                writeln!(records)?;
+            }
+        }
+
+        Ok(records)
+    }
+
+    fn write_arm64_rules<T: Write>(
+        mut target: T,
+        frame: &Arm64Frame,
+        previous: Option<&Arm64Frame>,
+    ) -> Result<bool, CfiError> {
+        let mut written = false;
+
+        let cfa = frame.cfa_rule();
+        if previous.map_or(true, |p| p.cfa_rule() != cfa) {
+            write!(target, " .cfa: {} {} +", cfa.0, cfa.1)?;
+            written = true;
+        }
+
+        for (&register, &offset) in &frame.registers {
+            if previous.map_or(false, |p| p.registers.get(&register) == Some(&offset)) {
+                continue;
+            }
+
+            let name = if register == ARM64_LR {
+                ".ra"
+            } else {
+                match CpuFamily::Arm64.cfi_register_name(register) {
+                    Some(name) => name,
+                    None => continue,
+                }
+            };
+
+            write!(target, " {}: .cfa {} + ^", name, offset)?;
+            written = true;
+        }
+
+        // Until the link register is saved, it still holds the return address.
+        if previous.is_none() && !frame.registers.contains_key(&ARM64_LR) {
+            write!(target, " .ra: x30")?;
+        }
+
+        Ok(written)
+    }
+}
+
+/// Reads entries from a compact unwind information section (`__unwind_info`).
+///
+/// Returns pairs of function addresses relative to the image base and their encodings in
+/// ascending order. The final entry marks the end of the last function and has an encoding of
+/// zero. Consecutive functions with identical encodings may be merged into a single entry.
+fn parse_compact_unwind(data: &[u8]) -> Option<Vec<(u32, u32)>> {
+    let read_u16 = |offset: usize| -> Option<u32> {
+        let bytes = data.get(offset..offset.checked_add(2)?)?;
+        Some(u32::from(u16::from_le_bytes(bytes.try_into().ok()?)))
+    };
+
+    let read_u32 = |offset: usize| -> Option<u32> {
+        let bytes = data.get(offset..offset.checked_add(4)?)?;
+        Some(u32::from_le_bytes(bytes.try_into().ok()?))
+    };
+
+    if read_u32(0)? != 1 {
+        return None;
+    }
+
+    let common_offset = read_u32(4)? as usize;
+    let common_count = read_u32(8)?;
+    let index_offset = read_u32(20)? as usize;
+    let index_count = read_u32(24)? as usize;
+
+    let mut entries = Vec::new();
+
+    for index in 0..index_count {
+        let entry = index_offset + index * 12;
+        let function_offset = read_u32(entry)?;
+        let page = read_u32(entry + 4)? as usize;
+
+        // The last index entry has no second level page and marks the end of the last function.
+        if page == 0 {
+            entries.push((function_offset, 0));
+            break;
+        }
+
+        let entry_offset = page + read_u16(page + 4)? as usize;
+        let entry_count = read_u16(page + 6)? as usize;
+
+        match read_u32(page)? {
+            UNWIND_SECOND_LEVEL_REGULAR => {
+                for i in 0..entry_count {
+                    let offset = entry_offset + i * 8;
+                    entries.push((read_u32(offset)?, read_u32(offset + 4)?));
+                }
+            }
+            UNWIND_SECOND_LEVEL_COMPRESSED => {
+                // Compressed entries store the function offset relative to the index entry and an
+                // index into the common encodings, followed by encodings local to the page.
+                let encodings_offset = page + read_u16(page + 8)? as usize;
+                for i in 0..entry_count {
+                    let value = read_u32(entry_offset + i * 4)?;
+                    let encoding_index = value >> 24;
+                    let encoding = if encoding_index < common_count {
+                        read_u32(common_offset + encoding_index as usize * 4)?
+                    } else {
+                        let local_index = (encoding_index - common_count) as usize;
+                        read_u32(encodings_offset + local_index * 4)?
+                    };
+
+                    // Skip entries pointing beyond the address space, which cannot be valid.
+                    if let Some(address) = function_offset.checked_add(value & 0x00ff_ffff) {
+                        entries.push((address, encoding));
+                    }
+                }
+            }
+            _ => return None,
+        }
+    }
+
+    Some(entries)
+}
+
+/// Formats the CFA rule and register rules for a compact unwind encoding.
+///
+/// Registers are given as DWARF register numbers with their offsets relative to the CFA.
+fn format_compact_unwind_rules(
+    cpu_family: CpuFamily,
+    cfa: String,
+    ra: &str,
+    registers: &[(u16, i64)],
+) -> String {
+    let mut rules = format!(".cfa: {} .ra: {}", cfa, ra);
+    for &(register, offset) in registers {
+        if let Some(name) = cpu_family.cfi_register_name(register) {
+            rules.push_str(&format!(" {}: .cfa {} + ^", name, offset));
+        }
+    }
+    rules
+}
+
+/// Converts an x86_64 compact unwind encoding into Breakpad CFI rules.
+///
+/// `code` contains the machine code of the function, which is needed to read the stack size of
+/// frameless functions with large stacks.
+fn compact_unwind_rules_x86_64(encoding: u32, code: Option<&[u8]>) -> Option<String> {
+    // Maps register numbers used in compact unwind encodings to DWARF register numbers:
+    // `rbx`, `r12`, `r13`, `r14`, `r15` and `rbp`.
+    const REGISTERS: [u16; 7] = [0, 3, 12, 13, 14, 15, 6];
+
+    let mut registers = Vec::new();
+
+    let cfa = match encoding & UNWIND_MODE_MASK {
+        UNWIND_X86_64_MODE_RBP_FRAME => {
+            // Up to five registers are saved at consecutive slots below the saved `rbp`.
+            let offset = i64::from((encoding >> 16) & 0xff);
+            let locations = encoding & 0x7fff;
+
+            registers.push((REGISTERS[6], -16));
+            for i in 0..5 {
+                let register = (locations >> (3 * i)) & 0x7;
+                if register != 0 {
+                    let position = -16 - 8 * offset + 8 * i64::from(i);
+                    registers.push((*REGISTERS.get(register as usize)?, position));
+                }
+            }
+
+            "$rbp 16 +".to_owned()
+        }
+        mode @ UNWIND_X86_64_MODE_STACK_IMMD | mode @ UNWIND_X86_64_MODE_STACK_IND => {
+            let size = (encoding >> 16) & 0xff;
+            let stack_size = if mode == UNWIND_X86_64_MODE_STACK_IMMD {
+                size * 8
+            } else {
+                let immediate = code?.get(size as usize..size as usize + 4)?;
+                let adjust = (encoding >> 13) & 0x7;
+                u32::from_le_bytes(immediate.try_into().ok()?) + adjust * 8
+            };
+
+            // Pushed registers are stored right below the return address. Their order is encoded
+            // as a permutation, which is decoded to indexes into the remaining registers.
+            let count = (encoding >> 10) & 0x7;
+            let permutation = decode_register_permutation(count, encoding & 0x3ff)?;
+
+            let mut used = [false; 7];
+            for (i, &index) in permutation.iter().enumerate() {
+                let register = (1..7).filter(|&r| !used[r]).nth(index as usize)?;
+                used[register] = true;
+
+                let position = -8 - 8 * i64::from(count) + 8 * i as i64;
+                registers.push((REGISTERS[register], position));
+            }
+
+            format!("$rsp {} +", stack_size)
+        }
+        _ => return None,
+    };
+
+    Some(format_compact_unwind_rules(
+        CpuFamily::Amd64,
+        cfa,
+        ".cfa -8 + ^",
+        &registers,
+    ))
+}
+
+/// Decodes the permutation of pushed registers in frameless x86_64 compact unwind encodings.
+///
+/// Returns, for each pushed register, its index among the registers not yet pushed.
+fn decode_register_permutation(count: u32, mut permutation: u32) -> Option<Vec<u32>> {
+    let factors: &[u32] = match count {
+        0 => &[],
+        1 => &[1],
+        2 => &[5, 1],
+        3 => &[20, 4, 1],
+        4 => &[60, 12, 3, 1],
+        5 => &[120, 24, 6, 2, 1],
+        // With six registers, the last one is implied by the others and always decodes to zero.
+        6 => &[120, 24, 6, 2, 1, 1],
+        _ => return None,
+    };
+
+    let mut indexes = Vec::with_capacity(factors.len());
+    for &factor in factors {
+        let index = permutation / factor;
+        permutation -= index * factor;
+        indexes.push(index);
+    }
+
+    Some(indexes)
+}
+
+/// Converts an ARM64 compact unwind encoding into Breakpad CFI rules.
+fn compact_unwind_rules_arm64(encoding: u32) -> Option<String> {
+    // Saved register pairs in the order in which they are stored, from high to low addresses.
+    const PAIRS: [(u32, u16); 9] = [
+        (0x001, 19),
+        (0x002, 21),
+        (0x004, 23),
+        (0x008, 25),
+        (0x010, 27),
+        (0x100, ARM64_V0 + 8),
+        (0x200, ARM64_V0 + 10),
+        (0x400, ARM64_V0 + 12),
+        (0x800, ARM64_V0 + 14),
+    ];
+
+    let mut registers = Vec::new();
+
+    let (cfa, ra, mut position) = match encoding & UNWIND_MODE_MASK {
+        UNWIND_ARM64_MODE_FRAME => {
+            registers.push((ARM64_FP, -16));
+            ("x29 16 +".to_owned(), ".cfa -8 + ^", -24)
+        }
+        UNWIND_ARM64_MODE_FRAMELESS => {
+            let stack_size = ((encoding >> 12) & 0xfff) * 16;
+            (format!("sp {} +", stack_size), "x30", -8)
+        }
+        _ => return None,
+    };
+
+    for &(flag, first) in &PAIRS {
+        if encoding & flag != 0 {
+            registers.push((first, position));
+            registers.push((first + 1, position - 8));
+            position -= 16;
+        }
+    }
+
+    Some(format_compact_unwind_rules(
+        CpuFamily::Arm64,
+        cfa,
+        ra,
+        &registers,
+    ))
+}
+
+/// A decoded ARM64 unwind code, describing the effect of a single prolog instruction.
+///
+/// See [ARM64 exception handling](https://docs.microsoft.com/en-us/cpp/build/arm64-exception-handling)
+/// for the encoding of unwind codes and packed unwind data.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Arm64UnwindCode {
+    /// Allocates stack space by decrementing `sp`.
+    Alloc(u32),
+    /// Decrements `sp` by `alloc`, then stores one or two registers at `[sp, #offset]`.
+    Save {
+        first: u16,
+        second: Option<u16>,
+        offset: u32,
+        alloc: u32,
+    },
+    /// Sets up the frame pointer with `add x29, sp, #offset`.
+    SetFramePointer(u32),
+    /// Stores the register pair following the pair stored by the previous instruction.
+    SaveNext,
+    /// An instruction without effect on unwinding.
+    Nop,
+}
+
+/// Decodes unwind codes up to the end of the prolog.
+///
+/// Codes are stored in the reverse order of prolog instructions. Returns `None` for codes that
+/// cannot be expressed in Breakpad CFI, such as custom stack frames of trap handlers.
+fn decode_arm64_unwind_codes(codes: &[u8]) -> Option<Vec<Arm64UnwindCode>> {
+    use Arm64UnwindCode::*;
+
+    let save = |first: u32, second: Option<u32>, offset: u32, alloc: u32| Save {
+        first: first as u16,
+        second: second.map(|r| r as u16),
+        offset,
+        alloc,
+    };
+
+    let fp = u32::from(ARM64_FP);
+    let lr = u32::from(ARM64_LR);
+    let v8 = u32::from(ARM64_V0) + 8;
+
+    let mut decoded = Vec::new();
+    let mut index = 0;
+
+    loop {
+        let byte = |offset: usize| codes.get(index + offset).map(|&b| u32::from(b));
+        let b0 = byte(0)?;
+
+        // Most two-byte codes encode a register index `x` and an offset `z` in their low bits.
+        let word = (b0 << 8) | byte(1).unwrap_or(0);
+        let (x, z) = ((word >> 6) & 0xf, word & 0x3f);
+        let (x_short, z_short) = ((word >> 5) & 0xf, word & 0x1f);
+
+        let (code, len) = match b0 {
+            0x00..=0x1f => (Alloc((b0 & 0x1f) * 16), 1),
+            0x20..=0x3f => (save(19, Some(20), 0, (b0 & 0x1f) * 8), 1),
+            0x40..=0x7f => (save(fp, Some(lr), (b0 & 0x3f) * 8, 0), 1),
+            0x80..=0xbf => (save(fp, Some(lr), 0, ((b0 & 0x3f) + 1) * 8), 1),
+            0xc0..=0xc7 => (Alloc((word & 0x7ff) * 16), 2),
+            0xc8..=0xcb => (save(19 + x, Some(20 + x), z * 8, 0), 2),
+            0xcc..=0xcf => (save(19 + x, Some(20 + x), 0, (z + 1) * 8), 2),
+            0xd0..=0xd3 => (save(19 + x, None, z * 8, 0), 2),
+            0xd4..=0xd5 => (save(19 + x_short, None, 0, (z_short + 1) * 8), 2),
+            0xd6..=0xd7 => (save(19 + 2 * (x & 0x7), Some(lr), z * 8, 0), 2),
+            0xd8..=0xd9 => (save(v8 + (x & 0x7), Some(v8 + (x & 0x7) + 1), z * 8, 0), 2),
+            0xda..=0xdb => {
+                let x = x & 0x7;
+                (save(v8 + x, Some(v8 + x + 1), 0, (z + 1) * 8), 2)
+            }
+            0xdc..=0xdd => (save(v8 + (x & 0x7), None, z * 8, 0), 2),
+            0xde => (save(v8 + (x_short & 0x7), None, 0, (z_short + 1) * 8), 2),
+            0xe0 => (Alloc((byte(1)? << 16 | byte(2)? << 8 | byte(3)?) * 16), 4),
+            0xe1 => (SetFramePointer(0), 1),
+            0xe2 => (SetFramePointer(byte(1)? * 8), 2),
+            0xe3 => (Nop, 1),
+            // `end` and `end_c` terminate the prolog.
+            0xe4 | 0xe5 => break,
+            0xe6 => (SaveNext, 1),
+            // `pac_sign_lr` signs the return address without moving it.
+            0xfc => (Nop, 1),
+            _ => return None,
+        };
+
+        if len > 1 && byte(len - 1).is_none() {
+            return None;
+        }
+
+        decoded.push(code);
+        index += len;
+    }
+
+    Some(decoded)
+}
+
+/// Unwind information of a function in an ARM64 PE file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Arm64UnwindInfo {
+    /// The length of the function in bytes.
+    length: u32,
+    /// Whether this is a function fragment without prolog.
+    fragment: bool,
+    /// Unwind codes in the order of prolog instructions.
+    prolog: Vec<Arm64UnwindCode>,
+}
+
+impl Arm64UnwindInfo {
+    /// Parses an `.xdata` record.
+    fn parse_xdata(data: &[u8]) -> Option<Self> {
+        let read_u32 = |offset: usize| -> Option<u32> {
+            Some(u32::from_le_bytes(
+                data.get(offset..offset + 4)?.try_into().ok()?,
+            ))
+        };
+
+        let header = read_u32(0)?;
+        let length = (header & 0x3ffff) * 4;
+        let single_epilog = header & (1 << 21) != 0;
+        let mut epilog_count = (header >> 22) & 0x1f;
+        let mut code_words = header >> 27;
+        let mut offset = 4;
+
+        // Large records store both counts in an extension word.
+        if epilog_count == 0 && code_words == 0 {
+            let extension = read_u32(4)?;
+            epilog_count = extension & 0xffff;
+            code_words = (extension >> 16) & 0xff;
+            offset += 4;
+        }
+
+        // Epilog scopes precede the unwind codes, unless there is a single epilog whose start
+        // index is stored in the header.
+        if !single_epilog {
+            offset += epilog_count as usize * 4;
+        }
+
+        let codes = data.get(offset..offset + code_words as usize * 4)?;
+        let mut prolog = decode_arm64_unwind_codes(codes)?;
+        prolog.reverse();
+
+        Some(Arm64UnwindInfo {
+            length,
+            fragment: false,
+            prolog,
+        })
+    }
+
+    /// Expands packed unwind data into the canonical prolog it describes.
+    fn from_packed(data: u32) -> Self {
+        use Arm64UnwindCode::*;
+
+        let fragment = data & 0x3 == 2;
+        let length = ((data >> 2) & 0x7ff) * 4;
+        let reg_f = (data >> 13) & 0x7;
+        let reg_i = (data >> 16) & 0xf;
+        let homing = (data >> 20) & 0x1 != 0;
+        let cr = (data >> 21) & 0x3;
+        let frame_size = ((data >> 23) & 0x1ff) * 16;
+
+        // With `CR == 01`, the link register is saved along with the integer registers.
+        let mut int_registers = (0..reg_i as u16).map(|i| 19 + i).collect::<Vec<_>>();
+        if cr == 1 {
+            int_registers.push(ARM64_LR);
+        }
+
+        // `RegF` is the number of saved floating point registers minus one, if any are saved.
+        let fp_count = if reg_f > 0 { reg_f as u16 + 1 } else { 0 };
+        let fp_registers = (0..fp_count).map(|i| ARM64_V0 + 8 + i).collect::<Vec<_>>();
+
+        let int_size = int_registers.len() as u32 * 8;
+        let fp_size = u32::from(fp_count) * 8;
+        let home_size = if homing { 64 } else { 0 };
+        let save_size = (int_size + fp_size + home_size + 15) & !15;
+        let local_size = frame_size.saturating_sub(save_size);
+
+        let mut prolog = Vec::new();
+
+        // `pacibsp` signs the return address.
+        if cr == 2 {
+            prolog.push(Nop);
+        }
+
+        // The first store allocates the entire register save area with a pre-indexed store.
+        let mut alloc = save_size;
+        let areas = [(&int_registers, 0), (&fp_registers, int_size)];
+        for &(registers, base) in &areas {
+            for (index, pair) in registers.chunks(2).enumerate() {
+                prolog.push(Save {
+                    first: pair[0],
+                    second: pair.get(1).copied(),
+                    offset: base + index as u32 * 16,
+                    alloc: mem::take(&mut alloc),
+                });
+            }
+        }
+
+        // Homing stores `x0` to `x7` in four instructions. These registers are volatile and do
+        // not need to be restored.
+        if homing {
+            for _ in 0..4 {
+                prolog.push(Alloc(mem::take(&mut alloc)));
+            }
+        }
+
+        let push_alloc = |prolog: &mut Vec<_>, size: u32| {
+            if size > 4080 {
+                prolog.push(Alloc(4080));
+                prolog.push(Alloc(size - 4080));
+            } else if size > 0 {
+                prolog.push(Alloc(size));
+            }
+        };
+
+        let save_fplr = |alloc| Save {
+            first: ARM64_FP,
+            second: Some(ARM64_LR),
+            offset: 0,
+            alloc,
+        };
+
+        if matches!(cr, 2 | 3) {
+            // Chained functions store `x29` and `lr` at the bottom of the frame. With `CR == 10`,
+            // the return address has been signed by `pacibsp` before.
+            if local_size <= 512 {
+                prolog.push(save_fplr(local_size));
+            } else {
+                push_alloc(&mut prolog, local_size);
+                prolog.push(save_fplr(0));
+            }
+            prolog.push(SetFramePointer(0));
+        } else {
+            push_alloc(&mut prolog, local_size);
+        }
+
+        Arm64UnwindInfo {
+            length,
+            fragment,
+            prolog,
+        }
+    }
+}
+
+/// The state of an ARM64 stack frame while executing the prolog.
+#[derive(Clone, Debug, Default)]
+struct Arm64Frame {
+    /// The distance between the CFA and `sp`.
+    stack_size: u32,
+    /// The distance between the CFA and `x29`, once the frame pointer has been set up.
+    frame_pointer: Option<u32>,
+    /// Offsets of saved registers relative to the CFA.
+    registers: BTreeMap<u16, i64>,
+    /// The last register pair stored, which is continued by `save_next`.
+    last_pair: Option<(u16, u32)>,
+}
+
+impl Arm64Frame {
+    /// Updates the frame with the effect of a prolog instruction.
+    ///
+    /// Returns an error if the unwind codes describe a stack larger than the address space.
+    fn apply(&mut self, code: Arm64UnwindCode) -> Result<(), CfiError> {
+        match code {
+            Arm64UnwindCode::Alloc(size) => self.allocate(size)?,
+            Arm64UnwindCode::Save {
+                first,
+                second,
+                offset,
+                alloc,
+            } => {
+                self.allocate(alloc)?;
+                let position = i64::from(offset) - i64::from(self.stack_size);
+                self.registers.insert(first, position);
+                if let Some(second) = second {
+                    self.registers.insert(second, position + 8);
+                }
+
+                self.last_pair = match second {
+                    Some(second) if second == first + 1 => Some((first, offset)),
+                    _ => None,
+                };
+            }
+            Arm64UnwindCode::SaveNext => {
+                if let Some((first, offset)) = self.last_pair {
+                    let offset = offset.checked_add(16).ok_or(CfiErrorKind::BadDebugInfo)?;
+
+                    self.apply(Arm64UnwindCode::Save {
+                        first: first + 2,
+                        second: Some(first + 3),
+                        offset,
+                        alloc: 0,
+                    })?;
+                }
+            }
+            Arm64UnwindCode::SetFramePointer(offset) => {
+                self.frame_pointer = Some(self.stack_size.saturating_sub(offset));
+            }
+            Arm64UnwindCode::Nop => {}
+        }
+
+        Ok(())
+    }
+
+    /// Grows the stack by the given number of bytes.
+    fn allocate(&mut self, size: u32) -> Result<(), CfiError> {
+        self.stack_size = self
+            .stack_size
+            .checked_add(size)
+            .ok_or(CfiErrorKind::BadDebugInfo)?;
+        Ok(())
+    }
+
+    /// Returns the register and offset from which the CFA is computed.
+    fn cfa_rule(&self) -> (&'static str, u32) {
+        match self.frame_pointer {
+            Some(offset) => ("x29", offset),
+            None => ("sp", self.stack_size),
+        }
+    }
+}
+
+/// Resolves a place-relative 31-bit offset, as used in ARM exception handling tables.
+fn prel31(value: u32, place: u64) -> u64 {
+    let offset = ((value << 1) as i32 >> 1) as i64;
+    place.wrapping_add(offset as u64)
+}
+
+/// Reads the unwind opcodes of an `.ARM.extab` entry at the given offset.
+///
+/// Entries either use one of the compact personality routines defined by the ARM EHABI, or start
+/// with the offset of a generic personality routine. For the latter, the opcodes are assumed to be
+/// laid out like for personality routine 1, which is the case for the GNU C++ personality routine.
+fn read_arm_extab_opcodes(data: &[u8], offset: u64, endian: Endian) -> Option<Vec<u8>> {
+    let read_word = |index: u64| -> Option<u32> {
+        let start = offset.checked_add(index * 4)? as usize;
+        Some(endian.read_u32(data.get(start..start.checked_add(4)?)?))
+    };
+
+    let mut word = read_word(0)?;
+    let mut next = 1;
+
+    // The number of additional words containing opcodes, and the number of personality bytes
+    // preceding the opcodes in the first word.
+    let (count, skip) = if word & 0x8000_0000 != 0 {
+        match (word >> 24) & 0x7f {
+            0 => (0, 1),
+            1 | 2 => ((word >> 16) & 0xff, 2),
+            _ => return None,
+        }
+    } else {
+        word = read_word(1)?;
+        next = 2;
+        (word >> 24, 1)
+    };
+
+    let mut opcodes = word.to_be_bytes()[skip..].to_vec();
+    for index in 0..u64::from(count) {
+        opcodes.extend_from_slice(&read_word(next + index)?.to_be_bytes());
+    }
+
+    Some(opcodes)
+}
+
+/// Converts ARM exception handling unwind opcodes into Breakpad CFI rules.
+///
+/// See [Exception Handling ABI for the Arm Architecture](https://github.com/ARM-software/abi-aa/blob/main/ehabi32/ehabi32.rst),
+/// section 10.3, for a description of the opcodes.
+fn arm_exidx_rules(opcodes: &[u8]) -> Option<String> {
+    let mut frame = ArmExidxFrame::default();
+    let mut opcodes = opcodes.iter().copied();
+
+    while let Some(opcode) = opcodes.next() {
+        match opcode {
+            0x00..=0x3f => frame.vsp += (i64::from(opcode & 0x3f) << 2) + 4,
+            0x40..=0x7f => frame.vsp -= (i64::from(opcode & 0x3f) << 2) + 4,
+            0x80..=0x8f => {
+                let mask = u16::from(opcode & 0x0f) << 8 | u16::from(opcodes.next()?);
+                // A mask of zero indicates that the function cannot be unwound.
+                if mask == 0 {
+                    return None;
+                }
+                frame.pop(mask << 4)?;
+            }
+            0x90..=0x9f => {
+                let register = u16::from(opcode & 0x0f);
+                // Moving from `sp` or `pc` is reserved. The virtual stack pointer cannot be
+                // expressed if the register has already been restored from the stack.
+                if register == ARM_SP
+                    || register == ARM_PC
+                    || frame.registers.contains_key(&register)
+                {
+                    return None;
+                }
+                frame.base = register;
+                frame.vsp = 0;
+            }
+            0xa0..=0xaf => {
+                let mut mask = ((2u16 << (opcode & 0x07)) - 1) << 4;
+                if opcode & 0x08 != 0 {
+                    mask |= 1 << ARM_LR;
+                }
+                frame.pop(mask)?;
+            }
+            0xb0 => break,
+            0xb1 => match opcodes.next()? {
+                mask @ 0x01..=0x0f => frame.pop(u16::from(mask))?,
+                _ => return None,
+            },
+            0xb2 => {
+                let mut value = 0u64;
+                let mut shift = 0;
+                loop {
+                    let byte = opcodes.next()?;
+                    let bits = u64::from(byte & 0x7f);
+                    let shifted = bits.checked_shl(shift)?;
+                    if shifted >> shift != bits {
+                        return None;
+                    }
+                    value |= shifted;
+                    shift += 7;
+                    if byte & 0x80 == 0 {
+                        break;
+                    }
+                }
+                // Reject increments that do not fit into the virtual stack pointer.
+                let increment: i64 = value.checked_mul(4)?.checked_add(0x204)?.try_into().ok()?;
+                frame.vsp = frame.vsp.checked_add(increment)?;
+            }
+            // VFP registers saved by `FSTMFDX`, which stores an additional word.
+            0xb3 => frame.vsp += 8 * (i64::from(opcodes.next()? & 0x0f) + 1) + 4,
+            0xb8..=0xbf => frame.vsp += 8 * (i64::from(opcode & 0x07) + 1) + 4,
+            // iWMMXt and VFP registers saved by `VPUSH`. Their values are not restored.
+            0xc0..=0xc5 | 0xd0..=0xd7 => frame.vsp += 8 * (i64::from(opcode & 0x07) + 1),
+            0xc6 | 0xc8 | 0xc9 => frame.vsp += 8 * (i64::from(opcodes.next()? & 0x0f) + 1),
+            0xc7 => match opcodes.next()? {
+                mask @ 0x01..=0x0f => frame.vsp += 4 * i64::from(mask.count_ones()),
+                _ => return None,
+            },
+            _ => return None,
+        }
+    }
+
+    frame.rules()
+}
+
+/// The state of a 32-bit ARM stack frame while executing unwind opcodes.
+#[derive(Clone, Debug)]
+struct ArmExidxFrame {
+    /// The register from which the virtual stack pointer is computed.
+    base: u16,
+    /// The offset of the virtual stack pointer from the base register.
+    vsp: i64,
+    /// Locations of restored registers, given by their base register and offset.
+    registers: BTreeMap<u16, (u16, i64)>,
+}
+
+impl Default for ArmExidxFrame {
+    fn default() -> Self {
+        ArmExidxFrame {
+            base: ARM_SP,
+            vsp: 0,
+            registers: BTreeMap::new(),
+        }
+    }
+}
+
+impl ArmExidxFrame {
+    /// Restores the registers in the mask from consecutive slots at the virtual stack pointer.
+    fn pop(&mut self, mask: u16) -> Option<()> {
+        for register in 0..16 {
+            if mask & (1 << register) == 0 {
+                continue;
+            }
+
+            // Popping `sp` would make the CFA depend on the contents of the stack.
+            if register == ARM_SP {
+                return None;
+            }
+
+            self.registers.insert(register, (self.base, self.vsp));
+            self.vsp += 4;
+        }
+
+        Some(())
+    }
+
+    /// Formats the final virtual stack pointer as CFA, followed by the return address and
+    /// restored registers.
+    fn rules(&self) -> Option<String> {
+        let name = |register| CpuFamily::Arm32.cfi_register_name(register);
+        let location = |&(base, offset): &(u16, i64)| -> Option<String> {
+            if base == self.base {
+                Some(format!(".cfa {} + ^", offset - self.vsp))
+            } else {
+                Some(format!("{} {} + ^", name(base)?, offset))
+            }
+        };
+
+        let mut rules = format!(".cfa: {} {} +", name(self.base)?, self.vsp);
+
+        // Functions return by popping `pc` or via the saved `lr`.
+        let ra = if self.registers.contains_key(&ARM_PC) {
+            ARM_PC
+        } else {
+            ARM_LR
+        };
+
+        match self.registers.get(&ra) {
+            Some(saved) => rules.push_str(&format!(" .ra: {}", location(saved)?)),
+            None => rules.push_str(" .ra: lr"),
+        }
+
+        for (&register, saved) in &self.registers {
+            if register != ra {
+                rules.push_str(&format!(" {}: {}", name(register)?, location(saved)?));
+            }
+        }
+
+        Some(rules)
+    }
 }
 
 impl<W: Write + Default> AsciiCfiWriter<W> {
@@ -4,5 +4,6 @@
 
 mod utils;
 
+pub mod breakpad;
 pub mod cfi;
 pub mod processor;
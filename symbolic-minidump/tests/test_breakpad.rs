@@ -0,0 +1,86 @@
+use std::str;
+
+use symbolic_common::ByteView;
+use symbolic_debuginfo::{Function, Object};
+use symbolic_minidump::breakpad::BreakpadWriter;
+use symbolic_testutils::fixture;
+
+use similar_asserts::assert_eq;
+
+type Error = Box<dyn std::error::Error>;
+
+/// Collects all functions of an object with a non-zero size, including their inlinees.
+fn functions(object: &Object<'_>) -> Result<Vec<String>, Error> {
+    let session = object.debug_session()?;
+    let mut functions = Vec::new();
+    for function in session.functions() {
+        let function: Function<'_> = function?;
+        if function.size > 0 {
+            functions.push(format!("{:#?}", function));
+        }
+    }
+    Ok(functions)
+}
+
+#[test]
+fn breakpad_from_breakpad_inlinees() -> Result<(), Error> {
+    let buffer = ByteView::open(fixture("linux/inline.sym"))?;
+    let object = Object::parse(&buffer)?;
+
+    let buf: Vec<u8> = BreakpadWriter::transform(&object, "inline")?;
+    let sym = str::from_utf8(&buf)?;
+
+    // The operating system is retained from the source file. The PUBLIC record is covered by
+    // `main` and therefore omitted.
+    insta::assert_snapshot!(sym, @r###"
+    MODULE Linux x86_64 0B3C7D5C2E8B4A7D9F1E2D3C4B5A69780 inline
+    INFO CODE_ID 5C7D3C0B8B2E7D4A9F1E2D3C4B5A6978
+    FILE 0 /src/main.c
+    FILE 1 /src/util.h
+    INLINE_ORIGIN 0 util_add
+    INLINE_ORIGIN 1 util_inner
+    FUNC 1000 40 0 main
+    INLINE 0 10 0 0 1008 18
+    INLINE 1 20 1 1 1010 8
+    1000 8 9 0
+    1008 8 21 1
+    1010 8 30 1
+    1018 8 22 1
+    1020 20 11 0
+    FUNC 1040 20 0 helper
+    INLINE 0 15 0 0 1044 4 1050 8
+    1040 4 14 0
+    1044 4 21 1
+    1048 8 16 0
+    1050 8 23 1
+    1058 8 17 0
+    STACK CFI INIT 1000 40 .cfa: $rsp 8 + .ra: .cfa -8 + ^
+    "###);
+
+    let written = Object::parse(&buf)?;
+    assert_eq!(written.debug_id(), object.debug_id());
+    assert_eq!(written.code_id(), object.code_id());
+    assert_eq!(functions(&written)?, functions(&object)?);
+
+    Ok(())
+}
+
+#[test]
+fn breakpad_from_elf() -> Result<(), Error> {
+    let buffer = ByteView::open(fixture("linux/crash.debug"))?;
+    let object = Object::parse(&buffer)?;
+
+    let buf: Vec<u8> = BreakpadWriter::transform(&object, "crash")?;
+    let written = Object::parse(&buf)?;
+
+    assert_eq!(
+        written.file_format(),
+        symbolic_debuginfo::FileFormat::Breakpad
+    );
+    assert_eq!(written.debug_id(), object.debug_id());
+    assert_eq!(written.arch(), object.arch());
+
+    assert_eq!(functions(&written)?.len(), functions(&object)?.len());
+
+    Ok(())
+}
@@ -32,6 +32,19 @@ fn cfi_from_elf() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn cfi_from_elf_arm_exidx() -> Result<(), Error> {
+    let buffer = ByteView::open(fixture("linux/arm_exidx"))?;
+    let object = Object::parse(&buffer)?;
+    assert!(object.has_unwind_info());
+
+    let buf: Vec<u8> = AsciiCfiWriter::transform(&object)?;
+    let cfi = str::from_utf8(&buf)?;
+    insta::assert_snapshot!("cfi_elf_arm_exidx", cfi);
+
+    Ok(())
+}
+
 #[test]
 fn cfi_from_macho() -> Result<(), Error> {
     let buffer = ByteView::open(fixture("macos/crash"))?;
@@ -47,6 +60,19 @@ fn cfi_from_macho() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn cfi_from_macho_arm64() -> Result<(), Error> {
+    let buffer = ByteView::open(fixture("macos/compact_arm64"))?;
+    let object = Object::parse(&buffer)?;
+    assert!(object.has_unwind_info());
+
+    let buf: Vec<u8> = AsciiCfiWriter::transform(&object)?;
+    let cfi = str::from_utf8(&buf)?;
+    insta::assert_snapshot!("cfi_macho_arm64", cfi);
+
+    Ok(())
+}
+
 #[test]
 fn cfi_from_sym_linux() -> Result<(), Error> {
     let buffer = ByteView::open(fixture("linux/crash.sym"))?;
@@ -106,3 +132,16 @@ fn cfi_from_pe_windows() -> Result<(), Error> {
 
     Ok(())
 }
+
+#[test]
+fn cfi_from_pe_arm64() -> Result<(), Error> {
+    let buffer = ByteView::open(fixture("windows/arm64.exe"))?;
+    let object = Object::parse(&buffer)?;
+    assert!(object.has_unwind_info());
+
+    let buf: Vec<u8> = AsciiCfiWriter::transform(&object)?;
+    let cfi = str::from_utf8(&buf)?;
+    insta::assert_snapshot!("cfi_pe_arm64", cfi);
+
+    Ok(())
+}
@@ -71,6 +71,7 @@ impl<'a> SymCache<'a> {
             symbols: self.header.symbols,
             files: self.header.files,
             data: self.data,
+            version: self.version(),
             index: 0,
         }
     }
@@ -133,7 +134,7 @@ impl<'a> SymCache<'a> {
                     .run_to_line(current_fn, addr)?
                     // A lookup of `None` indicates that there was no line record at all, so just
                     // assume the function's start address as start of the line.
-                    .map_or(current_fn.addr_start(), |(line_addr, _, _)| line_addr);
+                    .map_or(current_fn.addr_start(), |(line_addr, _, _, _)| line_addr);
 
                 if closest.map_or(true, |(_, _, a)| current_addr > a) {
                     closest = Some((current_id, current_fn, current_addr));
@@ -187,14 +188,15 @@ impl<'a> SymCache<'a> {
     ///  - `.0`: First instruction address of the source line
     ///  - `.1`: File id of the source file containing this line
     ///  - `.2`: Line number in the file
+    ///  - `.3`: Column number in the line, or `0` if unknown
     ///
     /// Returns `None` if the function does not have line records.
     fn run_to_line(
         &self,
         fun: &format::FuncRecord,
         addr: u64,
-    ) -> Result<Option<(u64, u16, u32)>, SymCacheError> {
-        let records = fun.line_records.read(self.data)?;
+    ) -> Result<Option<(u64, u16, u32, u16)>, SymCacheError> {
+        let records = fun.line_records.read_lines(self.data, self.version())?;
         if records.is_empty() {
             // A non-empty function without line records can happen in a couple
             // of cases:
@@ -218,10 +220,11 @@ impl<'a> SymCache<'a> {
         // record as fallback.
         let mut file_id = records[0].file_id;
         let mut line = u32::from(records[0].line);
+        let mut column = records[0].column;
         let mut running_addr = fun.addr_start();
         let mut line_addr = running_addr;
 
-        for rec in records {
+        for rec in records.iter() {
             // Keep running until we exceed the search address
             running_addr += u64::from(rec.addr_off);
             if running_addr > addr {
@@ -236,10 +239,11 @@ impl<'a> SymCache<'a> {
             }
 
             line = u32::from(rec.line);
+            column = rec.column;
             file_id = rec.file_id;
         }
 
-        Ok(Some((line_addr, file_id, line)))
+        Ok(Some((line_addr, file_id, line, column)))
     }
 
     /// Extracts source line information for an instruction address within the
@@ -260,10 +264,10 @@ impl<'a> SymCache<'a> {
         &self,
         fun: &'a format::FuncRecord,
         addr: u64,
-        inner_sym: Option<(u32, u64, &'a str, &'a str)>,
+        inner_sym: Option<(u32, u16, u64, &'a str, &'a str)>,
     ) -> Result<LineInfo<'a>, SymCacheError> {
-        let (line, line_addr, filename, base_dir) =
-            if let Some((line_addr, file_id, line)) = self.run_to_line(fun, addr)? {
+        let (line, column, line_addr, filename, base_dir) =
+            if let Some((line_addr, file_id, line, column)) = self.run_to_line(fun, addr)? {
                 // A missing file record indicates a bad symcache.
                 let file_record = read_file_record(self.data, self.header.files, file_id)?
                     .ok_or(SymCacheErrorKind::BadCacheFile)?;
@@ -273,6 +277,7 @@ impl<'a> SymCache<'a> {
                 // debugging information and the majority of all frames.
                 (
                     line,
+                    column,
                     line_addr,
                     file_record.filename.read_str(self.data)?,
                     file_record.base_dir.read_str(self.data)?,
@@ -292,7 +297,7 @@ impl<'a> SymCache<'a> {
                 // case, we can only return empty line information. Also top-
                 // level functions without line records pulled from the symbol
                 // table will hit this branch.
-                (0, 0, "", "")
+                (0, 0, 0, "", "")
             };
 
         Ok(LineInfo {
@@ -302,6 +307,7 @@ impl<'a> SymCache<'a> {
             line_addr,
             instr_addr: addr,
             line,
+            column,
             lang: Language::from_u32(fun.lang.into()),
             symbol: read_symbol(self.data, self.header.symbols, fun.symbol_id())?,
             filename,
@@ -337,7 +343,7 @@ pub struct Lookup<'a, 'c> {
     cache: &'c SymCache<'a>,
     funcs: &'a [format::FuncRecord],
     current: Option<(u64, usize, &'a format::FuncRecord)>,
-    inner: Option<(u32, u64, &'a str, &'a str)>,
+    inner: Option<(u32, u16, u64, &'a str, &'a str)>,
 }
 
 impl<'a, 'c> Lookup<'a, 'c> {
@@ -373,6 +379,7 @@ impl<'a, 'c> Iterator for Lookup<'a, 'c> {
         if let Ok(ref line_info) = line_result {
             self.inner = Some((
                 line_info.line(),
+                line_info.column(),
                 line_info.line_address(),
                 line_info.filename(),
                 line_info.compilation_dir(),
@@ -409,6 +416,7 @@ pub struct LineInfo<'a> {
     line_addr: u64,
     instr_addr: u64,
     line: u32,
+    column: u16,
     lang: Language,
     symbol: Option<&'a str>,
     filename: &'a str,
@@ -475,6 +483,11 @@ impl<'a> LineInfo<'a> {
         self.line
     }
 
+    /// The column number within the line, or `0` if unknown.
+    pub fn column(&self) -> u16 {
+        self.column
+    }
+
     /// The source code language.
     pub fn language(&self) -> Language {
         self.lang
@@ -524,6 +537,7 @@ pub struct Functions<'a> {
     symbols: format::Seg<format::Seg<u8, u16>>,
     files: format::Seg<format::FileRecord, u16>,
     data: &'a [u8],
+    version: u32,
     index: u32,
 }
 
@@ -542,6 +556,7 @@ impl<'a> Iterator for Functions<'a> {
             symbols: self.symbols,
             files: self.files,
             data: self.data,
+            version: self.version,
             index: self.index,
         }));
 
@@ -559,6 +574,7 @@ pub struct Function<'a> {
     symbols: format::Seg<format::Seg<u8, u16>>,
     files: format::Seg<format::FileRecord, u16>,
     data: &'a [u8],
+    version: u32,
     index: u32,
 }
 
@@ -608,6 +624,7 @@ impl<'a> Function<'a> {
             lines: self.record.line_records,
             files: self.files,
             data: self.data,
+            version: self.version,
             address: 0,
             index: 0,
         }
@@ -654,6 +671,7 @@ pub struct Lines<'a> {
     lines: format::Seg<format::LineRecord, u16>,
     files: format::Seg<format::FileRecord, u16>,
     data: &'a [u8],
+    version: u32,
     address: u64,
     index: u16,
 }
@@ -662,7 +680,7 @@ impl<'a> Iterator for Lines<'a> {
     type Item = Result<Line<'a>, SymCacheError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let record = match self.lines.get(self.data, self.index) {
+        let record = match self.lines.get_line(self.data, self.index, self.version) {
             Ok(Some(record)) => record,
             Ok(None) => return None,
             Err(error) => return Some(Err(error)),
@@ -682,7 +700,7 @@ impl<'a> Iterator for Lines<'a> {
 
 /// A line covered by a [`Function`](symbolic_debuginfo::Function).
 pub struct Line<'a> {
-    record: &'a format::LineRecord,
+    record: format::LineRecord,
     file: Option<&'a format::FileRecord>,
     data: &'a [u8],
     address: u64,
@@ -699,6 +717,11 @@ impl<'a> Line<'a> {
         self.record.line
     }
 
+    /// The column number of the line, or `0` if unknown.
+    pub fn column(&self) -> u16 {
+        self.record.column
+    }
+
     /// The base_dir of the line.
     pub fn base_dir(&self) -> &str {
         match self.file {
@@ -721,6 +744,7 @@ impl fmt::Debug for Line<'_> {
         f.debug_struct("Line")
             .field("address", &self.address())
             .field("line", &self.line())
+            .field("column", &self.column())
             .field("base_dir", &self.base_dir())
             .field("filename", &self.filename())
             .finish()
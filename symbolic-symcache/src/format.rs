@@ -1,5 +1,6 @@
 //! Definition of the binary format for SymCaches.
 
+use std::borrow::Cow;
 use std::cmp::Ordering;
 use std::fmt;
 use std::io;
@@ -14,7 +15,7 @@ use crate::error::{SymCacheError, SymCacheErrorKind};
 pub const SYMCACHE_MAGIC: [u8; 4] = *b"SYMC";
 
 /// The latest version of the file format.
-pub const SYMCACHE_VERSION: u32 = 6;
+pub const SYMCACHE_VERSION: u32 = 7;
 
 // Version history:
 //
@@ -24,6 +25,7 @@ pub const SYMCACHE_VERSION: u32 = 6;
 // 4: PR #155: Functions with more than 65k line records
 // 5: PR #221: Invalid inlinee nesting leading to wrong stack traces
 // 6: PR #319: Correct line offsets and spacer line records
+// 7: Column numbers in line records
 
 /// Loads binary data from a segment.
 pub(crate) fn get_slice(data: &[u8], offset: usize, len: usize) -> Result<&[u8], io::Error> {
@@ -270,6 +272,70 @@ pub struct LineRecord {
 
     /// The line number of the line record.
     pub line: u16,
+
+    /// The column number of the line record, or `0` if unknown.
+    pub column: u16,
+}
+
+/// DEPRECATED. Line record used by SymCaches before version 7.
+#[repr(C, packed)]
+#[derive(Default, Copy, Clone, Debug)]
+pub struct LineRecordV1 {
+    /// Offset to the previous line record in the same function, or to the [function address](FuncRecord::addr_start)
+    /// if this is the first line.
+    pub addr_off: u8,
+
+    /// Index of the file record in the [`files`](Header::files) segment.
+    pub file_id: u16,
+
+    /// The line number of the line record.
+    pub line: u16,
+}
+
+impl From<&'_ LineRecordV1> for LineRecord {
+    fn from(record: &LineRecordV1) -> Self {
+        LineRecord {
+            addr_off: record.addr_off,
+            file_id: record.file_id,
+            line: record.line,
+            column: 0,
+        }
+    }
+}
+
+impl Seg<LineRecord, u16> {
+    /// Reads the line records of this segment from a SymCache of the given version.
+    ///
+    /// SymCaches before version 7 store line records without columns. These are converted into
+    /// the current layout with an unknown column.
+    pub fn read_lines<'a>(
+        &self,
+        data: &'a [u8],
+        version: u32,
+    ) -> Result<Cow<'a, [LineRecord]>, SymCacheError> {
+        if version >= 7 {
+            return self.read(data).map(Cow::Borrowed);
+        }
+
+        let legacy = Seg::<LineRecordV1, u16>::new(self.offset, self.len);
+        let records = legacy.read(data)?.iter().map(LineRecord::from).collect();
+        Ok(Cow::Owned(records))
+    }
+
+    /// Reads a single line record within this segment from a SymCache of the given version.
+    pub fn get_line(
+        &self,
+        data: &[u8],
+        index: u16,
+        version: u32,
+    ) -> Result<Option<LineRecord>, SymCacheError> {
+        if version >= 7 {
+            return Ok(self.get(data, index)?.copied());
+        }
+
+        let legacy = Seg::<LineRecordV1, u16>::new(self.offset, self.len);
+        Ok(legacy.get(data, index)?.map(LineRecord::from))
+    }
 }
 
 /// The start of a SymCache file.
@@ -446,6 +446,7 @@ where
         let mut last_address = start_address;
         let mut last_file = 0;
         let mut last_line = 0;
+        let mut last_column = 0;
 
         while let Some(line) = lines.peek() {
             let file_id = self.insert_file(&line.file)?;
@@ -474,6 +475,12 @@ where
                 if rest.is_none() {
                     last_file = file_id;
                     last_line = line.line.min(std::u16::MAX.into()) as u16;
+                    // Columns that do not fit are dropped rather than clamped, since a clamped
+                    // column would point to the wrong location.
+                    last_column = line
+                        .column
+                        .filter(|&column| column <= std::u16::MAX.into())
+                        .unwrap_or(0) as u16;
                 }
 
                 // Check if we can still add a line record to this function without exceeding limits
@@ -489,6 +496,7 @@ where
                     addr_off: current_offset as u8,
                     file_id: last_file,
                     line: last_line,
+                    column: last_column,
                 });
             }
 
@@ -174,3 +174,117 @@ fn test_lookup_modulo_u16() -> Result<(), Error> {
 
     Ok(())
 }
+
+#[test]
+fn test_lookup_columns() -> Result<(), Error> {
+    let buffer = ByteView::open(fixture("linux/embed/embed"))?;
+    let object = Object::parse(&buffer)?;
+
+    let mut buffer = Vec::new();
+    SymCacheWriter::write_object(&object, Cursor::new(&mut buffer))?;
+    let symcache = SymCache::parse(&buffer)?;
+    let symbols = symcache.lookup(0x112d)?.collect::<Vec<_>>()?;
+
+    assert_eq!(symbols.len(), 1);
+    assert_eq!(symbols[0].function_name(), "answer");
+    assert_eq!(symbols[0].line(), 3);
+    assert_eq!(symbols[0].column(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_lookup_pe_dwarf() -> Result<(), Error> {
+    let buffer = ByteView::open(fixture("windows/mingw.exe"))?;
+    let object = Object::parse(&buffer)?;
+
+    let mut buffer = Vec::new();
+    SymCacheWriter::write_object(&object, Cursor::new(&mut buffer))?;
+    let symcache = SymCache::parse(&buffer)?;
+    assert!(symcache.has_line_info());
+
+    let symbols = symcache.lookup(0x101c)?.collect::<Vec<_>>()?;
+
+    assert_eq!(symbols.len(), 1);
+    assert_eq!(symbols[0].function_name(), "main");
+    assert_eq!(symbols[0].line(), 8);
+
+    Ok(())
+}
+
+#[test]
+fn test_lookup_function_starts() -> Result<(), Error> {
+    let buffer = ByteView::open(fixture("macos/function_starts"))?;
+    let object = Object::parse(&buffer)?;
+
+    let mut buffer = Vec::new();
+    SymCacheWriter::write_object(&object, Cursor::new(&mut buffer))?;
+    let symcache = SymCache::parse(&buffer)?;
+
+    let symbols = symcache.lookup(0x1050)?.collect::<Vec<_>>()?;
+    assert_eq!(symbols.len(), 1);
+    assert_eq!(symbols[0].function_name(), "main");
+
+    // The following function has no symbol and must not be attributed to `main`.
+    let symbols = symcache.lookup(0x1070)?.collect::<Vec<_>>()?;
+    assert!(symbols.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_lookup_breakpad_inlinees() -> Result<(), Error> {
+    let buffer = ByteView::open(fixture("linux/inline.sym"))?;
+    let object = Object::parse(&buffer)?;
+
+    let mut buffer = Vec::new();
+    SymCacheWriter::write_object(&object, Cursor::new(&mut buffer))?;
+    let symcache = SymCache::parse(&buffer)?;
+    let symbols = symcache.lookup(0x1012)?.collect::<Vec<_>>()?;
+
+    let frames = symbols
+        .iter()
+        .map(|s| {
+            (
+                s.function_name().as_str().to_owned(),
+                s.filename(),
+                s.line(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        frames,
+        [
+            ("util_inner".to_owned(), "util.h", 30),
+            ("util_add".to_owned(), "util.h", 20),
+            ("main".to_owned(), "main.c", 10),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_lookup_portable_pdb() -> Result<(), Error> {
+    let buffer = ByteView::open(fixture("windows/portable.pdb"))?;
+    let object = Object::parse(&buffer)?;
+
+    let mut buffer = Vec::new();
+    SymCacheWriter::write_object(&object, Cursor::new(&mut buffer))?;
+    let symcache = SymCache::parse(&buffer)?;
+
+    // Methods are addressed by their `MethodDef` row above the lower 24 bits of the IL offset.
+    let symbols = symcache.lookup(0x0100_0005)?.collect::<Vec<_>>()?;
+    assert_eq!(symbols.len(), 1);
+    assert_eq!(symbols[0].function_address(), 0x0100_0000);
+    assert_eq!(symbols[0].filename(), "Program.cs");
+    assert_eq!(symbols[0].line(), 7);
+
+    let symbols = symcache.lookup(0x0200_0006)?.collect::<Vec<_>>()?;
+    assert_eq!(symbols.len(), 1);
+    assert_eq!(symbols[0].filename(), "Util.cs");
+    assert_eq!(symbols[0].line(), 3);
+
+    Ok(())
+}
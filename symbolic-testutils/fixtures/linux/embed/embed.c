@@ -0,0 +1,7 @@
+int answer(int value) {
+    return value * 2 + 42;
+}
+
+int main(int argc, char **argv) {
+    return answer(argc);
+}
@@ -0,0 +1,14 @@
+#include <stdio.h>
+
+static inline int square(int value) {
+    return value * value;
+}
+
+int compute(int value) {
+    return square(value) + 1;
+}
+
+int main(int argc, char **argv) {
+    printf("%d\n", compute(argc));
+    return 0;
+}
@@ -0,0 +1,37 @@
+namespace shapes {
+
+enum class Color : int { Red = 1, Green = 2, Blue = -1 };
+
+struct Point {
+    int x;
+    int y;
+};
+
+class Shape {
+public:
+    virtual ~Shape() {}
+    Color color;
+};
+
+struct Flags {
+    unsigned visible : 1;
+    unsigned layer : 3;
+};
+
+class Circle : public Shape {
+public:
+    Point center;
+    double radius;
+    Flags flags;
+};
+
+typedef Point Vector;
+
+} // namespace shapes
+
+int main() {
+    shapes::Circle circle;
+    shapes::Vector vector = {1, 2};
+    circle.center = vector;
+    return circle.center.x;
+}